@@ -4,44 +4,88 @@
     //     lines: {
     //         key: string;
     //         value: string;
+    //         status?: "warning" | "critical";
+    //         clock_offset?: number;
     //     }[];
     // }
 
+    // interface HttpMonitor {
+    //     name: string;
+    //     url: string;
+    //     up: boolean;
+    //     status?: number;
+    //     latency_ms?: number;
+    //     error?: string;
+    // }
+
+    // interface SeriesData {
+    //     metric: string;
+    //     window_seconds: number;
+    //     points: { timestamp: number; value: number }[];
+    // }
+
     const CLEAR_SPEED = 75; // ms
     const WRITE_SPEED = 50; // ms
     const START_DELAY = 500; // ms
+    const CHART_WINDOW = '1h';
+    const CHART_POINTS = 60;
+    // set when helios is served below a path prefix, e.g. `/helios`
+    const BASE_PATH = document.querySelector('#detail')?.dataset.basePath ?? '';
+    // how the page writes durations, the lines fetched later are asked for in the same style
+    const DURATIONS = document.querySelector('#detail')?.dataset.durations ?? 'full';
+    // the units of the sizes in the lines, `si` for powers of 1000 or `iec` for powers of 1024
+    const BYTE_UNITS = document.querySelector('#detail')?.dataset.byteUnits ?? 'iec';
+
+    const CHARTS = [
+        { title: 'CPU', metrics: ['cpu'], max: 100, format: formatPercent },
+        { title: 'Memory', metrics: ['memory'], max: 100, format: formatPercent },
+        { title: 'Network', metrics: ['network_rx', 'network_tx'], max: null, format: formatRate },
+    ];
 
     // Global abort controller for fetch requests so we don't have multiple fetches running at the same time
     const state = {
         controller: new AbortController(),
     };
 
-    function writeDataToHTML(data) {
+    function writeInfoLines(target, username, host, lines) {
         // create the host header first
-        const base = document.querySelector('#detail');
-        const clonedBase = base.cloneNode(true);
-        clonedBase.innerHTML = ''; // clear the content
-
         const hostHeader = document.createElement('p');
         hostHeader.className = 'host-header';
-        hostHeader.innerHTML = `noaione<span class="host-at">@</span>${data.host}`;
+        const hostAt = document.createElement('span');
+        hostAt.className = 'host-at';
+        hostAt.textContent = '@';
+        hostHeader.append(username, hostAt, host);
 
-        clonedBase.appendChild(hostHeader);
+        target.appendChild(hostHeader);
 
         // make dashed line
         const dashedLine = document.createElement('p');
         dashedLine.className = 'detail-line';
-        dashedLine.textContent = '-'.repeat(data.host.length + 8); // 8 is for "noaione@"
+        dashedLine.textContent = '-'.repeat(host.length + username.length + 1); // +1 is for "@"
 
-        clonedBase.appendChild(dashedLine);
+        target.appendChild(dashedLine);
 
         // make each line
-        data.lines.forEach((line) => {
+        lines.forEach((line) => {
             const lineEl = document.createElement('p');
-            lineEl.className = 'detail-line';
-            lineEl.innerHTML = `<span class="detail-line-root">${line.key}</span>: ${line.value}`;
-            clonedBase.appendChild(lineEl);
+            lineEl.className = line.status ? `detail-line detail-line-${line.status}` : 'detail-line';
+            const keyEl = document.createElement('span');
+            keyEl.className = 'detail-line-root';
+            keyEl.textContent = line.key;
+            lineEl.append(keyEl, `: ${line.value}`);
+            if (line.clock_offset !== undefined) {
+                lineEl.dataset.clockOffset = line.clock_offset;
+            }
+            target.appendChild(lineEl);
         });
+    }
+
+    function writeDataToHTML(data) {
+        const base = document.querySelector('#detail');
+        const clonedBase = base.cloneNode(true);
+        clonedBase.innerHTML = ''; // clear the content
+
+        writeInfoLines(clonedBase, base.dataset.username ?? 'helios', data.host, data.lines);
 
         // add break and palette grid
         const breakEl = document.createElement('br');
@@ -53,6 +97,23 @@
         base.parentNode.replaceChild(clonedBase, base);
     }
 
+    /**
+     * Move the local time lines forward, they only change on the server every refresh.
+     */
+    function tickClocks() {
+        document.querySelectorAll('[data-clock-offset]').forEach((lineEl) => {
+            const valueNode = lineEl.lastChild;
+            if (!valueNode || valueNode.nodeType !== Node.TEXT_NODE) {
+                return;
+            }
+
+            // the server's local time is UTC shifted by its offset
+            const offset = Number(lineEl.dataset.clockOffset);
+            const localTime = new Date(Date.now() + offset * 1000).toISOString().slice(0, 19).replace('T', ' ');
+            valueNode.textContent = valueNode.textContent.replace(/\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2}/, localTime);
+        });
+    }
+
     function writePaletteGrid()  {
         const baseGrid = document.createElement('div');
 
@@ -67,9 +128,195 @@
         return baseGrid;
     }
 
+    function formatPercent(value) {
+        return `${value.toFixed(0)}%`;
+    }
+
+    function formatRate(value) {
+        const base = BYTE_UNITS === 'si' ? 1000 : 1024;
+        const units = BYTE_UNITS === 'si' ? ['B', 'kB', 'MB', 'GB', 'TB'] : ['B', 'KiB', 'MiB', 'GiB', 'TiB'];
+        let size = value;
+        let unitIndex = 0;
+        while (size >= base && unitIndex < units.length - 1) {
+            size /= base;
+            unitIndex++;
+        }
+
+        return `${unitIndex === 0 ? size.toFixed(0) : size.toFixed(1)} ${units[unitIndex]}/s`;
+    }
+
+    /**
+     * Draw the series of one chart as SVG polylines, the first series is the primary one.
+     * @param {{ title: string; metrics: string[]; max: number | null; format: (value: number) => string }} chart
+     * @param {SeriesData[]} series
+     * @returns {HTMLDivElement}
+     */
+    function writeChart(chart, series) {
+        const chartEl = document.createElement('div');
+        chartEl.className = 'chart';
+
+        const latest = series.map((data) => data.points.at(-1)?.value).filter((value) => value !== undefined);
+        const titleEl = document.createElement('p');
+        titleEl.className = 'chart-title';
+        titleEl.textContent = latest.length > 0 ? `${chart.title}: ${latest.map(chart.format).join(' / ')}` : chart.title;
+        chartEl.appendChild(titleEl);
+
+        const width = 100;
+        const height = 30;
+        const allValues = series.flatMap((data) => data.points.map((point) => point.value));
+        const max = chart.max ?? Math.max(1, ...allValues);
+
+        const svg = document.createElementNS('http://www.w3.org/2000/svg', 'svg');
+        svg.setAttribute('class', 'chart-svg');
+        svg.setAttribute('viewBox', `0 0 ${width} ${height}`);
+        svg.setAttribute('preserveAspectRatio', 'none');
+
+        series.forEach((data, index) => {
+            if (data.points.length === 0) {
+                return;
+            }
+
+            // the x axis always covers the whole window, so gaps in the history stay visible
+            const span = Math.max(1, data.window_seconds);
+            const start = Date.now() / 1000 - span;
+            const coordinates = data.points.map((point) => {
+                const x = Math.max(0, ((point.timestamp - start) / span) * width);
+                const y = height - (Math.min(point.value, max) / max) * height;
+                return `${x.toFixed(2)},${y.toFixed(2)}`;
+            });
+
+            const line = document.createElementNS('http://www.w3.org/2000/svg', 'polyline');
+            line.setAttribute('class', index === 0 ? 'chart-line' : 'chart-line chart-line-secondary');
+            line.setAttribute('points', coordinates.join(' '));
+            svg.appendChild(line);
+        });
+
+        chartEl.appendChild(svg);
+        return chartEl;
+    }
+
+    function writePeers(peers) {
+        const container = document.querySelector('#peers');
+        const peerEls = peers.map((peer) => {
+            const peerEl = document.createElement('div');
+            peerEl.className = 'peer-detail';
+
+            // same as `PeerStatus::lines`, the last known info plus why the peer is unreachable
+            const lines = [...(peer.info?.lines ?? [])];
+            if (peer.error) {
+                lines.push({
+                    key: 'Status',
+                    value: `Unreachable (${peer.error})`,
+                    status: peer.last_seen ? 'warning' : 'critical',
+                });
+            }
+
+            writeInfoLines(peerEl, peer.username, peer.info?.host ?? peer.url, lines);
+            return peerEl;
+        });
+
+        container.replaceChildren(...peerEls);
+    }
+
+    /**
+     * Rewrite the rows of the services section, the title stays the one the page was rendered with.
+     * @param {HttpMonitor[]} services
+     */
+    function writeServices(services) {
+        const container = document.querySelector('#services-list');
+        const serviceEls = services.map((service) => {
+            const state = service.up ? 'up' : 'down';
+            const lineEl = document.createElement('p');
+            lineEl.className = 'detail-line';
+
+            const badgeEl = document.createElement('span');
+            badgeEl.className = `service-badge service-badge-${state}`;
+            badgeEl.textContent = state.toUpperCase();
+
+            const nameEl = document.createElement('a');
+            nameEl.className = 'detail-line-root';
+            nameEl.href = service.url;
+            nameEl.target = '_blank';
+            nameEl.rel = 'noreferrer noopener';
+            nameEl.textContent = service.name;
+
+            // same as `service_detail` of the rendered page
+            const detail = service.latency_ms !== undefined
+                ? `${service.latency_ms.toFixed(0)} ms`
+                : service.error ?? 'down';
+            lineEl.append(badgeEl, ' ', nameEl, `: ${detail}`);
+            return lineEl;
+        });
+
+        container.replaceChildren(...serviceEls);
+    }
+
+    async function refreshServices() {
+        // only rendered when HTTP monitors are configured
+        if (!document.querySelector('#services-list')) {
+            return;
+        }
+
+        try {
+            const response = await fetch(`${BASE_PATH}/api/v1/monitors/http`);
+            if (!response.ok) {
+                throw new Error(`HTTP error! status: ${response.status}`);
+            }
+
+            writeServices(await response.json());
+        } catch (error) {
+            console.error('Error fetching services:', error);
+        }
+    }
+
+    async function refreshPeers() {
+        // only rendered when federation peers are configured
+        if (!document.querySelector('#peers')) {
+            return;
+        }
+
+        try {
+            const response = await fetch(`${BASE_PATH}/api/v1/peers`);
+            if (!response.ok) {
+                throw new Error(`HTTP error! status: ${response.status}`);
+            }
+
+            const data = await response.json();
+            writePeers(data.peers);
+        } catch (error) {
+            console.error('Error fetching peers:', error);
+        }
+    }
+
+    async function refreshCharts() {
+        const container = document.querySelector('#charts');
+        if (!container) {
+            return;
+        }
+
+        try {
+            const chartEls = await Promise.all(CHARTS.map(async (chart) => {
+                const series = await Promise.all(chart.metrics.map(async (metric) => {
+                    const response = await fetch(`${BASE_PATH}/api/v1/series?metric=${metric}&window=${CHART_WINDOW}&points=${CHART_POINTS}`);
+                    if (!response.ok) {
+                        throw new Error(`HTTP error! status: ${response.status}`);
+                    }
+
+                    return response.json();
+                }));
+
+                return writeChart(chart, series);
+            }));
+
+            container.replaceChildren(...chartEls);
+        } catch (error) {
+            console.error('Error fetching series:', error);
+        }
+    }
+
     async function refreshData() {
         try {
-            const response = await fetch('/s', {
+            const response = await fetch(`${BASE_PATH}/api/v1/lines?duration=${DURATIONS}`, {
                 signal: state.controller.signal, // use the global abort controller
             });
 
@@ -79,12 +326,15 @@
 
             const data = await response.json();
             writeDataToHTML(data);
+            refreshCharts();
+            refreshServices();
+            refreshPeers();
         } catch (error) {
             console.error('Error fetching data:', error);
         }
     }
 
-    function start() {
+    function startPolling() {
         setInterval(() => {
             // abort any ongoing fetch request
             if (state.controller.signal.aborted) {
@@ -96,7 +346,33 @@
 
             refreshData();
         }, 20000); // refresh every 20 seconds
+    }
+
+    function startLiveUpdates() {
+        if (!('EventSource' in window)) {
+            // no SSE support, fallback to polling
+            startPolling();
+            return;
+        }
+
+        // the browser reconnects on its own if the stream drops
+        const source = new EventSource(`${BASE_PATH}/events?duration=${DURATIONS}`);
+        source.addEventListener('system-info', (event) => {
+            try {
+                writeDataToHTML(JSON.parse(event.data));
+                refreshCharts();
+                refreshServices();
+                refreshPeers();
+            } catch (error) {
+                console.error('Error parsing live data:', error);
+            }
+        });
+    }
+
+    function start() {
         refreshData(); // initial fetch
+        startLiveUpdates();
+        setInterval(tickClocks, 1000);
 
         /**
          * Write text to the console.
@@ -0,0 +1,21 @@
+use std::process::Command;
+
+fn main() {
+    // embed some build metadata for `--version`
+    let git_hash = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|hash| hash.trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    let target = std::env::var("TARGET").unwrap_or_else(|_| "unknown".to_string());
+    let profile = std::env::var("PROFILE").unwrap_or_else(|_| "unknown".to_string());
+
+    println!("cargo:rustc-env=HELIOS_GIT_HASH={git_hash}");
+    println!("cargo:rustc-env=HELIOS_BUILD_TARGET={target}");
+    println!("cargo:rustc-env=HELIOS_BUILD_PROFILE={profile}");
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs/heads");
+}
@@ -0,0 +1,245 @@
+use std::net::{IpAddr, SocketAddr};
+
+use axum::{
+    extract::{ConnectInfo, Request},
+    http::{HeaderMap, HeaderName, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::config::{self, AccessRules, Cidr};
+
+const X_FORWARDED_FOR: HeaderName = HeaderName::from_static("x-forwarded-for");
+
+/// Turn away clients outside of `access.page`.
+pub async fn page_access(request: Request, next: Next) -> Response {
+    check(&config::get().access.page, request, next).await
+}
+
+/// Turn away clients outside of `access.api`.
+pub async fn api_access(request: Request, next: Next) -> Response {
+    check(&config::get().access.api, request, next).await
+}
+
+async fn check(rules: &AccessRules, request: Request, next: Next) -> Response {
+    if rules.allow.is_empty() && rules.deny.is_empty() {
+        return next.run(request).await;
+    }
+
+    // without an address there's nothing to match, only let it through if nothing is required
+    let permitted = match client_ip(&request) {
+        Some(ip) => rules.permits(ip),
+        None => rules.allow.is_empty(),
+    };
+    if !permitted {
+        return (StatusCode::FORBIDDEN, "Forbidden").into_response();
+    }
+
+    next.run(request).await
+}
+
+/// Address of the client, from the `Forwarded` or `X-Forwarded-For` header when the connection
+/// comes from one of `trusted_proxies`.
+pub fn client_ip(request: &Request) -> Option<IpAddr> {
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()?
+        .0
+        .ip();
+
+    resolve(peer, request.headers(), &config::get().trusted_proxies)
+}
+
+/// The client behind `peer`, `None` when a proxy forwarded it without a usable address.
+fn resolve(peer: IpAddr, headers: &HeaderMap, trusted_proxies: &[Cidr]) -> Option<IpAddr> {
+    let peer = peer.to_canonical();
+    let is_trusted = |ip: IpAddr| trusted_proxies.iter().any(|cidr| cidr.contains(ip));
+    if !is_trusted(peer) {
+        return Some(peer);
+    }
+
+    // every proxy appends the address it got the request from, so the rightmost one that isn't a
+    // trusted proxy is the client, anything left of it could have been sent by the client itself
+    let hops = forwarded_for(headers);
+    for hop in hops.iter().rev() {
+        match hop {
+            Some(ip) if is_trusted(*ip) => continue,
+            Some(ip) => return Some(*ip),
+            // what's left of an obfuscated or `unknown` hop can't be told apart from a forgery
+            None => return None,
+        }
+    }
+
+    // only trusted proxies, the first one got the request from the client directly
+    Some(hops.first().copied().flatten().unwrap_or(peer))
+}
+
+/// Addresses the request was forwarded for, first hop first, `None` for the obfuscated and
+/// `unknown` ones.
+///
+/// `Forwarded` (RFC 7239) is used when present, `X-Forwarded-For` otherwise.
+fn forwarded_for(headers: &HeaderMap) -> Vec<Option<IpAddr>> {
+    let values = |name| {
+        headers
+            .get_all(name)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .collect::<Vec<_>>()
+    };
+
+    let forwarded = values(header::FORWARDED);
+    if !forwarded.is_empty() {
+        return forwarded
+            .into_iter()
+            .filter_map(|element| {
+                element.split(';').find_map(|pair| {
+                    let (name, value) = pair.trim().split_once('=')?;
+                    name.trim()
+                        .eq_ignore_ascii_case("for")
+                        .then(|| parse_node(value.trim().trim_matches('"')))
+                })
+            })
+            .collect();
+    }
+
+    values(X_FORWARDED_FOR)
+        .into_iter()
+        .map(parse_node)
+        .collect()
+}
+
+/// An address with an optional port, IPv6 ones with a port are in brackets like `[::1]:443`.
+fn parse_node(node: &str) -> Option<IpAddr> {
+    let address = node
+        .parse::<IpAddr>()
+        .or_else(|_| node.parse::<SocketAddr>().map(|addr| addr.ip()))
+        .or_else(|_| node.trim_start_matches('[').trim_end_matches(']').parse())
+        .ok()?;
+    Some(address.to_canonical())
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+
+    const PROXY: &str = "10.0.0.1";
+
+    fn ip(value: &str) -> IpAddr {
+        value.parse().unwrap()
+    }
+
+    fn headers(name: HeaderName, values: &[&str]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for value in values {
+            headers.append(name.clone(), HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    fn resolve_behind_proxy(headers: &HeaderMap) -> Option<IpAddr> {
+        let trusted_proxies =
+            ["10.0.0.0/8", "fd00::/8"].map(|value| Cidr::try_from(value.to_string()).unwrap());
+        resolve(ip(PROXY), headers, &trusted_proxies)
+    }
+
+    #[test]
+    fn untrusted_peers_are_the_client() {
+        let headers = headers(X_FORWARDED_FOR, &["203.0.113.1"]);
+
+        assert_eq!(
+            resolve(ip("198.51.100.1"), &headers, &[]),
+            Some(ip("198.51.100.1"))
+        );
+        assert_eq!(
+            resolve(ip("::ffff:198.51.100.1"), &headers, &[]),
+            Some(ip("198.51.100.1"))
+        );
+    }
+
+    #[test]
+    fn ipv4_mapped_proxies_are_trusted() {
+        let trusted_proxies = [Cidr::try_from(PROXY.to_string()).unwrap()];
+        let headers = headers(X_FORWARDED_FOR, &["203.0.113.1"]);
+
+        assert_eq!(
+            resolve(ip("::ffff:10.0.0.1"), &headers, &trusted_proxies),
+            Some(ip("203.0.113.1"))
+        );
+    }
+
+    #[test]
+    fn rightmost_untrusted_hop_is_the_client() {
+        // the client forged the first hop, the proxies appended the rest
+        let headers = headers(X_FORWARDED_FOR, &["198.51.100.66, 203.0.113.1", "10.0.0.2"]);
+
+        assert_eq!(resolve_behind_proxy(&headers), Some(ip("203.0.113.1")));
+    }
+
+    #[test]
+    fn only_trusted_hops_give_the_first_one() {
+        let headers = headers(X_FORWARDED_FOR, &["10.0.0.3, 10.0.0.2"]);
+
+        assert_eq!(resolve_behind_proxy(&headers), Some(ip("10.0.0.3")));
+        assert_eq!(resolve_behind_proxy(&HeaderMap::new()), Some(ip(PROXY)));
+    }
+
+    #[test]
+    fn forwarded_takes_precedence() {
+        let mut headers = headers(header::FORWARDED, &["for=203.0.113.1;proto=https"]);
+        headers.append(X_FORWARDED_FOR, HeaderValue::from_static("198.51.100.66"));
+
+        assert_eq!(resolve_behind_proxy(&headers), Some(ip("203.0.113.1")));
+    }
+
+    #[test]
+    fn forwarded_nodes_can_be_quoted_bracketed_and_have_ports() {
+        for (value, expected) in [
+            ("for=203.0.113.1", "203.0.113.1"),
+            ("For=\"203.0.113.1\"", "203.0.113.1"),
+            ("for=\"203.0.113.1:4711\"", "203.0.113.1"),
+            ("for=\"[2001:db8::1]\"", "2001:db8::1"),
+            ("for=\"[2001:db8::1]:4711\"", "2001:db8::1"),
+            ("for=\"[::ffff:203.0.113.1]:4711\"", "203.0.113.1"),
+            ("proto=https;by=10.0.0.1; for=203.0.113.1", "203.0.113.1"),
+        ] {
+            let headers = headers(header::FORWARDED, &[value]);
+            assert_eq!(
+                resolve_behind_proxy(&headers),
+                Some(ip(expected)),
+                "{value}"
+            );
+        }
+    }
+
+    #[test]
+    fn forwarded_picks_the_rightmost_untrusted_element() {
+        let headers = headers(
+            header::FORWARDED,
+            &[
+                "for=198.51.100.66, for=\"[2001:db8::1]:4711\"",
+                "for=\"[fd00::2]\";proto=https",
+            ],
+        );
+
+        assert_eq!(resolve_behind_proxy(&headers), Some(ip("2001:db8::1")));
+    }
+
+    #[test]
+    fn unknown_and_obfuscated_hops_are_not_skipped() {
+        for value in [
+            "for=198.51.100.66, for=unknown",
+            "for=198.51.100.66, for=_hidden",
+        ] {
+            let headers = headers(header::FORWARDED, &[value]);
+            assert_eq!(resolve_behind_proxy(&headers), None, "{value}");
+        }
+
+        let headers = headers(X_FORWARDED_FOR, &["198.51.100.66, garbage, 10.0.0.2"]);
+        assert_eq!(resolve_behind_proxy(&headers), None);
+    }
+}
@@ -0,0 +1,50 @@
+use async_trait::async_trait;
+use serde_json::json;
+
+use super::{Alert, Notifier, http_client, post_json};
+
+/// Sends alerts as an embed to a Discord channel webhook.
+pub struct DiscordNotifier {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl DiscordNotifier {
+    pub fn new(url: String) -> Self {
+        DiscordNotifier {
+            url,
+            client: http_client(),
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for DiscordNotifier {
+    fn name(&self) -> &'static str {
+        "discord"
+    }
+
+    async fn notify(&self, alert: &Alert) -> Result<(), String> {
+        let (r, g, b) = alert.status.color();
+        let field =
+            |name: &str, value: String| json!({ "name": name, "value": value, "inline": true });
+        let fields = [
+            field("Host", alert.host.clone()),
+            field("Metric", alert.metric_label()),
+            field("Value", alert.metric.format_value(alert.value)),
+            field("Threshold", alert.metric.format_value(alert.threshold)),
+        ];
+
+        let payload = json!({
+            "username": "helios",
+            "embeds": [{
+                "title": alert.title(),
+                "color": (u32::from(r) << 16) | (u32::from(g) << 8) | u32::from(b),
+                "fields": fields,
+                "timestamp": alert.rfc3339_timestamp(),
+            }],
+        });
+
+        post_json(&self.client, &self.url, &payload).await
+    }
+}
@@ -0,0 +1,94 @@
+use async_trait::async_trait;
+use lettre::{
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+    message::{Mailbox, header::ContentType},
+    transport::smtp::authentication::Credentials,
+};
+
+use super::{Alert, Notifier, REQUEST_TIMEOUT};
+use crate::config::{EmailConfig, SmtpTls};
+
+/// Emails alerts through an SMTP server.
+pub struct EmailNotifier {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+    to: Vec<Mailbox>,
+}
+
+impl EmailNotifier {
+    pub fn new(email_config: &EmailConfig) -> Result<Self, String> {
+        let from = email_config
+            .from
+            .parse()
+            .map_err(|err| format!("invalid `alerts.email.from`: {err}"))?;
+        let to = email_config
+            .to
+            .iter()
+            .map(|to| to.parse())
+            .collect::<Result<Vec<Mailbox>, _>>()
+            .map_err(|err| format!("invalid `alerts.email.to`: {err}"))?;
+        if to.is_empty() {
+            return Err("`alerts.email.to` is empty".to_string());
+        }
+
+        let host = email_config.host.as_str();
+        let builder = match email_config.tls {
+            SmtpTls::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(host),
+            SmtpTls::Starttls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host),
+            SmtpTls::None => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(
+                host,
+            )),
+        }
+        .map_err(|err| format!("invalid `alerts.email.host`: {err}"))?;
+
+        let mut builder = builder.timeout(Some(REQUEST_TIMEOUT));
+        if let Some(port) = email_config.port {
+            builder = builder.port(port);
+        } else if email_config.tls == SmtpTls::None {
+            builder = builder.port(25);
+        }
+        if let (Some(username), Some(password)) = (&email_config.username, &email_config.password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+
+        Ok(EmailNotifier {
+            transport: builder.build(),
+            from,
+            to,
+        })
+    }
+}
+
+#[async_trait]
+impl Notifier for EmailNotifier {
+    fn name(&self) -> &'static str {
+        "email"
+    }
+
+    async fn notify(&self, alert: &Alert) -> Result<(), String> {
+        let mut builder = Message::builder()
+            .from(self.from.clone())
+            .subject(format!("[helios] {} on {}", alert.title(), alert.host))
+            .header(ContentType::TEXT_PLAIN);
+        for to in &self.to {
+            builder = builder.to(to.clone());
+        }
+
+        let body = format!(
+            "{}\n\nHost: {}\nMetric: {}\nValue: {}\nThreshold: {}\nTime: {}\n",
+            alert.title(),
+            alert.host,
+            alert.metric_label(),
+            alert.metric.format_value(alert.value),
+            alert.metric.format_value(alert.threshold),
+            alert.rfc3339_timestamp(),
+        );
+        let message = builder.body(body).map_err(|err| err.to_string())?;
+
+        self.transport
+            .send(message)
+            .await
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    }
+}
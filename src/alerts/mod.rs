@@ -0,0 +1,306 @@
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use serde::Serialize;
+
+use crate::{
+    config::{self, AlertMetric, AlertRule},
+    sysgetter::SystemInfoRaw,
+};
+
+mod discord;
+mod email;
+mod slack;
+mod webhook;
+
+pub use discord::DiscordNotifier;
+pub use email::EmailNotifier;
+pub use slack::SlackNotifier;
+pub use webhook::WebhookNotifier;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A destination alerts are sent to, e.g. a webhook or an email address.
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    /// Used in the logs when sending fails.
+    fn name(&self) -> &'static str;
+
+    async fn notify(&self, alert: &Alert) -> Result<(), String>;
+}
+
+/// A rule that started or stopped firing.
+#[derive(Debug, Clone, Serialize)]
+pub struct Alert {
+    pub status: AlertStatus,
+    /// The rule name, `memory > 90` when it has none.
+    pub rule: String,
+    pub metric: AlertMetric,
+    /// What the value belongs to when the metric has several, e.g. the mount point of a disk.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    pub value: f64,
+    pub threshold: f64,
+    pub host: String,
+    /// Unix timestamp in seconds.
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertStatus {
+    Firing,
+    Resolved,
+}
+
+/// Evaluates the configured rules against every snapshot and notifies on transitions.
+pub struct Alerts {
+    notifiers: Arc<Vec<Box<dyn Notifier>>>,
+    /// Keyed by the rule index and the subject.
+    states: HashMap<(usize, Option<String>), RuleState>,
+    cooldown: Duration,
+}
+
+#[derive(Debug, Default)]
+struct RuleState {
+    firing: bool,
+    /// Whether the firing notification was sent, only then is the resolve one sent too.
+    notified: bool,
+    last_notified: Option<Instant>,
+}
+
+impl Alerts {
+    /// Alerts with every notifier from the config, `None` when there are no rules or notifiers.
+    pub fn from_config() -> Option<Self> {
+        let alerts_config = &config::get().alerts;
+        if alerts_config.rules.is_empty() {
+            return None;
+        }
+
+        let mut notifiers: Vec<Box<dyn Notifier>> = vec![];
+        for url in &alerts_config.webhooks {
+            notifiers.push(Box::new(WebhookNotifier::new(url.clone())));
+        }
+        for url in &alerts_config.discord_webhooks {
+            notifiers.push(Box::new(DiscordNotifier::new(url.clone())));
+        }
+        for url in &alerts_config.slack_webhooks {
+            notifiers.push(Box::new(SlackNotifier::new(url.clone())));
+        }
+        if let Some(email_config) = &alerts_config.email {
+            match EmailNotifier::new(email_config) {
+                Ok(notifier) => notifiers.push(Box::new(notifier)),
+                Err(err) => tracing::warn!("Not sending alerts by email, {err}"),
+            }
+        }
+        if notifiers.is_empty() {
+            tracing::warn!("Alert rules are configured without anywhere to send them to");
+            return None;
+        }
+
+        Some(Alerts {
+            notifiers: Arc::new(notifiers),
+            states: HashMap::new(),
+            cooldown: Duration::from_secs(alerts_config.cooldown),
+        })
+    }
+
+    /// Check every rule against `raw`, sending notifications in the background.
+    ///
+    /// A rule firing again within the cooldown of its last notification is only sent once the
+    /// cooldown passed and it still fires, so a value hovering around the threshold doesn't
+    /// flood the notifiers.
+    pub fn evaluate(&mut self, raw: &SystemInfoRaw) {
+        let now = Instant::now();
+        let mut alerts = vec![];
+
+        for (index, rule) in config::get().alerts.rules.iter().enumerate() {
+            let values = metric_values(rule.metric, raw);
+
+            for (subject, value) in &values {
+                let state = self.states.entry((index, subject.clone())).or_default();
+                let firing = rule.op.compare(*value, rule.threshold);
+                let resolved = state.firing && !firing;
+                state.firing = firing;
+
+                let notify = if resolved {
+                    std::mem::take(&mut state.notified)
+                } else if firing && !state.notified {
+                    // held back by the cooldown until it passes, if the rule still fires then
+                    state.notified = state
+                        .last_notified
+                        .is_none_or(|last| now.duration_since(last) >= self.cooldown);
+                    state.notified
+                } else {
+                    false
+                };
+                if notify {
+                    state.last_notified = Some(now);
+                    alerts.push(alert(rule, subject.clone(), *value, firing, &raw.host));
+                }
+            }
+
+            // a disk that got unmounted or a sensor that disappeared resolves silently
+            self.states.retain(|(rule_index, subject), _| {
+                *rule_index != index || values.iter().any(|(current, _)| current == subject)
+            });
+        }
+
+        for alert in alerts {
+            let notifiers = self.notifiers.clone();
+            tokio::spawn(async move {
+                for notifier in notifiers.iter() {
+                    if let Err(err) = notifier.notify(&alert).await {
+                        tracing::warn!("Failed to send alert to {}: {err}", notifier.name());
+                    }
+                }
+            });
+        }
+    }
+}
+
+impl Alert {
+    /// `Firing: memory > 90` or `Resolved: memory > 90`.
+    pub fn title(&self) -> String {
+        match self.status {
+            AlertStatus::Firing => format!("Firing: {}", self.rule),
+            AlertStatus::Resolved => format!("Resolved: {}", self.rule),
+        }
+    }
+
+    /// The metric with its subject, e.g. `disk (/home)`.
+    pub fn metric_label(&self) -> String {
+        match &self.subject {
+            Some(subject) => format!("{} ({subject})", self.metric.name()),
+            None => self.metric.name().to_string(),
+        }
+    }
+
+    pub fn rfc3339_timestamp(&self) -> String {
+        chrono::DateTime::from_timestamp(self.timestamp, 0)
+            .unwrap_or_default()
+            .to_rfc3339()
+    }
+}
+
+impl AlertStatus {
+    /// Red of the critical lines when firing, teal from the page palette when resolved.
+    pub fn color(&self) -> (u8, u8, u8) {
+        match self {
+            AlertStatus::Firing => (0xaf, 0x54, 0x57),
+            AlertStatus::Resolved => (0x3e, 0xac, 0xac),
+        }
+    }
+}
+
+/// HTTP client shared by the notifiers posting to webhooks.
+fn http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .unwrap_or_default()
+}
+
+async fn post_json(
+    client: &reqwest::Client,
+    url: &str,
+    payload: &impl Serialize,
+) -> Result<(), String> {
+    let body = serde_json::to_vec(payload).map_err(|err| err.to_string())?;
+    client
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(body)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map(|_| ())
+        .map_err(|err| err.to_string())
+}
+
+/// Current values of `metric`, one per subject for metrics that have several.
+fn metric_values(metric: AlertMetric, raw: &SystemInfoRaw) -> Vec<(Option<String>, f64)> {
+    match metric {
+        AlertMetric::Cpu => raw
+            .cpu
+            .iter()
+            .map(|cpu| (None, f64::from(cpu.usage_percent)))
+            .collect(),
+        AlertMetric::Memory => raw
+            .memory
+            .iter()
+            .map(|memory| (None, memory.usage_percent))
+            .collect(),
+        AlertMetric::Swap => raw
+            .swap
+            .iter()
+            .filter(|swap| swap.total_bytes > 0)
+            .map(|swap| (None, swap.usage_percent))
+            .collect(),
+        AlertMetric::Disk => raw
+            .disks
+            .iter()
+            .flatten()
+            .map(|disk| (Some(disk.mount_point.clone()), disk.usage_percent))
+            .collect(),
+        AlertMetric::Temperature => raw
+            .temperatures
+            .iter()
+            .flatten()
+            .map(|sensor| (Some(sensor.label.clone()), f64::from(sensor.celsius)))
+            .collect(),
+        AlertMetric::Battery => raw
+            .batteries
+            .iter()
+            .flatten()
+            .map(|battery| {
+                (
+                    Some(battery.name.clone()),
+                    f64::from(battery.capacity_percent),
+                )
+            })
+            .collect(),
+        AlertMetric::FailedUnits => raw
+            .services
+            .iter()
+            .map(|services| (None, services.failed as f64))
+            .collect(),
+        AlertMetric::DegradedRaid => raw
+            .raid
+            .iter()
+            .flatten()
+            .map(|array| {
+                let missing = array.devices_total.saturating_sub(array.devices_active);
+                (Some(array.name.clone()), f64::from(missing))
+            })
+            .collect(),
+    }
+}
+
+fn alert(rule: &AlertRule, subject: Option<String>, value: f64, firing: bool, host: &str) -> Alert {
+    Alert {
+        status: if firing {
+            AlertStatus::Firing
+        } else {
+            AlertStatus::Resolved
+        },
+        rule: rule.name.clone().unwrap_or_else(|| {
+            format!(
+                "{} {} {}",
+                rule.metric.name(),
+                rule.op.symbol(),
+                rule.threshold
+            )
+        }),
+        metric: rule.metric,
+        subject,
+        value,
+        threshold: rule.threshold,
+        host: host.to_string(),
+        timestamp: chrono::Utc::now().timestamp(),
+    }
+}
@@ -0,0 +1,56 @@
+use async_trait::async_trait;
+use serde_json::json;
+
+use super::{Alert, Notifier, http_client, post_json};
+
+/// Sends alerts to a Slack incoming webhook.
+pub struct SlackNotifier {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl SlackNotifier {
+    pub fn new(url: String) -> Self {
+        SlackNotifier {
+            url,
+            client: http_client(),
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for SlackNotifier {
+    fn name(&self) -> &'static str {
+        "slack"
+    }
+
+    async fn notify(&self, alert: &Alert) -> Result<(), String> {
+        let (r, g, b) = alert.status.color();
+        let field = |name: &str, value: String| json!({ "type": "mrkdwn", "text": format!("*{name}*\n{value}") });
+
+        let payload = json!({
+            // shown in the notification itself, the attachment only in the channel
+            "text": format!("{} on {}", alert.title(), alert.host),
+            "attachments": [{
+                "color": format!("#{r:02x}{g:02x}{b:02x}"),
+                "blocks": [
+                    {
+                        "type": "section",
+                        "text": { "type": "mrkdwn", "text": format!("*{}*", alert.title()) },
+                    },
+                    {
+                        "type": "section",
+                        "fields": [
+                            field("Host", alert.host.clone()),
+                            field("Metric", alert.metric_label()),
+                            field("Value", alert.metric.format_value(alert.value)),
+                            field("Threshold", alert.metric.format_value(alert.threshold)),
+                        ],
+                    },
+                ],
+            }],
+        });
+
+        post_json(&self.client, &self.url, &payload).await
+    }
+}
@@ -0,0 +1,29 @@
+use async_trait::async_trait;
+
+use super::{Alert, Notifier, http_client, post_json};
+
+/// POSTs the [`Alert`] as JSON to any URL.
+pub struct WebhookNotifier {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl WebhookNotifier {
+    pub fn new(url: String) -> Self {
+        WebhookNotifier {
+            url,
+            client: http_client(),
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    async fn notify(&self, alert: &Alert) -> Result<(), String> {
+        post_json(&self.client, &self.url, alert).await
+    }
+}
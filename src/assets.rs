@@ -0,0 +1,173 @@
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    path::Path,
+};
+
+use axum::{
+    body::Body,
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+
+use crate::config;
+
+/// IMF-fixdate, the date format of HTTP headers.
+const HTTP_DATE_FORMAT: &str = "%a, %d %b %Y %H:%M:%S GMT";
+
+/// Embedded assets only change with a new build, and the ETag catches that anyway.
+pub const EMBEDDED_CACHE_CONTROL: &str = "public, max-age=86400";
+/// Files read from disk can change at any time, always revalidate them.
+pub const REVALIDATE_CACHE_CONTROL: &str = "no-cache";
+
+/// Where `--dev` reads the assets from, the `assets/` directory of the source tree.
+const DEV_ASSET_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/assets");
+
+/// Serve a file of `assets/` embedded in the binary, unless [`read_override`] finds it on disk.
+pub fn serve_embedded(
+    headers: &HeaderMap,
+    content_type: &'static str,
+    path: &str,
+    embedded: &'static [u8],
+) -> Response {
+    match read_override(path) {
+        Some(body) => serve(headers, content_type, body, REVALIDATE_CACHE_CONTROL),
+        None => serve(headers, content_type, embedded, EMBEDDED_CACHE_CONTROL),
+    }
+}
+
+/// A file of `assets/` replaced on disk, from `asset_dir`, or the source tree with `--dev`.
+///
+/// `None` means the embedded version is used, a missing file only gets a warning with `--dev`,
+/// where it is likely a typo.
+pub fn read_override(path: &str) -> Option<Vec<u8>> {
+    let config = config::get();
+    if let Some(asset_dir) = &config.asset_dir {
+        let path = asset_dir.join(path);
+        match std::fs::read(&path) {
+            Ok(contents) => return Some(contents),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => tracing::warn!("Failed to read {}: {err}", path.display()),
+        }
+    }
+    if !config.dev {
+        return None;
+    }
+
+    let path = Path::new(DEV_ASSET_DIR).join(path);
+    match std::fs::read(&path) {
+        Ok(contents) => Some(contents),
+        Err(err) => {
+            tracing::warn!("Failed to read {}: {err}", path.display());
+            None
+        }
+    }
+}
+
+/// Serve an asset with an ETag, answering `304 Not Modified` when the client already has it.
+pub fn serve(
+    headers: &HeaderMap,
+    content_type: &'static str,
+    body: impl Into<Body> + AsRef<[u8]>,
+    cache_control: &'static str,
+) -> Response {
+    let etag = etag(body.as_ref());
+    let cache_headers = [
+        (header::ETAG, etag.clone()),
+        (
+            header::CACHE_CONTROL,
+            HeaderValue::from_static(cache_control),
+        ),
+    ];
+
+    if is_not_modified(headers, &etag) {
+        return (StatusCode::NOT_MODIFIED, cache_headers).into_response();
+    }
+
+    (
+        cache_headers,
+        [(header::CONTENT_TYPE, HeaderValue::from_static(content_type))],
+        body.into(),
+    )
+        .into_response()
+}
+
+/// Serve a snapshot as JSON with an ETag and `Last-Modified`, answering `304 Not Modified` when
+/// the client already has it, so pollers don't download the same snapshot again.
+pub fn serve_snapshot(headers: &HeaderMap, value: &impl Serialize, collected_at: i64) -> Response {
+    let body = match serde_json::to_vec(value) {
+        Ok(body) => body,
+        Err(err) => {
+            tracing::error!("Failed to serialize the snapshot: {err}");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let etag = etag(&body);
+    let mut cache_headers = HeaderMap::new();
+    cache_headers.insert(header::ETAG, etag.clone());
+    cache_headers.insert(
+        header::CACHE_CONTROL,
+        HeaderValue::from_static(REVALIDATE_CACHE_CONTROL),
+    );
+    if let Some(last_modified) = chrono::DateTime::from_timestamp(collected_at, 0)
+        .and_then(|time| HeaderValue::from_str(&time.format(HTTP_DATE_FORMAT).to_string()).ok())
+    {
+        cache_headers.insert(header::LAST_MODIFIED, last_modified);
+    }
+
+    // `If-Modified-Since` is only a fallback for clients that don't send `If-None-Match`
+    let not_modified = if headers.contains_key(header::IF_NONE_MATCH) {
+        is_not_modified(headers, &etag)
+    } else {
+        is_not_modified_since(headers, collected_at)
+    };
+    if not_modified {
+        return (StatusCode::NOT_MODIFIED, cache_headers).into_response();
+    }
+
+    (
+        cache_headers,
+        [(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/json"),
+        )],
+        body,
+    )
+        .into_response()
+}
+
+/// Strong ETag from a hash of the content.
+fn etag(content: &[u8]) -> HeaderValue {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+
+    HeaderValue::from_str(&format!("\"{:016x}\"", hasher.finish()))
+        .expect("a hex string should be a valid header value")
+}
+
+/// Check `If-None-Match` against `etag`, using the weak comparison required for GET.
+fn is_not_modified(headers: &HeaderMap, etag: &HeaderValue) -> bool {
+    let Some(if_none_match) = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+    else {
+        return false;
+    };
+    let Ok(etag) = etag.to_str() else {
+        return false;
+    };
+
+    if_none_match.split(',').map(str::trim).any(|candidate| {
+        candidate == "*" || candidate.strip_prefix("W/").unwrap_or(candidate) == etag
+    })
+}
+
+/// Check `If-Modified-Since` against when the content was last modified, a unix timestamp.
+fn is_not_modified_since(headers: &HeaderMap, modified_at: i64) -> bool {
+    headers
+        .get(header::IF_MODIFIED_SINCE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| chrono::DateTime::parse_from_rfc2822(value).ok())
+        .is_some_and(|since| modified_at <= since.timestamp())
+}
@@ -0,0 +1,248 @@
+use std::sync::Mutex;
+
+use argon2::{
+    Argon2, PasswordHash, PasswordHasher, PasswordVerifier,
+    password_hash::{SaltString, rand_core::OsRng},
+};
+use axum::{
+    extract::Request,
+    http::{HeaderMap, HeaderValue, Method, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use base64::{Engine, prelude::BASE64_STANDARD};
+use subtle::ConstantTimeEq;
+
+use crate::config::{self, AuthConfig, Scope};
+
+/// Routes that stay reachable without credentials.
+///
+/// Health checks and crawlers don't carry any, and agent pushes are authenticated by their
+/// signature instead.
+const PUBLIC_PATHS: [&str; 6] = [
+    "/livez",
+    "/readyz",
+    "/__heartbeat__",
+    "/api/v1/push",
+    "/robots.txt",
+    "/.well-known/security.txt",
+];
+
+/// The last accepted `Authorization` header, so a page load doesn't verify the hash for every asset.
+static LAST_VERIFIED: Mutex<Option<Verified>> = Mutex::new(None);
+
+/// An accepted `Authorization` header with the credentials it was checked against, so it stops
+/// being accepted once a reload changes them.
+#[derive(Debug, PartialEq)]
+struct Verified {
+    authorization: HeaderValue,
+    username: String,
+    password_hash: String,
+}
+
+/// Reject requests without valid Basic credentials when `[auth]` is configured.
+pub async fn basic_auth(request: Request, next: Next) -> Response {
+    if request.method() == Method::OPTIONS
+        || PUBLIC_PATHS.contains(&request.uri().path())
+        || has_basic_credentials(request.headers()).await
+    {
+        return next.run(request).await;
+    }
+
+    unauthorized(false)
+}
+
+/// Guard the machine-readable endpoints with the API tokens, falling back to Basic auth.
+///
+/// Without any `[[api.tokens]]` the endpoints are only protected by `[auth]`, except the
+/// failed services listing which always needs a token with `read:services`.
+pub async fn api_auth(request: Request, next: Next) -> Response {
+    if request.method() == Method::OPTIONS || PUBLIC_PATHS.contains(&request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    let scope = required_scope(request.uri().path());
+    if scope == Scope::ReadServices && !any_token_allows(scope) {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+
+    let enforced = scope == Scope::ReadServices || !config::get().api.tokens.is_empty();
+    if !enforced {
+        return basic_auth(request, next).await;
+    }
+
+    match bearer_token(request.headers()) {
+        Some(token) => match token_scopes(token) {
+            None => unauthorized(true),
+            Some(scopes) if !allows(&scopes, scope) => StatusCode::FORBIDDEN.into_response(),
+            Some(_) => next.run(request).await,
+        },
+        // the page itself reads the API with the browser's Basic credentials
+        None if scope != Scope::ReadServices
+            && config::get().auth.is_enabled()
+            && has_basic_credentials(request.headers()).await =>
+        {
+            next.run(request).await
+        }
+        None => unauthorized(true),
+    }
+}
+
+/// Scope an API route needs.
+fn required_scope(path: &str) -> Scope {
+    match path {
+        "/api/v1/processes" => Scope::ReadProcesses,
+        "/api/v1/services/failed" => Scope::ReadServices,
+        _ => Scope::ReadInfo,
+    }
+}
+
+fn allows(scopes: &[Scope], scope: Scope) -> bool {
+    scopes.contains(&scope) || scopes.contains(&Scope::Admin)
+}
+
+/// Scopes of a configured token, `systemd.token` counts as one with `read:services`.
+///
+/// Every token is compared in constant time, so the response time doesn't tell how close a
+/// guess was or which token it came close to.
+fn token_scopes(token: &str) -> Option<Vec<Scope>> {
+    let config = config::get();
+    let mut scopes = None;
+    for api_token in &config.api.tokens {
+        if bool::from(api_token.token.as_bytes().ct_eq(token.as_bytes())) && scopes.is_none() {
+            scopes = Some(api_token.scopes.clone());
+        }
+    }
+    if let Some(systemd_token) = &config.systemd.token
+        && bool::from(systemd_token.as_bytes().ct_eq(token.as_bytes()))
+        && scopes.is_none()
+    {
+        scopes = Some(vec![Scope::ReadServices]);
+    }
+
+    scopes
+}
+
+fn any_token_allows(scope: Scope) -> bool {
+    let config = config::get();
+    (scope == Scope::ReadServices && config.systemd.token.is_some())
+        || config
+            .api
+            .tokens
+            .iter()
+            .any(|api_token| allows(&api_token.scopes, scope))
+}
+
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+}
+
+/// `401` challenging for a bearer token, Basic credentials, or both.
+fn unauthorized(bearer: bool) -> Response {
+    let mut challenges = vec![];
+    if bearer {
+        challenges.push("Bearer");
+    }
+    if config::get().auth.is_enabled() {
+        challenges.push("Basic realm=\"helios\", charset=\"UTF-8\"");
+    }
+
+    let mut response = StatusCode::UNAUTHORIZED.into_response();
+    for challenge in challenges {
+        response.headers_mut().append(
+            header::WWW_AUTHENTICATE,
+            HeaderValue::from_static(challenge),
+        );
+    }
+    response
+}
+
+/// Check the Basic credentials, always true when `[auth]` is not configured.
+async fn has_basic_credentials(headers: &HeaderMap) -> bool {
+    let config = config::get();
+    if !config.auth.is_enabled() {
+        return true;
+    }
+
+    let headers = headers.clone();
+    tokio::task::spawn_blocking(move || is_authorized(&headers, &config.auth))
+        .await
+        .unwrap_or(false)
+}
+
+fn is_authorized(headers: &HeaderMap, auth_config: &AuthConfig) -> bool {
+    let Some(authorization) = headers.get(header::AUTHORIZATION) else {
+        return false;
+    };
+
+    let (Some(expected_username), Some(expected_hash)) =
+        (&auth_config.username, &auth_config.password_hash)
+    else {
+        return false;
+    };
+    let accepted = Verified {
+        authorization: authorization.clone(),
+        username: expected_username.clone(),
+        password_hash: expected_hash.clone(),
+    };
+    // not held while verifying, the hash is deliberately slow and would hold up every request
+    if LAST_VERIFIED
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        == Some(&accepted)
+    {
+        return true;
+    }
+
+    let Some((username, password)) = decode_basic(authorization) else {
+        return false;
+    };
+    let Ok(password_hash) = PasswordHash::new(expected_hash) else {
+        return false;
+    };
+
+    let verified = Argon2::default()
+        .verify_password(password.as_bytes(), &password_hash)
+        .is_ok();
+    if !verified || username != *expected_username {
+        return false;
+    }
+
+    *LAST_VERIFIED.lock().unwrap_or_else(|e| e.into_inner()) = Some(accepted);
+    true
+}
+
+/// Split a `Basic` authorization header into the username and password.
+fn decode_basic(authorization: &HeaderValue) -> Option<(String, String)> {
+    let encoded = authorization.to_str().ok()?.strip_prefix("Basic ")?;
+    let decoded = String::from_utf8(BASE64_STANDARD.decode(encoded.trim()).ok()?).ok()?;
+    let (username, password) = decoded.split_once(':')?;
+
+    Some((username.to_string(), password.to_string()))
+}
+
+/// Hash a password into the PHC string expected by `auth.password_hash`.
+pub fn hash_password(password: &str) -> Result<String, argon2::password_hash::Error> {
+    let salt = SaltString::generate(&mut OsRng);
+    let hash = Argon2::default().hash_password(password.as_bytes(), &salt)?;
+
+    Ok(hash.to_string())
+}
+
+/// Make sure a configured password hash can actually be verified against.
+pub fn validate(auth_config: &AuthConfig) -> Result<(), String> {
+    match (&auth_config.username, &auth_config.password_hash) {
+        (None, None) => Ok(()),
+        (Some(_), None) | (None, Some(_)) => {
+            Err("both `auth.username` and `auth.password_hash` must be set".to_string())
+        }
+        (Some(_), Some(password_hash)) => PasswordHash::new(password_hash)
+            .map(|_| ())
+            .map_err(|err| format!("invalid `auth.password_hash`: {err}")),
+    }
+}
@@ -0,0 +1,217 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde::Deserialize;
+
+use crate::{
+    assets,
+    state::AppState,
+    sysgetter::{LineStatus, SystemInfo, SystemInfoRaw},
+};
+
+// shields.io colors
+const GREEN: &str = "#4c1";
+const YELLOW: &str = "#dfb317";
+const RED: &str = "#e05d44";
+const GREY: &str = "#9f9f9f";
+const LABEL_COLOR: &str = "#555";
+
+/// Usage at or above these is shown in yellow and red.
+const WARNING_PERCENT: f64 = 75.0;
+const CRITICAL_PERCENT: f64 = 90.0;
+const WARNING_CELSIUS: f32 = 70.0;
+const CRITICAL_CELSIUS: f32 = 85.0;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct BadgeQuery {
+    /// Replaces the metric name on the left side.
+    label: Option<String>,
+    /// Mount point for the `disk` badge, `/` by default.
+    mount: Option<String>,
+}
+
+/// Shields-style SVG badge of a single metric, e.g. `/badge/memory.svg`.
+///
+/// Metrics without data, like `battery` on a desktop, render a grey `n/a` badge.
+pub async fn badge_handler(
+    State(state): State<AppState>,
+    Path(file): Path<String>,
+    Query(query): Query<BadgeQuery>,
+    headers: HeaderMap,
+) -> Response {
+    let Some(metric) = file.strip_suffix(".svg") else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    let snapshot = state.latest();
+    let Some(value) = badge_value(metric, &snapshot.info, &snapshot.raw, &query) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let (message, color) = value.unwrap_or(("n/a".to_string(), GREY));
+    let label = query.label.as_deref().unwrap_or(metric);
+
+    assets::serve(
+        &headers,
+        "image/svg+xml",
+        render(label, &message, color),
+        assets::REVALIDATE_CACHE_CONTROL,
+    )
+}
+
+/// The message and color of a badge, `None` for an unknown metric.
+fn badge_value(
+    metric: &str,
+    info: &SystemInfo,
+    raw: &SystemInfoRaw,
+    query: &BadgeQuery,
+) -> Option<Option<(String, &'static str)>> {
+    let value = match metric {
+        "status" => {
+            let worst = info
+                .lines()
+                .iter()
+                .filter_map(|line| line.status())
+                .max_by_key(|status| match status {
+                    LineStatus::Warning => 1,
+                    LineStatus::Critical => 2,
+                });
+            Some(match worst {
+                None => ("ok".to_string(), GREEN),
+                Some(LineStatus::Warning) => ("warning".to_string(), YELLOW),
+                Some(LineStatus::Critical) => ("critical".to_string(), RED),
+            })
+        }
+        "uptime" => raw
+            .os
+            .as_ref()
+            .map(|os| (compact_duration(os.uptime_seconds), GREEN)),
+        "cpu" => raw
+            .cpu
+            .as_ref()
+            .map(|cpu| percent(f64::from(cpu.usage_percent))),
+        "memory" => raw
+            .memory
+            .as_ref()
+            .map(|memory| percent(memory.usage_percent)),
+        "swap" => raw
+            .swap
+            .as_ref()
+            .filter(|swap| swap.total_bytes > 0)
+            .map(|swap| percent(swap.usage_percent)),
+        "disk" => {
+            let mount = query.mount.as_deref().unwrap_or("/");
+            raw.disks
+                .iter()
+                .flatten()
+                .find(|disk| disk.mount_point == mount)
+                .map(|disk| percent(disk.usage_percent))
+        }
+        "temperature" => raw
+            .temperatures
+            .iter()
+            .flatten()
+            .map(|sensor| sensor.celsius)
+            .reduce(f32::max)
+            .map(|celsius| {
+                let color = if celsius >= CRITICAL_CELSIUS {
+                    RED
+                } else if celsius >= WARNING_CELSIUS {
+                    YELLOW
+                } else {
+                    GREEN
+                };
+                (format!("{celsius:.0}°C"), color)
+            }),
+        "battery" => raw.batteries.iter().flatten().next().map(|battery| {
+            let capacity = f64::from(battery.capacity_percent);
+            // low charge is the bad direction here
+            let (_, color) = percent(100.0 - capacity);
+            (format!("{capacity:.0}%"), color)
+        }),
+        "containers" => raw.containers.as_ref().map(|containers| {
+            (
+                format!("{}/{} running", containers.running, containers.total),
+                GREEN,
+            )
+        }),
+        "failed_units" => raw.services.as_ref().map(|services| {
+            let color = if services.failed > 0 { RED } else { GREEN };
+            (services.failed.to_string(), color)
+        }),
+        _ => return None,
+    };
+
+    Some(value)
+}
+
+fn percent(value: f64) -> (String, &'static str) {
+    let color = if value >= CRITICAL_PERCENT {
+        RED
+    } else if value >= WARNING_PERCENT {
+        YELLOW
+    } else {
+        GREEN
+    };
+
+    (format!("{value:.0}%"), color)
+}
+
+/// `3d 4h`, `5h 12m`, or `42m`, the full uptime line is too long for a badge.
+fn compact_duration(seconds: u64) -> String {
+    let days = seconds / 86400;
+    let hours = (seconds % 86400) / 3600;
+    let minutes = (seconds % 3600) / 60;
+
+    if days > 0 {
+        format!("{days}d {hours}h")
+    } else if hours > 0 {
+        format!("{hours}h {minutes}m")
+    } else {
+        format!("{minutes}m")
+    }
+}
+
+/// Approximate width of `text` in 11px Verdana, what shields.io badges are set in.
+fn text_width(text: &str) -> u32 {
+    let width: f32 = text
+        .chars()
+        .map(|ch| match ch {
+            'i' | 'j' | 'l' | '.' | ',' | ':' | ';' | '\'' | '|' | '!' | ' ' => 3.9,
+            'f' | 'r' | 't' | 'I' | '(' | ')' | '[' | ']' | '/' | '-' => 4.9,
+            'm' | 'w' | 'M' | 'W' | '%' => 10.0,
+            'A'..='Z' | '0'..='9' => 7.5,
+            _ => 6.6,
+        })
+        .sum();
+
+    width.ceil() as u32
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Flat shields.io style badge with a grey label and a colored message.
+fn render(label: &str, message: &str, color: &str) -> String {
+    const PADDING: u32 = 10;
+
+    let label_width = text_width(label) + PADDING;
+    let message_width = text_width(message) + PADDING;
+    let width = label_width + message_width;
+    // text is laid out at 10x scale like shields.io, for sub-pixel positioning
+    let label_x = label_width * 5;
+    let message_x = label_width * 10 + message_width * 5;
+    let label_length = (label_width - PADDING) * 10;
+    let message_length = (message_width - PADDING) * 10;
+    let label = escape(label);
+    let message = escape(message);
+
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="20" role="img" aria-label="{label}: {message}"><title>{label}: {message}</title><linearGradient id="s" x2="0" y2="100%"><stop offset="0" stop-color="#bbb" stop-opacity=".1"/><stop offset="1" stop-opacity=".1"/></linearGradient><clipPath id="r"><rect width="{width}" height="20" rx="3" fill="#fff"/></clipPath><g clip-path="url(#r)"><rect width="{label_width}" height="20" fill="{LABEL_COLOR}"/><rect x="{label_width}" width="{message_width}" height="20" fill="{color}"/><rect width="{width}" height="20" fill="url(#s)"/></g><g fill="#fff" text-anchor="middle" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" text-rendering="geometricPrecision" font-size="110"><text aria-hidden="true" x="{label_x}" y="150" fill="#010101" fill-opacity=".3" transform="scale(.1)" textLength="{label_length}">{label}</text><text x="{label_x}" y="140" transform="scale(.1)" textLength="{label_length}">{label}</text><text aria-hidden="true" x="{message_x}" y="150" fill="#010101" fill-opacity=".3" transform="scale(.1)" textLength="{message_length}">{message}</text><text x="{message_x}" y="140" transform="scale(.1)" textLength="{message_length}">{message}</text></g></svg>"##
+    )
+}
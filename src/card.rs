@@ -0,0 +1,207 @@
+use std::sync::LazyLock;
+
+use ab_glyph::{Font, FontRef, PxScale, ScaleFont, point};
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use tiny_skia::{Color, FilterQuality, Mask, Paint, Pixmap, PixmapPaint, Rect, Transform};
+
+use crate::{
+    HELIOS_BANNER, assets, config,
+    state::AppState,
+    sysgetter::{LineStatus, PALETTE, SystemInfo},
+};
+
+const FONT: &[u8] = include_bytes!("../assets/fonts/DejaVuSansMono.ttf");
+const FONT_BOLD: &[u8] = include_bytes!("../assets/fonts/DejaVuSansMono-Bold.ttf");
+
+const PADDING: f32 = 24.0;
+const LOGO_SIZE: f32 = 192.0;
+const FONT_SIZE: f32 = 16.0;
+const LINE_HEIGHT: f32 = 24.0;
+// `.block-palette`
+const PALETTE_WIDTH: f32 = 28.0;
+const PALETTE_HEIGHT: f32 = 20.0;
+
+// colors from style.css
+const BACKGROUND: (u8, u8, u8) = (0x13, 0x16, 0x1a);
+const HOST: (u8, u8, u8) = (0xdf, 0xac, 0xac);
+const HOST_AT: (u8, u8, u8) = (0xf9, 0xfa, 0xfb);
+const KEY: (u8, u8, u8) = (0xac, 0xac, 0xfc);
+const TEXT: (u8, u8, u8) = (0xff, 0xff, 0xff);
+const WARNING: (u8, u8, u8) = (0xe3, 0xb3, 0x54);
+const CRITICAL: (u8, u8, u8) = (0xaf, 0x54, 0x57);
+
+static FONTS: LazyLock<(FontRef<'static>, FontRef<'static>)> = LazyLock::new(|| {
+    (
+        FontRef::try_from_slice(FONT).expect("the embedded font should be valid"),
+        FontRef::try_from_slice(FONT_BOLD).expect("the embedded bold font should be valid"),
+    )
+});
+
+/// A piece of text drawn in one color and weight.
+struct Span<'a> {
+    text: &'a str,
+    color: (u8, u8, u8),
+    bold: bool,
+}
+
+/// The page's info block rendered to a PNG, for places that only embed images.
+pub async fn card_handler(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    let info = state.latest().info;
+    match tokio::task::spawn_blocking(move || render(&info)).await {
+        Ok(Some(png)) => {
+            assets::serve(&headers, "image/png", png, assets::REVALIDATE_CACHE_CONTROL)
+        }
+        _ => {
+            tracing::error!("Failed to render the status card");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+fn render(info: &SystemInfo) -> Option<Vec<u8>> {
+    let username = &config::get().username;
+    let separator = "-".repeat(info.host().len() + username.chars().count() + 1);
+
+    let mut rows = vec![
+        vec![
+            Span::new(username, HOST, true),
+            Span::new("@", HOST_AT, true),
+            Span::new(info.host(), HOST, true),
+        ],
+        vec![Span::new(&separator, TEXT, false)],
+    ];
+    for line in info.lines() {
+        let color = match line.status() {
+            None => TEXT,
+            Some(LineStatus::Warning) => WARNING,
+            Some(LineStatus::Critical) => CRITICAL,
+        };
+        rows.push(vec![
+            Span::new(line.key(), KEY, true),
+            Span::new(": ", color, false),
+            Span::new(line.value(), color, false),
+        ]);
+    }
+
+    let text_width = rows
+        .iter()
+        .map(|row| row.iter().map(Span::width).sum::<f32>())
+        .fold(PALETTE_WIDTH * 8.0, f32::max);
+    let text_height = rows.len() as f32 * LINE_HEIGHT + LINE_HEIGHT + PALETTE_HEIGHT * 2.0;
+    let text_x = PADDING * 2.0 + LOGO_SIZE;
+    let width = text_x + text_width + PADDING;
+    let height = PADDING * 2.0 + text_height.max(LOGO_SIZE);
+
+    let mut pixmap = Pixmap::new(width.ceil() as u32, height.ceil() as u32)?;
+    pixmap.fill(rgb(BACKGROUND));
+
+    let logo = Pixmap::decode_png(HELIOS_BANNER).ok()?;
+    let scale = LOGO_SIZE / logo.width() as f32;
+    pixmap.draw_pixmap(
+        0,
+        0,
+        logo.as_ref(),
+        &PixmapPaint {
+            quality: FilterQuality::Bicubic,
+            ..PixmapPaint::default()
+        },
+        Transform::from_scale(scale, scale).post_translate(PADDING, PADDING),
+        None,
+    );
+
+    let mut y = PADDING;
+    for row in &rows {
+        let mut x = text_x;
+        for span in row {
+            draw_text(&mut pixmap, span, x, y);
+            x += span.width();
+        }
+        y += LINE_HEIGHT;
+    }
+
+    // two rows of eight like the page
+    y += LINE_HEIGHT;
+    for (index, color) in PALETTE.iter().enumerate() {
+        let x = text_x + (index % 8) as f32 * PALETTE_WIDTH;
+        let y = y + (index / 8) as f32 * PALETTE_HEIGHT;
+        if let Some(rect) = Rect::from_xywh(x, y, PALETTE_WIDTH, PALETTE_HEIGHT) {
+            pixmap.fill_rect(rect, &paint(*color), Transform::identity(), None);
+        }
+    }
+
+    pixmap.encode_png().ok()
+}
+
+impl<'a> Span<'a> {
+    fn new(text: &'a str, color: (u8, u8, u8), bold: bool) -> Self {
+        Span { text, color, bold }
+    }
+
+    fn font(&self) -> &'static FontRef<'static> {
+        if self.bold { &FONTS.1 } else { &FONTS.0 }
+    }
+
+    fn width(&self) -> f32 {
+        let font = self.font().as_scaled(PxScale::from(FONT_SIZE));
+        self.text
+            .chars()
+            .map(|ch| font.h_advance(font.glyph_id(ch)))
+            .sum()
+    }
+}
+
+/// Draw `span` with its top left corner at `x`, `y`.
+fn draw_text(pixmap: &mut Pixmap, span: &Span, x: f32, y: f32) {
+    let font = span.font();
+    let scaled = font.as_scaled(PxScale::from(FONT_SIZE));
+    // center the text vertically in the line
+    let baseline = y + (LINE_HEIGHT + scaled.ascent() + scaled.descent()) / 2.0;
+
+    let Some(mut mask) = Mask::new(pixmap.width(), pixmap.height()) else {
+        return;
+    };
+    let mask_width = mask.width() as i32;
+    let mask_height = mask.height() as i32;
+    let coverage = mask.data_mut();
+
+    let mut cursor = x;
+    for ch in span.text.chars() {
+        let glyph_id = scaled.glyph_id(ch);
+        let glyph = glyph_id.with_scale_and_position(FONT_SIZE, point(cursor, baseline));
+        cursor += scaled.h_advance(glyph_id);
+
+        let Some(outline) = font.outline_glyph(glyph) else {
+            continue;
+        };
+        let bounds = outline.px_bounds();
+        outline.draw(|glyph_x, glyph_y, value| {
+            let px = bounds.min.x as i32 + glyph_x as i32;
+            let py = bounds.min.y as i32 + glyph_y as i32;
+            if (0..mask_width).contains(&px) && (0..mask_height).contains(&py) {
+                let index = (py * mask_width + px) as usize;
+                let value = (value.clamp(0.0, 1.0) * 255.0) as u8;
+                coverage[index] = coverage[index].max(value);
+            }
+        });
+    }
+
+    let Some(rect) = Rect::from_xywh(x, y, (cursor - x).max(1.0), LINE_HEIGHT) else {
+        return;
+    };
+    pixmap.fill_rect(rect, &paint(span.color), Transform::identity(), Some(&mask));
+}
+
+fn rgb((r, g, b): (u8, u8, u8)) -> Color {
+    Color::from_rgba8(r, g, b, 0xff)
+}
+
+fn paint(color: (u8, u8, u8)) -> Paint<'static> {
+    let mut paint = Paint::default();
+    paint.set_color(rgb(color));
+    paint.anti_alias = false;
+    paint
+}
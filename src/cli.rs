@@ -0,0 +1,84 @@
+use std::{net::IpAddr, path::PathBuf};
+
+use clap::Parser;
+
+use crate::config::Config;
+
+const LONG_VERSION: &str = concat!(
+    env!("CARGO_PKG_VERSION"),
+    " (",
+    env!("HELIOS_GIT_HASH"),
+    ", ",
+    env!("HELIOS_BUILD_TARGET"),
+    ", ",
+    env!("HELIOS_BUILD_PROFILE"),
+    ")"
+);
+
+#[derive(Debug, Parser)]
+#[command(name = "helios", about, version = LONG_VERSION)]
+pub struct Cli {
+    /// Path to the configuration file (defaults to `HELIOS_CONFIG` or `./helios.toml`)
+    #[arg(short, long, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Port to listen on
+    #[arg(short, long)]
+    pub port: Option<u16>,
+
+    /// Address to listen on, e.g. `0.0.0.0` or `::`
+    #[arg(short, long, value_name = "ADDR")]
+    pub bind: Option<IpAddr>,
+
+    /// Seconds between background refreshes of the system info
+    #[arg(short, long, value_name = "SECONDS")]
+    pub refresh_interval: Option<u64>,
+
+    /// Name shown before the `@` in the host header
+    #[arg(short, long, value_name = "NAME")]
+    pub username: Option<String>,
+
+    /// Minimum level of the log output (error, warn, info, debug, trace)
+    #[arg(short, long, value_name = "LEVEL")]
+    pub log_level: Option<String>,
+
+    /// Push snapshots to this helios hub endpoint, e.g. `https://hub.example.com/api/v1/push`
+    #[arg(long, value_name = "URL")]
+    pub push_to: Option<String>,
+
+    /// Read the page's assets and templates from the `assets/` directory of the source tree on
+    /// every request, to work on the frontend without rebuilding
+    #[arg(long)]
+    pub dev: bool,
+
+    /// Read a password from stdin, print its hash for `auth.password_hash`, and exit
+    #[arg(long)]
+    pub hash_password: bool,
+}
+
+impl Cli {
+    /// Override the loaded configuration with anything passed on the command line.
+    pub fn apply(&self, config: &mut Config) {
+        if let Some(port) = self.port {
+            config.port = port;
+        }
+        if let Some(bind) = self.bind {
+            config.bind = bind;
+        }
+        if let Some(refresh_interval) = self.refresh_interval {
+            config.refresh_interval = refresh_interval;
+        }
+        if let Some(username) = &self.username {
+            config.username = username.clone();
+        }
+        if let Some(log_level) = &self.log_level {
+            config.log_level = log_level.clone();
+        }
+        if let Some(push_to) = &self.push_to {
+            config.push.url = Some(push_to.clone());
+        }
+        if self.dev {
+            config.dev = true;
+        }
+    }
+}
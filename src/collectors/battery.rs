@@ -0,0 +1,90 @@
+use std::path::Path;
+
+use async_trait::async_trait;
+
+use super::Collector;
+use crate::sysgetter::{BatteryInfoRaw, LineInfo, SystemInfoRaw};
+
+const POWER_SUPPLY_DIR: &str = "/sys/class/power_supply";
+
+/// Laptop battery charge, charging state, and health.
+pub struct BatteryCollector;
+
+#[async_trait]
+impl Collector for BatteryCollector {
+    fn name(&self) -> &'static str {
+        "battery"
+    }
+
+    async fn collect(&self, raw: &mut SystemInfoRaw) {
+        let Ok(entries) = std::fs::read_dir(POWER_SUPPLY_DIR) else {
+            return;
+        };
+
+        let mut batteries: Vec<BatteryInfoRaw> = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| read_battery(&entry.path()))
+            .collect();
+        batteries.sort_by(|a, b| a.name.cmp(&b.name));
+
+        if !batteries.is_empty() {
+            raw.batteries = Some(batteries);
+        }
+    }
+
+    fn lines(&self, raw: &SystemInfoRaw) -> Vec<LineInfo> {
+        let Some(batteries) = &raw.batteries else {
+            return vec![];
+        };
+
+        batteries
+            .iter()
+            .map(|battery| {
+                let key = if batteries.len() > 1 {
+                    format!("Battery ({})", battery.name)
+                } else {
+                    "Battery".to_string()
+                };
+
+                let mut value = format!("{}% ({})", battery.capacity_percent, battery.status);
+                if let Some(health) = battery.health_percent {
+                    value.push_str(&format!(", {health:.0}% health"));
+                }
+
+                LineInfo::new(key, value)
+            })
+            .collect()
+    }
+}
+
+fn read_battery(path: &Path) -> Option<BatteryInfoRaw> {
+    if read_value(path, "type")? != "Battery" {
+        return None;
+    }
+
+    let name = path.file_name()?.to_string_lossy().to_string();
+    let capacity_percent = read_value(path, "capacity")?.parse().ok()?;
+    let status = read_value(path, "status").unwrap_or_else(|| "Unknown".to_string());
+
+    // drivers expose either energy (µWh) or charge (µAh) counters
+    let health_percent = ["energy", "charge"].iter().find_map(|prefix| {
+        let full: f64 = read_value(path, &format!("{prefix}_full"))?.parse().ok()?;
+        let design: f64 = read_value(path, &format!("{prefix}_full_design"))?
+            .parse()
+            .ok()?;
+        (design > 0.0).then(|| (full / design) * 100.0)
+    });
+
+    Some(BatteryInfoRaw {
+        name,
+        capacity_percent,
+        status,
+        health_percent,
+    })
+}
+
+fn read_value(path: &Path, file: &str) -> Option<String> {
+    std::fs::read_to_string(path.join(file))
+        .ok()
+        .map(|value| value.trim().to_string())
+}
@@ -0,0 +1,85 @@
+//! Limits of the cgroup helios runs in, what a container is actually allowed to use.
+//!
+//! Inside a cgroup namespace `/sys/fs/cgroup` is the container's own cgroup, outside of one it's
+//! the root cgroup, which has no limits set.
+
+use std::path::Path;
+
+const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
+/// Memory limit and usage in bytes, `None` when no limit is set.
+///
+/// The usage leaves out inactive page cache, which is reclaimed before the limit is hit, like
+/// `docker stats` does.
+pub fn memory_limit() -> Option<(u64, u64)> {
+    let root = Path::new(CGROUP_ROOT);
+    let (limit, usage, stat, inactive_key) = if root.join("cgroup.controllers").exists() {
+        (
+            // `max` when unlimited, which fails to parse
+            read_u64(&root.join("memory.max"))?,
+            read_u64(&root.join("memory.current"))?,
+            root.join("memory.stat"),
+            "inactive_file",
+        )
+    } else {
+        let memory = root.join("memory");
+        (
+            // unlimited is a page-aligned i64::MAX, it's filtered against the host total
+            read_u64(&memory.join("memory.limit_in_bytes"))?,
+            read_u64(&memory.join("memory.usage_in_bytes"))?,
+            memory.join("memory.stat"),
+            "total_inactive_file",
+        )
+    };
+
+    let inactive = std::fs::read_to_string(stat)
+        .ok()
+        .and_then(|stat| {
+            stat.lines().find_map(|line| {
+                let (key, value) = line.split_once(' ')?;
+                (key == inactive_key).then(|| value.trim().parse::<u64>().ok())?
+            })
+        })
+        .unwrap_or(0);
+
+    Some((limit, usage.saturating_sub(inactive)))
+}
+
+/// CPU quota in cores, e.g. `1.5` for `--cpus=1.5`, `None` when no quota is set.
+pub fn cpu_quota() -> Option<f32> {
+    let root = Path::new(CGROUP_ROOT);
+    let (quota, period) = if root.join("cgroup.controllers").exists() {
+        // `<quota> <period>`, the quota is `max` when unlimited
+        let max = std::fs::read_to_string(root.join("cpu.max")).ok()?;
+        let mut fields = max.split_whitespace();
+        let quota = fields.next()?.parse::<u64>().ok()?;
+        let period = fields.next()?.parse::<u64>().ok()?;
+        (quota, period)
+    } else {
+        let cpu = root.join("cpu");
+        // -1 when unlimited, which fails to parse
+        let quota = read_u64(&cpu.join("cpu.cfs_quota_us"))?;
+        let period = read_u64(&cpu.join("cpu.cfs_period_us"))?;
+        (quota, period)
+    };
+
+    (period > 0).then(|| quota as f32 / period as f32)
+}
+
+/// CPU time used by the cgroup so far, in microseconds.
+pub fn cpu_usage_usec() -> Option<u64> {
+    let root = Path::new(CGROUP_ROOT);
+    if root.join("cgroup.controllers").exists() {
+        let stat = std::fs::read_to_string(root.join("cpu.stat")).ok()?;
+        stat.lines()
+            .find_map(|line| line.strip_prefix("usage_usec "))
+            .and_then(|usec| usec.trim().parse().ok())
+    } else {
+        // nanoseconds on v1
+        read_u64(&root.join("cpuacct").join("cpuacct.usage")).map(|nsec| nsec / 1000)
+    }
+}
+
+fn read_u64(path: &Path) -> Option<u64> {
+    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+}
@@ -0,0 +1,195 @@
+use std::{sync::Mutex, time::Instant};
+
+use async_trait::async_trait;
+use serde::Serialize;
+use sysinfo::{Components, CpuRefreshKind, MINIMUM_CPU_UPDATE_INTERVAL, RefreshKind, System};
+
+use super::{Collector, cgroup, temperature::format_temperature};
+use crate::{
+    config,
+    sysgetter::{ContainerLimitsRaw, CpuInfoRaw, LineInfo, SystemInfoRaw, calculate_cpu_freq},
+};
+
+/// Label fragments of the CPU package sensor, most specific first.
+///
+/// Intel reports `Package id 0`, AMD `Tdie` or `Tctl` (offset on some models), ARM boards `cpu_thermal`.
+const PACKAGE_SENSORS: [&str; 4] = ["package id", "tdie", "tctl", "cpu"];
+
+/// CPU brand, core count, frequency, usage since the previous collection, package temperature,
+/// and the CPU quota inside a container.
+pub struct CpuCollector {
+    /// CPU usage is computed against the previous refresh, so keep the instance around.
+    sys: Mutex<(System, Instant)>,
+    components: Mutex<Components>,
+    /// CPU time of the cgroup at the previous collection, to compute its usage against the quota.
+    cgroup_usage: Mutex<Option<(u64, Instant)>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CpuUsageRaw {
+    pub brand: String,
+    pub usage_percent: f32,
+    pub cores: Vec<CoreUsageRaw>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CoreUsageRaw {
+    pub name: String,
+    pub usage_percent: f32,
+    pub frequency_mhz: u64,
+}
+
+impl Default for CpuCollector {
+    fn default() -> Self {
+        CpuCollector {
+            sys: Mutex::new((new_cpu_system(), Instant::now())),
+            components: Mutex::new(Components::new_with_refreshed_list()),
+            cgroup_usage: Mutex::new(None),
+        }
+    }
+}
+
+#[async_trait]
+impl Collector for CpuCollector {
+    fn name(&self) -> &'static str {
+        "cpu"
+    }
+
+    async fn collect(&self, raw: &mut SystemInfoRaw) {
+        // usage is meaningless if the two samples are too close together
+        let wait = {
+            let guard = self.sys.lock().unwrap_or_else(|e| e.into_inner());
+            MINIMUM_CPU_UPDATE_INTERVAL.saturating_sub(guard.1.elapsed())
+        };
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+
+        let mut guard = self.sys.lock().unwrap_or_else(|e| e.into_inner());
+        let (sys, refreshed_at) = &mut *guard;
+        sys.refresh_cpu_all();
+        *refreshed_at = Instant::now();
+
+        let cpus = sys.cpus();
+        let quota = cgroup::cpu_quota()
+            .filter(|quota| config::get().cpu.container_limit && *quota < cpus.len() as f32);
+        let mut usage_percent = sys.global_cpu_usage();
+        if let Some(quota) = quota {
+            raw.container_limits
+                .get_or_insert_with(ContainerLimitsRaw::default)
+                .cpus = Some(quota);
+            // the host's usage says nothing about how close the container is to its quota
+            if let Some(usage) = self.quota_usage(quota) {
+                usage_percent = usage;
+            }
+        }
+
+        raw.cpu = cpus.first().map(|cpu| CpuInfoRaw {
+            brand: cpu.brand().to_string(),
+            cores: cpus.len(),
+            frequency_mhz: cpu.frequency(),
+            usage_percent,
+            temperature_celsius: self.package_temperature(),
+        });
+    }
+
+    fn lines(&self, raw: &SystemInfoRaw) -> Vec<LineInfo> {
+        let Some(cpu) = &raw.cpu else {
+            return vec![];
+        };
+
+        let cpu_freq = calculate_cpu_freq(cpu.frequency_mhz);
+        let quota = raw.container_limits.as_ref().and_then(|limits| limits.cpus);
+        let mut value = match quota {
+            Some(quota) => format!(
+                "{} ({}) @ {cpu_freq} — {:.0}% of {} cores used (container)",
+                cpu.brand,
+                cpu.cores,
+                cpu.usage_percent,
+                (quota * 100.0).round() / 100.0
+            ),
+            None => format!(
+                "{} ({}) @ {cpu_freq} — {:.0}% used",
+                cpu.brand, cpu.cores, cpu.usage_percent
+            ),
+        };
+        if let Some(celsius) = cpu.temperature_celsius {
+            value.push_str(", ");
+            value.push_str(&format_temperature(
+                celsius,
+                config::get().temperatures.unit,
+            ));
+        }
+        vec![LineInfo::new("CPU", value)]
+    }
+}
+
+impl CpuCollector {
+    /// Usage of the cgroup since the previous collection, relative to its quota.
+    fn quota_usage(&self, quota: f32) -> Option<f32> {
+        let usage_usec = cgroup::cpu_usage_usec()?;
+        let now = Instant::now();
+
+        let mut previous = self.cgroup_usage.lock().unwrap_or_else(|e| e.into_inner());
+        let (previous_usec, previous_at) = previous.replace((usage_usec, now))?;
+        let elapsed_usec = now.duration_since(previous_at).as_micros() as f32;
+        if elapsed_usec == 0.0 {
+            return None;
+        }
+
+        let used_usec = usage_usec.saturating_sub(previous_usec) as f32;
+        Some((used_usec / (elapsed_usec * quota) * 100.0).min(100.0))
+    }
+
+    /// Reading of `cpu.temperature_sensor`, or the first sensor that looks like the CPU package.
+    fn package_temperature(&self) -> Option<f32> {
+        let cpu_config = &config::get().cpu;
+        if !cpu_config.temperature {
+            return None;
+        }
+
+        let mut components = self.components.lock().unwrap_or_else(|e| e.into_inner());
+        components.refresh(true);
+
+        let find = |fragment: &str| {
+            let fragment = fragment.to_ascii_lowercase();
+            components
+                .iter()
+                .filter(|component| component.label().to_ascii_lowercase().contains(&fragment))
+                .find_map(|component| component.temperature())
+        };
+        match &cpu_config.temperature_sensor {
+            Some(sensor) => find(sensor),
+            None => PACKAGE_SENSORS.iter().find_map(|sensor| find(sensor)),
+        }
+    }
+}
+
+fn new_cpu_system() -> System {
+    System::new_with_specifics(RefreshKind::nothing().with_cpu(CpuRefreshKind::everything()))
+}
+
+/// Sample per-core usage and frequency over a short interval.
+///
+/// This blocks for [`MINIMUM_CPU_UPDATE_INTERVAL`] since CPU usage needs two samples.
+pub fn sample_cpu_usage() -> Option<CpuUsageRaw> {
+    let mut sys = new_cpu_system();
+    std::thread::sleep(MINIMUM_CPU_UPDATE_INTERVAL);
+    sys.refresh_cpu_all();
+
+    let cpus = sys.cpus();
+    let brand = cpus.first()?.brand().to_string();
+
+    Some(CpuUsageRaw {
+        brand,
+        usage_percent: sys.global_cpu_usage(),
+        cores: cpus
+            .iter()
+            .map(|cpu| CoreUsageRaw {
+                name: cpu.name().to_string(),
+                usage_percent: cpu.cpu_usage(),
+                frequency_mhz: cpu.frequency(),
+            })
+            .collect(),
+    })
+}
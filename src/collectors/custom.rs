@@ -0,0 +1,116 @@
+use std::{io, process::Stdio, time::Duration};
+
+use async_trait::async_trait;
+use tokio::{io::AsyncReadExt, process::Command};
+
+use super::Collector;
+use crate::{
+    config::{self, CustomLineConfig},
+    sysgetter::{CustomLineRaw, LineInfo, SystemInfoRaw},
+};
+
+/// The `[[lines.custom]]` lines from the config, e.g. where the machine is hosted.
+///
+/// Lines with a `command` take the command's output as their value, like neofetch's custom modules.
+pub struct CustomCollector;
+
+#[async_trait]
+impl Collector for CustomCollector {
+    fn name(&self) -> &'static str {
+        "custom"
+    }
+
+    async fn collect(&self, raw: &mut SystemInfoRaw) {
+        let custom = &config::get().lines.custom;
+        if custom.is_empty() {
+            return;
+        }
+
+        let mut lines = vec![];
+        for line in custom {
+            let value = match &line.command {
+                Some(command) => match run_command(command, line).await {
+                    Ok(value) => value,
+                    Err(err) => {
+                        tracing::warn!(
+                            "Custom line `{}` failed to run `{command}`: {err}",
+                            line.key
+                        );
+                        continue;
+                    }
+                },
+                None => line.value.clone(),
+            };
+
+            lines.push(CustomLineRaw {
+                key: line.key.clone(),
+                value,
+            });
+        }
+
+        raw.custom = Some(lines);
+    }
+
+    fn lines(&self, raw: &SystemInfoRaw) -> Vec<LineInfo> {
+        raw.custom
+            .iter()
+            .flatten()
+            .map(|line| LineInfo::new(line.key.clone(), line.value.clone()))
+            .collect()
+    }
+}
+
+/// The output of `command` on a single line, cut off at `max_length` characters.
+///
+/// Only as much of the output as can make it into the line is read, a command that keeps
+/// writing after that is killed.
+async fn run_command(command: &str, line: &CustomLineConfig) -> io::Result<String> {
+    // every character can take up to 4 bytes, plus some leeway for whitespace to collapse
+    let limit = (line.max_length as u64 + 1) * 4;
+    let mut child = Command::new("sh")
+        .args(["-c", command])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()?;
+    let stdout = child.stdout.take().expect("stdout should be piped");
+
+    let run = async move {
+        let mut output = vec![];
+        stdout.take(limit + 1).read_to_end(&mut output).await?;
+        if output.len() as u64 > limit {
+            output.truncate(limit as usize);
+            child.start_kill()?;
+            child.wait().await?;
+            return Ok((output, true));
+        }
+
+        let status = child.wait().await?;
+        if !status.success() {
+            return Err(io::Error::other(format!("exited with {status}")));
+        }
+        Ok((output, false))
+    };
+    let (output, cut_off) = tokio::time::timeout(Duration::from_secs(line.timeout), run)
+        .await
+        .map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("did not finish within {}s", line.timeout),
+            )
+        })??;
+
+    let stdout = String::from_utf8_lossy(&output);
+    let value = stdout.split_whitespace().collect::<Vec<_>>().join(" ");
+    if cut_off || value.chars().count() > line.max_length {
+        let mut truncated = value
+            .chars()
+            .take(line.max_length.saturating_sub(1))
+            .collect::<String>();
+        truncated.push('…');
+        return Ok(truncated);
+    }
+
+    Ok(value)
+}
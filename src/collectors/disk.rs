@@ -0,0 +1,100 @@
+use std::{collections::HashSet, sync::Mutex};
+
+use async_trait::async_trait;
+use sysinfo::Disks;
+
+use super::Collector;
+use crate::{
+    config,
+    sysgetter::{DiskInfoRaw, LineInfo, SystemInfoRaw, format_bytes, usage_percent},
+};
+
+/// Usage of every mounted disk that is not filtered out by the config.
+pub struct DiskCollector {
+    disks: Mutex<Disks>,
+}
+
+impl Default for DiskCollector {
+    fn default() -> Self {
+        DiskCollector {
+            disks: Mutex::new(Disks::new_with_refreshed_list()),
+        }
+    }
+}
+
+#[async_trait]
+impl Collector for DiskCollector {
+    fn name(&self) -> &'static str {
+        "disk"
+    }
+
+    async fn collect(&self, raw: &mut SystemInfoRaw) {
+        let disks_config = &config::get().disks;
+        let mut disks = self.disks.lock().unwrap_or_else(|e| e.into_inner());
+        // also picks up new mounts and drops the unmounted ones
+        disks.refresh(true);
+        let mut mounted: HashSet<String> = HashSet::new();
+        let mut disk_infos: Vec<DiskInfoRaw> = vec![];
+        for disk in disks.list() {
+            let total_space = disk.total_space();
+            let available_space = disk.available_space();
+            let used_space = total_space - available_space;
+
+            let disk_name = disk.name().to_string_lossy().to_string();
+            if mounted.contains(&disk_name) {
+                continue; // Skip already processed disks
+            }
+            mounted.insert(disk_name.clone());
+
+            let file_system = disk.file_system().to_string_lossy();
+            if file_system.is_empty() {
+                continue; // Skip disks without a file system
+            }
+            let mount_point = disk.mount_point().to_string_lossy().to_string();
+            if disks_config.is_ignored(&file_system, &mount_point) {
+                continue; // Skip temporary, special, or user-ignored filesystems
+            }
+
+            disk_infos.push(DiskInfoRaw {
+                name: disk_name,
+                mount_point,
+                file_system: file_system.to_string(),
+                total_bytes: total_space,
+                used_bytes: used_space,
+                available_bytes: available_space,
+                usage_percent: usage_percent(used_space, total_space),
+            });
+        }
+
+        raw.disks = Some(disk_infos);
+    }
+
+    fn lines(&self, raw: &SystemInfoRaw) -> Vec<LineInfo> {
+        let Some(disks) = &raw.disks else {
+            return vec![];
+        };
+
+        let disk_total = disks.len();
+        disks
+            .iter()
+            .map(|disk| {
+                let disk_key = if disk_total > 1 {
+                    format!("Disk ({})", disk.mount_point)
+                } else {
+                    "Disk".to_string()
+                };
+
+                LineInfo::new(
+                    disk_key,
+                    format!(
+                        "{} / {} ({:.1}%) - {}",
+                        format_bytes(disk.used_bytes),
+                        format_bytes(disk.total_bytes),
+                        disk.usage_percent,
+                        disk.file_system
+                    ),
+                )
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,137 @@
+use std::{collections::HashMap, path::Path, sync::Mutex, time::Instant};
+
+use async_trait::async_trait;
+
+use super::Collector;
+use crate::sysgetter::{DiskIoDeviceRaw, DiskIoInfoRaw, LineInfo, SystemInfoRaw, format_bytes};
+
+const DISKSTATS_PATH: &str = "/proc/diskstats";
+/// `/proc/diskstats` always counts in 512 byte sectors, regardless of the device.
+const SECTOR_SIZE: u64 = 512;
+
+/// Read and write throughput of every physical disk between refreshes.
+#[derive(Default)]
+pub struct DiskIoCollector {
+    previous: Mutex<Option<IoSample>>,
+}
+
+struct IoSample {
+    taken_at: Instant,
+    counters: HashMap<String, (u64, u64)>,
+}
+
+#[async_trait]
+impl Collector for DiskIoCollector {
+    fn name(&self) -> &'static str {
+        "diskio"
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        false
+    }
+
+    async fn collect(&self, raw: &mut SystemInfoRaw) {
+        let Ok(content) = std::fs::read_to_string(DISKSTATS_PATH) else {
+            return;
+        };
+
+        let now = Instant::now();
+        let counters = parse_diskstats(&content);
+        let mut previous = self.previous.lock().unwrap_or_else(|e| e.into_inner());
+
+        let mut devices: Vec<DiskIoDeviceRaw> = counters
+            .iter()
+            .map(|(name, (read_bytes, written_bytes))| {
+                let rates = previous.as_ref().and_then(|sample| {
+                    let (prev_read, prev_written) = sample.counters.get(name)?;
+                    let elapsed = now.duration_since(sample.taken_at).as_secs_f64();
+                    if elapsed <= 0.0 {
+                        return None;
+                    }
+
+                    let read_rate = read_bytes.checked_sub(*prev_read)? as f64 / elapsed;
+                    let write_rate = written_bytes.checked_sub(*prev_written)? as f64 / elapsed;
+                    Some((read_rate as u64, write_rate as u64))
+                });
+
+                DiskIoDeviceRaw {
+                    name: name.clone(),
+                    read_bytes: *read_bytes,
+                    written_bytes: *written_bytes,
+                    read_bytes_per_sec: rates.map(|(read, _)| read),
+                    write_bytes_per_sec: rates.map(|(_, write)| write),
+                }
+            })
+            .collect();
+        devices.sort_by(|a, b| a.name.cmp(&b.name));
+
+        *previous = Some(IoSample {
+            taken_at: now,
+            counters,
+        });
+
+        if devices.is_empty() {
+            return;
+        }
+
+        let read_bytes_per_sec = devices.iter().map(|d| d.read_bytes_per_sec).sum();
+        let write_bytes_per_sec = devices.iter().map(|d| d.write_bytes_per_sec).sum();
+        raw.disk_io = Some(DiskIoInfoRaw {
+            read_bytes_per_sec,
+            write_bytes_per_sec,
+            devices,
+        });
+    }
+
+    fn lines(&self, raw: &SystemInfoRaw) -> Vec<LineInfo> {
+        let Some(disk_io) = &raw.disk_io else {
+            return vec![];
+        };
+
+        // the first collection has nothing to compare against yet
+        let (Some(read), Some(write)) = (disk_io.read_bytes_per_sec, disk_io.write_bytes_per_sec)
+        else {
+            return vec![];
+        };
+
+        vec![LineInfo::new(
+            "Disk I/O",
+            format!("R {}/s, W {}/s", format_bytes(read), format_bytes(write)),
+        )]
+    }
+}
+
+/// Parse the read and written byte counters of every whole, physical disk.
+fn parse_diskstats(content: &str) -> HashMap<String, (u64, u64)> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            // major minor name reads merged sectors_read ms writes merged sectors_written ...
+            if fields.len() < 10 {
+                return None;
+            }
+
+            let name = fields[2];
+            if !is_physical_disk(name) {
+                return None;
+            }
+
+            let sectors_read: u64 = fields[5].parse().ok()?;
+            let sectors_written: u64 = fields[9].parse().ok()?;
+            Some((
+                name.to_string(),
+                (sectors_read * SECTOR_SIZE, sectors_written * SECTOR_SIZE),
+            ))
+        })
+        .collect()
+}
+
+/// Whole disks have an entry in `/sys/block`, partitions do not.
+fn is_physical_disk(name: &str) -> bool {
+    if name.starts_with("loop") || name.starts_with("ram") {
+        return false;
+    }
+
+    Path::new("/sys/block").join(name).exists()
+}
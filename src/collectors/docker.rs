@@ -0,0 +1,156 @@
+use std::{io, path::Path, time::Duration};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use super::Collector;
+use crate::{
+    config,
+    sysgetter::{ContainersInfoRaw, LineInfo, SystemInfoRaw},
+};
+
+const DOCKER_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Container counts from the local Docker daemon.
+pub struct DockerCollector;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ContainerInfoRaw {
+    pub id: String,
+    pub name: String,
+    pub image: String,
+    /// created, running, paused, restarting, exited, etc.
+    pub state: String,
+    /// Human readable status, e.g. "Up 3 hours".
+    pub status: String,
+}
+
+/// The subset of `GET /containers/json` we care about.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct DockerContainer {
+    id: String,
+    #[serde(default)]
+    names: Vec<String>,
+    #[serde(default)]
+    image: String,
+    #[serde(default)]
+    state: String,
+    #[serde(default)]
+    status: String,
+}
+
+#[async_trait]
+impl Collector for DockerCollector {
+    fn name(&self) -> &'static str {
+        "docker"
+    }
+
+    async fn collect(&self, raw: &mut SystemInfoRaw) {
+        // no socket means no docker, stay quiet about it
+        if !Path::new(&config::get().docker.socket).exists() {
+            return;
+        }
+
+        match list_containers().await {
+            Ok(containers) => {
+                let running = containers
+                    .iter()
+                    .filter(|container| container.state == "running")
+                    .count();
+
+                raw.containers = Some(ContainersInfoRaw {
+                    running,
+                    total: containers.len(),
+                });
+            }
+            Err(err) => tracing::warn!("Failed to query docker: {err}"),
+        }
+    }
+
+    fn lines(&self, raw: &SystemInfoRaw) -> Vec<LineInfo> {
+        let Some(containers) = &raw.containers else {
+            return vec![];
+        };
+
+        vec![LineInfo::new(
+            "Containers",
+            format!(
+                "{} running ({} total)",
+                containers.running, containers.total
+            ),
+        )]
+    }
+}
+
+/// List every container, including stopped ones, from the configured Docker socket.
+pub async fn list_containers() -> io::Result<Vec<ContainerInfoRaw>> {
+    let body = docker_get(&config::get().docker.socket, "/containers/json?all=true").await?;
+    let containers: Vec<DockerContainer> = serde_json::from_slice(&body)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+    Ok(containers
+        .into_iter()
+        .map(|container| ContainerInfoRaw {
+            id: container.id.chars().take(12).collect(),
+            name: container
+                .names
+                .first()
+                .map(|name| name.trim_start_matches('/').to_string())
+                .unwrap_or_default(),
+            image: container.image,
+            state: container.state,
+            status: container.status,
+        })
+        .collect())
+}
+
+/// Minimal HTTP/1.0 GET over the Docker unix socket, returning the response body.
+///
+/// HTTP/1.0 keeps the daemon from using chunked encoding, so the body is simply
+/// everything after the headers until the connection closes.
+#[cfg(unix)]
+async fn docker_get(socket: &str, path: &str) -> io::Result<Vec<u8>> {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let request = async {
+        let mut stream = tokio::net::UnixStream::connect(socket).await?;
+        stream
+            .write_all(format!("GET {path} HTTP/1.0\r\nHost: docker\r\n\r\n").as_bytes())
+            .await?;
+
+        let mut response = vec![];
+        stream.read_to_end(&mut response).await?;
+        Ok::<_, io::Error>(response)
+    };
+
+    let response = tokio::time::timeout(DOCKER_TIMEOUT, request)
+        .await
+        .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "docker did not respond"))??;
+
+    let header_end = response
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "malformed HTTP response"))?;
+    let status_line = response[..header_end]
+        .split(|byte| *byte == b'\n')
+        .next()
+        .map(String::from_utf8_lossy)
+        .unwrap_or_default();
+    if !status_line.contains(" 200 ") {
+        return Err(io::Error::other(format!(
+            "unexpected docker response: {}",
+            status_line.trim()
+        )));
+    }
+
+    Ok(response[header_end + 4..].to_vec())
+}
+
+#[cfg(not(unix))]
+async fn docker_get(_socket: &str, _path: &str) -> io::Result<Vec<u8>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "docker sockets are only supported on unix",
+    ))
+}
@@ -0,0 +1,52 @@
+use async_trait::async_trait;
+
+use super::Collector;
+use crate::sysgetter::{EntropyInfoRaw, LineInfo, LineStatus, SystemInfoRaw};
+
+const ENTROPY_AVAIL_PATH: &str = "/proc/sys/kernel/random/entropy_avail";
+const POOL_SIZE_PATH: &str = "/proc/sys/kernel/random/poolsize";
+/// Below this `getrandom` may block on older kernels, 5.18 and later always report 256.
+const LOW_ENTROPY_BITS: u32 = 200;
+
+/// Entropy available to the kernel's random number generator, Linux only.
+pub struct EntropyCollector;
+
+#[async_trait]
+impl Collector for EntropyCollector {
+    fn name(&self) -> &'static str {
+        "entropy"
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        false
+    }
+
+    async fn collect(&self, raw: &mut SystemInfoRaw) {
+        let read = |path: &str| {
+            std::fs::read_to_string(path)
+                .ok()
+                .and_then(|value| value.trim().parse::<u32>().ok())
+        };
+        let Some(available_bits) = read(ENTROPY_AVAIL_PATH) else {
+            return;
+        };
+
+        raw.entropy = Some(EntropyInfoRaw {
+            available_bits,
+            pool_size_bits: read(POOL_SIZE_PATH),
+        });
+    }
+
+    fn lines(&self, raw: &SystemInfoRaw) -> Vec<LineInfo> {
+        let Some(entropy) = &raw.entropy else {
+            return vec![];
+        };
+
+        let value = match entropy.pool_size_bits {
+            Some(pool_size) => format!("{} / {pool_size} bits", entropy.available_bits),
+            None => format!("{} bits", entropy.available_bits),
+        };
+        let status = (entropy.available_bits < LOW_ENTROPY_BITS).then_some(LineStatus::Warning);
+        vec![LineInfo::new("Entropy", value).with_status(status)]
+    }
+}
@@ -0,0 +1,80 @@
+use std::{
+    sync::{LazyLock, Mutex},
+    time::Instant,
+};
+
+use async_trait::async_trait;
+use sysinfo::{MINIMUM_CPU_UPDATE_INTERVAL, ProcessRefreshKind, ProcessesToUpdate, System};
+
+use super::Collector;
+use crate::sysgetter::{LineInfo, SelfInfoRaw, SystemInfoRaw, format_bytes, format_uptime};
+
+/// Shared by the collector and `/api/v1/self`, CPU usage is computed against the previous refresh.
+static SELF_SYSTEM: LazyLock<Mutex<(System, Option<Instant>)>> =
+    LazyLock::new(|| Mutex::new((System::new(), None)));
+
+/// How long helios itself has been running and the resources it uses.
+pub struct HeliosCollector;
+
+#[async_trait]
+impl Collector for HeliosCollector {
+    fn name(&self) -> &'static str {
+        "helios"
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        false
+    }
+
+    async fn collect(&self, raw: &mut SystemInfoRaw) {
+        raw.helios = tokio::task::spawn_blocking(self_usage).await.ok().flatten();
+    }
+
+    fn lines(&self, raw: &SystemInfoRaw) -> Vec<LineInfo> {
+        let Some(helios) = &raw.helios else {
+            return vec![];
+        };
+
+        vec![LineInfo::new(
+            "Helios",
+            format!(
+                "v{}, up {} — {}, {:.1}% CPU",
+                helios.version,
+                format_uptime(helios.uptime_seconds),
+                format_bytes(helios.rss_bytes),
+                helios.cpu_usage_percent
+            ),
+        )]
+    }
+}
+
+/// Uptime, memory, and CPU usage of the helios process.
+///
+/// The first call blocks for [`MINIMUM_CPU_UPDATE_INTERVAL`] since CPU usage needs two samples.
+pub fn self_usage() -> Option<SelfInfoRaw> {
+    let pid = sysinfo::get_current_pid().ok()?;
+    let refresh_kind = ProcessRefreshKind::nothing().with_cpu().with_memory();
+
+    let mut guard = SELF_SYSTEM.lock().unwrap_or_else(|e| e.into_inner());
+    let (sys, refreshed_at) = &mut *guard;
+    let wait = match refreshed_at {
+        Some(refreshed_at) => MINIMUM_CPU_UPDATE_INTERVAL.saturating_sub(refreshed_at.elapsed()),
+        None => {
+            sys.refresh_processes_specifics(ProcessesToUpdate::Some(&[pid]), true, refresh_kind);
+            MINIMUM_CPU_UPDATE_INTERVAL
+        }
+    };
+    if !wait.is_zero() {
+        std::thread::sleep(wait);
+    }
+    sys.refresh_processes_specifics(ProcessesToUpdate::Some(&[pid]), true, refresh_kind);
+    *refreshed_at = Some(Instant::now());
+
+    let process = sys.process(pid)?;
+    Some(SelfInfoRaw {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        uptime_seconds: process.run_time(),
+        rss_bytes: process.memory(),
+        cpu_usage_percent: process.cpu_usage(),
+    })
+}
@@ -0,0 +1,179 @@
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use sysinfo::{MemoryRefreshKind, RefreshKind, System};
+
+use super::{Collector, cgroup};
+use crate::{
+    config::{self, MemoryMode},
+    sysgetter::{
+        ContainerLimitsRaw, LineInfo, SwapDeviceRaw, SystemInfoRaw, UsageInfoRaw, format_bytes,
+        usage_percent,
+    },
+};
+
+const PROC_SWAPS_PATH: &str = "/proc/swaps";
+
+/// Memory and swap usage, every swap device on Linux, and the memory limit inside a container.
+pub struct MemoryCollector {
+    sys: Mutex<System>,
+}
+
+impl Default for MemoryCollector {
+    fn default() -> Self {
+        MemoryCollector {
+            sys: Mutex::new(System::new_with_specifics(
+                RefreshKind::nothing().with_memory(MemoryRefreshKind::everything()),
+            )),
+        }
+    }
+}
+
+#[async_trait]
+impl Collector for MemoryCollector {
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    async fn collect(&self, raw: &mut SystemInfoRaw) {
+        let mut sys = self.sys.lock().unwrap_or_else(|e| e.into_inner());
+        sys.refresh_memory();
+
+        let memory_config = &config::get().memory;
+        let total = sys.total_memory();
+        // the host's memory tells nothing about what a limited container can use
+        let limit = cgroup::memory_limit()
+            .filter(|(limit, _)| memory_config.container_limit && *limit < total);
+
+        raw.memory = Some(match limit {
+            Some((limit, used)) => {
+                raw.container_limits
+                    .get_or_insert_with(ContainerLimitsRaw::default)
+                    .memory_bytes = Some(limit);
+                UsageInfoRaw::new(limit, used)
+            }
+            None => {
+                let used = match memory_config.mode {
+                    MemoryMode::Used => sys.used_memory(),
+                    MemoryMode::NoCache => {
+                        used_without_cache().unwrap_or_else(|| sys.used_memory())
+                    }
+                    MemoryMode::Available => total.saturating_sub(sys.available_memory()),
+                };
+                UsageInfoRaw::new(total, used)
+            }
+        });
+        raw.swap = Some(UsageInfoRaw::new(sys.total_swap(), sys.used_swap()));
+        raw.swap_devices = std::fs::read_to_string(PROC_SWAPS_PATH)
+            .ok()
+            .map(|swaps| parse_swaps(&swaps));
+    }
+
+    fn lines(&self, raw: &SystemInfoRaw) -> Vec<LineInfo> {
+        let mut lines = vec![];
+
+        if let Some(memory) = &raw.memory {
+            let mut value = match config::get().memory.mode {
+                MemoryMode::Available => format!(
+                    "{} available / {}",
+                    format_bytes(memory.total_bytes.saturating_sub(memory.used_bytes)),
+                    format_bytes(memory.total_bytes)
+                ),
+                _ => memory.as_display(),
+            };
+            let limited = raw
+                .container_limits
+                .as_ref()
+                .is_some_and(|limits| limits.memory_bytes.is_some());
+            if limited {
+                value.push_str(" (container)");
+            }
+            lines.push(LineInfo::new("Memory", value));
+        }
+
+        let swap_devices = raw
+            .swap_devices
+            .as_ref()
+            .filter(|devices| config::get().memory.swap_devices && devices.len() > 1);
+        match (&raw.swap, swap_devices) {
+            (Some(_), Some(devices)) => {
+                for device in devices {
+                    lines.push(LineInfo::new(
+                        format!("Swap ({})", device.device),
+                        format!(
+                            "{} / {} ({:.1}%) - {}",
+                            format_bytes(device.used_bytes),
+                            format_bytes(device.size_bytes),
+                            device.usage_percent,
+                            device.kind
+                        ),
+                    ));
+                }
+            }
+            (Some(swap), None) if swap.total_bytes > 0 => {
+                lines.push(LineInfo::new("Swap", swap.as_display()));
+            }
+            (Some(_), None) => lines.push(LineInfo::new("Swap", "Disabled")),
+            (None, _) => {}
+        }
+
+        lines
+    }
+}
+
+/// Every swap device listed in `/proc/swaps`, sizes there are in KiB.
+fn parse_swaps(swaps: &str) -> Vec<SwapDeviceRaw> {
+    swaps
+        .lines()
+        // header: Filename Type Size Used Priority
+        .skip(1)
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            // spaces in the path are escaped as `\040`
+            let device = fields.next()?.replace("\\040", " ");
+            let kind = fields.next()?.to_string();
+            let size_bytes = fields.next()?.parse::<u64>().ok()? * 1024;
+            let used_bytes = fields.next()?.parse::<u64>().ok()? * 1024;
+            let priority = fields.next()?.parse::<i32>().ok()?;
+
+            Some(SwapDeviceRaw {
+                device,
+                kind,
+                size_bytes,
+                used_bytes,
+                usage_percent: usage_percent(used_bytes, size_bytes),
+                priority,
+            })
+        })
+        .collect()
+}
+
+/// Used memory the way htop counts it, leaving out buffers, page cache, and reclaimable slab.
+///
+/// Only available on Linux, where `/proc/meminfo` exists.
+fn used_without_cache() -> Option<u64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    let field = |name: &str| {
+        meminfo
+            .lines()
+            .find_map(|line| line.strip_prefix(name)?.strip_prefix(':'))
+            .and_then(|value| {
+                value
+                    .trim()
+                    .trim_end_matches("kB")
+                    .trim()
+                    .parse::<u64>()
+                    .ok()
+            })
+            .map(|kib| kib * 1024)
+    };
+
+    let total = field("MemTotal")?;
+    let free = field("MemFree")?;
+    let buffers = field("Buffers").unwrap_or(0);
+    // shared memory is counted as cache but can't be dropped
+    let cache = (field("Cached").unwrap_or(0) + field("SReclaimable").unwrap_or(0))
+        .saturating_sub(field("Shmem").unwrap_or(0));
+
+    Some(total.saturating_sub(free + buffers + cache))
+}
@@ -0,0 +1,178 @@
+use std::sync::LazyLock;
+
+use async_trait::async_trait;
+use tracing::Instrument;
+
+use crate::{
+    config, i18n,
+    sysgetter::{HOSTNAME, LineInfo, SystemInfo, SystemInfoRaw},
+};
+
+mod battery;
+mod cgroup;
+mod cpu;
+mod custom;
+mod disk;
+mod diskio;
+mod docker;
+mod entropy;
+mod helios;
+mod memory;
+mod monitors;
+mod network;
+mod os;
+mod packages;
+mod process_count;
+mod public_ip;
+mod raid;
+mod session;
+mod smart;
+mod systemd;
+mod temperature;
+mod throttling;
+mod time;
+mod users;
+
+pub use battery::BatteryCollector;
+pub use cpu::{CpuCollector, sample_cpu_usage};
+pub use custom::CustomCollector;
+pub use disk::DiskCollector;
+pub use diskio::DiskIoCollector;
+pub use docker::{DockerCollector, list_containers};
+pub use entropy::EntropyCollector;
+pub use helios::{HeliosCollector, self_usage};
+pub use memory::MemoryCollector;
+pub use monitors::MonitorsCollector;
+pub use network::{NetworkCollector, list_interfaces};
+pub use os::OsCollector;
+pub use packages::PackagesCollector;
+pub use process_count::ProcessCountCollector;
+pub use public_ip::PublicIpCollector;
+pub use raid::RaidCollector;
+pub use session::SessionCollector;
+pub use smart::SmartCollector;
+pub use systemd::{SystemdCollector, failed_units};
+pub use temperature::TemperatureCollector;
+pub use throttling::ThrottlingCollector;
+pub use time::TimeCollector;
+pub use users::UsersCollector;
+
+static REGISTRY: LazyLock<CollectorRegistry> = LazyLock::new(CollectorRegistry::from_config);
+
+/// A source of system information, e.g. memory usage or mounted disks.
+#[async_trait]
+pub trait Collector: Send + Sync {
+    /// Identifier used to enable or disable the collector in the config.
+    fn name(&self) -> &'static str;
+
+    /// Opt-in collectors return `false` and only run when listed in `collectors.enabled`.
+    fn enabled_by_default(&self) -> bool {
+        true
+    }
+
+    /// Gather fresh data and store it in this collector's section of `raw`.
+    async fn collect(&self, raw: &mut SystemInfoRaw);
+
+    /// Render this collector's section of `raw` into display lines.
+    fn lines(&self, raw: &SystemInfoRaw) -> Vec<LineInfo>;
+}
+
+/// Ordered set of collectors, the order decides the order of the displayed lines.
+#[derive(Default)]
+pub struct CollectorRegistry {
+    collectors: Vec<Box<dyn Collector>>,
+}
+
+impl CollectorRegistry {
+    pub fn new() -> Self {
+        CollectorRegistry::default()
+    }
+
+    /// Registry with every built-in collector that is not disabled in the config.
+    pub fn from_config() -> Self {
+        let collectors_config = &config::get().collectors;
+        let mut registry = CollectorRegistry::new();
+
+        registry.register(OsCollector);
+        registry.register(PackagesCollector::default());
+        registry.register(SessionCollector);
+        registry.register(CpuCollector::default());
+        registry.register(MemoryCollector::default());
+        registry.register(DiskCollector::default());
+        registry.register(DiskIoCollector::default());
+        registry.register(SmartCollector::default());
+        registry.register(RaidCollector);
+        registry.register(NetworkCollector::default());
+        registry.register(PublicIpCollector::default());
+        registry.register(TemperatureCollector::default());
+        registry.register(BatteryCollector);
+        registry.register(DockerCollector);
+        registry.register(SystemdCollector);
+        registry.register(UsersCollector);
+        registry.register(TimeCollector);
+        registry.register(EntropyCollector);
+        registry.register(ThrottlingCollector);
+        registry.register(ProcessCountCollector::default());
+        registry.register(HeliosCollector);
+        registry.register(MonitorsCollector::default());
+        registry.register(CustomCollector);
+
+        registry.collectors.retain(|collector| {
+            collectors_config.is_enabled(collector.name(), collector.enabled_by_default())
+        });
+
+        registry
+    }
+
+    pub fn register(&mut self, collector: impl Collector + 'static) {
+        self.collectors.push(Box::new(collector));
+    }
+
+    /// Run every collector in order and merge their results into one snapshot.
+    pub async fn collect(&self) -> SystemInfoRaw {
+        let mut raw = SystemInfoRaw {
+            host: HOSTNAME.clone(),
+            ..Default::default()
+        };
+
+        // spans for the OTLP trace export, to tell which collector is slow
+        async {
+            for collector in &self.collectors {
+                collector
+                    .collect(&mut raw)
+                    .instrument(tracing::debug_span!(
+                        "collector",
+                        otel.name = collector.name()
+                    ))
+                    .await;
+            }
+        }
+        .instrument(tracing::debug_span!("collect"))
+        .await;
+
+        raw
+    }
+
+    /// Render a snapshot into the display lines, honoring the `lines` config.
+    ///
+    /// The labels are in the locale of the current [`i18n::with_locale`], matched against the
+    /// `lines` config in English before they are translated.
+    pub fn render(&self, raw: &SystemInfoRaw) -> SystemInfo {
+        let lines_config = &config::get().lines;
+        let locale = i18n::current();
+        let lines = self
+            .collectors
+            .iter()
+            .flat_map(|collector| collector.lines(raw))
+            .filter(|line| lines_config.is_shown(line.key()))
+            .map(|line| line.localized(locale))
+            .collect();
+
+        SystemInfo::new(raw.host.clone(), lines)
+    }
+}
+
+/// The registry built from the active configuration.
+pub fn registry() -> &'static CollectorRegistry {
+    &REGISTRY
+}
@@ -0,0 +1,269 @@
+use std::{
+    io,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use tokio::{net::TcpStream, process::Command};
+
+use super::Collector;
+use crate::{
+    config::{self, HostCheck, HostMonitorConfig, HttpMonitorConfig},
+    sysgetter::{HostMonitorRaw, HttpMonitorRaw, LineInfo, LineStatus, SystemInfoRaw},
+};
+
+/// Reachability and latency of the `[[monitors.hosts]]` and `[[monitors.http]]`, checked every
+/// `monitors.interval`.
+#[derive(Default)]
+pub struct MonitorsCollector {
+    checked: Mutex<Option<Check>>,
+    client: reqwest::Client,
+}
+
+/// The results of the last check, shown until the next one.
+struct Check {
+    at: Instant,
+    /// A reloaded config with other hosts or URLs is checked right away.
+    hosts: Vec<HostMonitorConfig>,
+    http: Vec<HttpMonitorConfig>,
+    monitors: Vec<HostMonitorRaw>,
+    http_monitors: Vec<HttpMonitorRaw>,
+}
+
+#[async_trait]
+impl Collector for MonitorsCollector {
+    fn name(&self) -> &'static str {
+        "monitors"
+    }
+
+    async fn collect(&self, raw: &mut SystemInfoRaw) {
+        let monitors_config = &config::get().monitors;
+        if monitors_config.hosts.is_empty() && monitors_config.http.is_empty() {
+            return;
+        }
+
+        let interval = Duration::from_secs(monitors_config.interval);
+        let cached = {
+            let checked = self.checked.lock().unwrap_or_else(|e| e.into_inner());
+            checked
+                .as_ref()
+                .filter(|check| {
+                    check.at.elapsed() < interval
+                        && check.hosts == monitors_config.hosts
+                        && check.http == monitors_config.http
+                })
+                .map(|check| (check.monitors.clone(), check.http_monitors.clone()))
+        };
+
+        let (monitors, http_monitors) = match cached {
+            Some(checked) => checked,
+            None => {
+                let timeout = Duration::from_secs(monitors_config.timeout.max(1));
+                // all at once, so a few unreachable hosts don't add up their timeouts
+                let host_checks = monitors_config
+                    .hosts
+                    .iter()
+                    .map(|host| tokio::spawn(check_host(host.clone(), timeout)))
+                    .collect::<Vec<_>>();
+                let http_checks = monitors_config
+                    .http
+                    .iter()
+                    .map(|http| {
+                        tokio::spawn(check_http(self.client.clone(), http.clone(), timeout))
+                    })
+                    .collect::<Vec<_>>();
+                let mut monitors = vec![];
+                for check in host_checks {
+                    match check.await {
+                        Ok(monitor) => monitors.push(monitor),
+                        Err(err) => tracing::warn!("Host check failed: {err}"),
+                    }
+                }
+                let mut http_monitors = vec![];
+                for check in http_checks {
+                    match check.await {
+                        Ok(monitor) => http_monitors.push(monitor),
+                        Err(err) => tracing::warn!("HTTP check failed: {err}"),
+                    }
+                }
+
+                *self.checked.lock().unwrap_or_else(|e| e.into_inner()) = Some(Check {
+                    at: Instant::now(),
+                    hosts: monitors_config.hosts.clone(),
+                    http: monitors_config.http.clone(),
+                    monitors: monitors.clone(),
+                    http_monitors: http_monitors.clone(),
+                });
+                (monitors, http_monitors)
+            }
+        };
+
+        if !monitors.is_empty() {
+            raw.monitors = Some(monitors);
+        }
+        if !http_monitors.is_empty() {
+            raw.http_monitors = Some(http_monitors);
+        }
+    }
+
+    /// Only the hosts, the URLs have their own section on the page.
+    fn lines(&self, raw: &SystemInfoRaw) -> Vec<LineInfo> {
+        raw.monitors
+            .iter()
+            .flatten()
+            .map(|monitor| {
+                let key = format!("Monitor ({})", monitor.name);
+                match monitor.latency_ms {
+                    // hosts on the same network answer well within a millisecond
+                    Some(latency_ms) if latency_ms < 1.0 => {
+                        LineInfo::new(key, format!("Up - {latency_ms:.2} ms"))
+                    }
+                    Some(latency_ms) => LineInfo::new(key, format!("Up - {latency_ms:.1} ms")),
+                    None => LineInfo::new(
+                        key,
+                        format!("Down - {}", monitor.error.as_deref().unwrap_or("no reply")),
+                    )
+                    .with_status(Some(LineStatus::Critical)),
+                }
+            })
+            .collect()
+    }
+}
+
+async fn check_host(host: HostMonitorConfig, timeout: Duration) -> HostMonitorRaw {
+    let result = match host.check {
+        HostCheck::Icmp => ping(&host.host, timeout).await,
+        HostCheck::Tcp => match host.port {
+            Some(port) => connect(&host.host, port, timeout).await,
+            None => Err("no `port` to connect to".to_string()),
+        },
+    };
+
+    HostMonitorRaw {
+        name: host.name.clone().unwrap_or_else(|| host.host.clone()),
+        host: host.host.clone(),
+        check: host.check,
+        port: host.port,
+        up: result.is_ok(),
+        latency_ms: result.as_ref().ok().copied(),
+        error: result.err(),
+        checked_at: chrono::Utc::now().timestamp(),
+    }
+}
+
+async fn check_http(
+    client: reqwest::Client,
+    http: HttpMonitorConfig,
+    timeout: Duration,
+) -> HttpMonitorRaw {
+    let started = Instant::now();
+    let (status, result) = match client.get(&http.url).timeout(timeout).send().await {
+        Ok(response) => {
+            let status = response.status();
+            let result = match http.status {
+                Some(expected) if status.as_u16() != expected => {
+                    Err(format!("status {status}, expected {expected}"))
+                }
+                None if !status.is_success() => Err(format!("status {status}")),
+                _ => match &http.keyword {
+                    Some(keyword) => match response.text().await {
+                        Ok(body) if body.contains(keyword.as_str()) => Ok(()),
+                        Ok(_) => Err(format!("`{keyword}` not found")),
+                        Err(err) => Err(err.to_string()),
+                    },
+                    None => Ok(()),
+                },
+            };
+            (Some(status.as_u16()), result)
+        }
+        Err(err) if err.is_timeout() => (None, Err("timed out".to_string())),
+        Err(err) => (None, Err(error_chain(&err))),
+    };
+
+    HttpMonitorRaw {
+        name: http.name.clone().unwrap_or_else(|| http.url.clone()),
+        url: http.url.clone(),
+        up: result.is_ok(),
+        status,
+        latency_ms: result
+            .is_ok()
+            .then(|| started.elapsed().as_secs_f64() * 1000.0),
+        error: result.err(),
+        checked_at: chrono::Utc::now().timestamp(),
+    }
+}
+
+/// The innermost cause of `err`, e.g. the refused connection behind `error sending request`.
+fn error_chain(err: &reqwest::Error) -> String {
+    let mut cause: &dyn std::error::Error = err;
+    while let Some(source) = cause.source() {
+        cause = source;
+    }
+
+    cause.to_string()
+}
+
+/// Round trip in milliseconds of one echo request sent with the system's `ping`, which can send
+/// them without the privileges a raw socket needs.
+async fn ping(host: &str, timeout: Duration) -> Result<f64, String> {
+    let seconds = timeout.as_secs().to_string();
+    // macOS takes the wait for a reply in milliseconds, `-t` is the overall timeout there
+    let timeout_flag = if cfg!(target_os = "macos") {
+        "-t"
+    } else {
+        "-W"
+    };
+    let output = Command::new("ping")
+        .args(["-n", "-c", "1", timeout_flag, &seconds, host])
+        .kill_on_drop(true)
+        .output();
+    let output = tokio::time::timeout(timeout + Duration::from_secs(1), output)
+        .await
+        .map_err(|_| "timed out".to_string())?
+        .map_err(|err| match err.kind() {
+            io::ErrorKind::NotFound => "`ping` is not installed".to_string(),
+            _ => format!("failed to run `ping`: {err}"),
+        })?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if !output.status.success() {
+        return Err(
+            if stdout.contains("0 received") || stdout.contains("0 packets received") {
+                "no reply".to_string()
+            } else {
+                // unknown hosts and the like are only explained on stderr
+                String::from_utf8_lossy(&output.stderr)
+                    .lines()
+                    .next()
+                    .map(|line| line.trim_start_matches("ping: ").to_string())
+                    .filter(|line| !line.is_empty())
+                    .unwrap_or_else(|| "no reply".to_string())
+            },
+        );
+    }
+
+    // `64 bytes from 1.1.1.1: icmp_seq=1 ttl=57 time=12.3 ms`
+    stdout
+        .split_whitespace()
+        .find_map(|word| word.strip_prefix("time=")?.parse::<f64>().ok())
+        .ok_or_else(|| "no round trip time in the output of `ping`".to_string())
+}
+
+/// Milliseconds the TCP handshake took, without resolving the name.
+async fn connect(host: &str, port: u16, timeout: Duration) -> Result<f64, String> {
+    let addr = tokio::time::timeout(timeout, tokio::net::lookup_host((host, port)))
+        .await
+        .map_err(|_| "timed out resolving the host".to_string())?
+        .map_err(|err| format!("failed to resolve the host: {err}"))?
+        .next()
+        .ok_or_else(|| "the host has no addresses".to_string())?;
+
+    let started = Instant::now();
+    tokio::time::timeout(timeout, TcpStream::connect(addr))
+        .await
+        .map_err(|_| "timed out".to_string())?
+        .map_err(|err| err.to_string())?;
+
+    Ok(started.elapsed().as_secs_f64() * 1000.0)
+}
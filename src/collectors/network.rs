@@ -0,0 +1,269 @@
+use std::{collections::HashMap, net::IpAddr, sync::Mutex, time::Instant};
+
+use async_trait::async_trait;
+use sysinfo::Networks;
+
+use super::Collector;
+use crate::{
+    config,
+    sysgetter::{
+        InterfaceInfoRaw, InterfaceTrafficRaw, LineInfo, NetworkInfoRaw, SystemInfoRaw,
+        format_bytes, mask_ip, mask_mac,
+    },
+};
+
+/// Count of globally routable IPv4 and IPv6 addresses, plus the current traffic rates.
+///
+/// The addresses themselves are only listed when `network.show_addresses` is set.
+pub struct NetworkCollector {
+    networks: Mutex<Networks>,
+    /// Byte counters from the previous collection, used to compute rates.
+    previous: Mutex<Option<TrafficSample>>,
+}
+
+impl Default for NetworkCollector {
+    fn default() -> Self {
+        NetworkCollector {
+            networks: Mutex::new(Networks::new_with_refreshed_list()),
+            previous: Mutex::new(None),
+        }
+    }
+}
+
+struct TrafficSample {
+    taken_at: Instant,
+    counters: HashMap<String, (u64, u64)>,
+}
+
+#[async_trait]
+impl Collector for NetworkCollector {
+    fn name(&self) -> &'static str {
+        "network"
+    }
+
+    async fn collect(&self, raw: &mut SystemInfoRaw) {
+        let mut networks = self.networks.lock().unwrap_or_else(|e| e.into_inner());
+        // also picks up interfaces that came up since the last refresh
+        networks.refresh(true);
+        let mut valid_ipv4 = 0;
+        let mut valid_ipv6 = 0;
+        let mut global_addresses = vec![];
+        for network in networks.values() {
+            let ip_address = network.ip_networks();
+            for ip in ip_address {
+                match ip.addr {
+                    IpAddr::V4(addr) => {
+                        if !addr.is_broadcast()
+                            && !addr.is_documentation()
+                            && !addr.is_link_local()
+                            && !addr.is_loopback()
+                            && !addr.is_multicast()
+                            && !addr.is_unspecified()
+                            && !addr.is_private()
+                        {
+                            valid_ipv4 += 1;
+                            global_addresses.push(ip.addr);
+                        }
+                    }
+                    IpAddr::V6(addr) => {
+                        if !addr.is_loopback()
+                            && !addr.is_multicast()
+                            && !addr.is_unicast_link_local()
+                            && !addr.is_unique_local()
+                            && !addr.is_unspecified()
+                        {
+                            valid_ipv6 += 1;
+                            global_addresses.push(ip.addr);
+                        }
+                    }
+                }
+            }
+        }
+        global_addresses.sort_unstable();
+        global_addresses.dedup();
+        let network_config = &config::get().network;
+        let addresses = network_config.show_addresses.then(|| {
+            global_addresses
+                .iter()
+                .map(|addr| display_ip(*addr))
+                .collect()
+        });
+
+        let now = Instant::now();
+        let mut interfaces: Vec<InterfaceTrafficRaw> = vec![];
+        let mut counters: HashMap<String, (u64, u64)> = HashMap::new();
+        {
+            let mut previous = self.previous.lock().unwrap_or_else(|e| e.into_inner());
+            for (name, network) in networks.iter() {
+                if name == "lo" {
+                    continue; // loopback traffic is not interesting
+                }
+
+                let rx_bytes = network.total_received();
+                let tx_bytes = network.total_transmitted();
+                counters.insert(name.clone(), (rx_bytes, tx_bytes));
+
+                let rates = previous.as_ref().and_then(|sample| {
+                    let (prev_rx, prev_tx) = sample.counters.get(name)?;
+                    let elapsed = now.duration_since(sample.taken_at).as_secs_f64();
+                    if elapsed <= 0.0 {
+                        return None;
+                    }
+
+                    // counters reset when an interface goes down, don't report bogus spikes
+                    let rx_rate = rx_bytes.checked_sub(*prev_rx)? as f64 / elapsed;
+                    let tx_rate = tx_bytes.checked_sub(*prev_tx)? as f64 / elapsed;
+                    Some((rx_rate as u64, tx_rate as u64))
+                });
+
+                interfaces.push(InterfaceTrafficRaw {
+                    name: name.clone(),
+                    rx_bytes,
+                    tx_bytes,
+                    rx_bytes_per_sec: rates.map(|(rx, _)| rx),
+                    tx_bytes_per_sec: rates.map(|(_, tx)| tx),
+                });
+            }
+
+            *previous = Some(TrafficSample {
+                taken_at: now,
+                counters,
+            });
+        }
+        interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let sum_rates = |rate: fn(&InterfaceTrafficRaw) -> Option<u64>| {
+            interfaces.iter().map(rate).sum::<Option<u64>>()
+        };
+        let rx_bytes_per_sec = sum_rates(|interface| interface.rx_bytes_per_sec);
+        let tx_bytes_per_sec = sum_rates(|interface| interface.tx_bytes_per_sec);
+
+        raw.network = Some(NetworkInfoRaw {
+            ipv4_count: valid_ipv4,
+            ipv6_count: valid_ipv6,
+            addresses,
+            rx_bytes_per_sec,
+            tx_bytes_per_sec,
+            interfaces,
+        });
+    }
+
+    fn lines(&self, raw: &SystemInfoRaw) -> Vec<LineInfo> {
+        let Some(network) = &raw.network else {
+            return vec![];
+        };
+
+        let public_ip = raw.public_ip.as_ref();
+        let public_ipv4 = public_ip
+            .and_then(|public_ip| public_ip.ipv4)
+            .map(|addr| display_ip(IpAddr::V4(addr)));
+        let public_ipv6 = public_ip
+            .and_then(|public_ip| public_ip.ipv6)
+            .map(|addr| display_ip(IpAddr::V6(addr)));
+
+        let mut lines = vec![];
+        let string_data = match &network.addresses {
+            Some(addresses) => {
+                // the public addresses are usually the same as the global ones unless behind NAT
+                let mut string_data = addresses.clone();
+                for public in [public_ipv4, public_ipv6].into_iter().flatten() {
+                    if !addresses.contains(&public) {
+                        string_data.push(format!("{public} (public)"));
+                    }
+                }
+                string_data
+            }
+            None => {
+                // behind NAT there are no routable local addresses, but the public one is still shown
+                let family =
+                    |name: &str, count: usize, public: Option<String>| match (count, public) {
+                        (0, None) => None,
+                        (0, Some(addr)) => Some(format!("{name} ({addr})")),
+                        (count, None) => Some(format!("{count}x {name}")),
+                        (count, Some(addr)) => Some(format!("{count}x {name} ({addr})")),
+                    };
+
+                [
+                    family("IPv4", network.ipv4_count, public_ipv4),
+                    family("IPv6", network.ipv6_count, public_ipv6),
+                ]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+            }
+        };
+        if !string_data.is_empty() {
+            lines.push(LineInfo::new("Network", string_data.join(", ")));
+        }
+
+        // the first collection has nothing to compare against yet
+        if let (Some(rx), Some(tx)) = (network.rx_bytes_per_sec, network.tx_bytes_per_sec) {
+            lines.push(LineInfo::new(
+                "Traffic",
+                format!("↓ {}/s, ↑ {}/s", format_bytes(rx), format_bytes(tx)),
+            ));
+        }
+
+        lines
+    }
+}
+
+/// `addr` as shown on the page, masked when `network.mask_addresses` is set.
+fn display_ip(addr: IpAddr) -> String {
+    if config::get().network.mask_addresses {
+        mask_ip(addr)
+    } else {
+        addr.to_string()
+    }
+}
+
+/// Every network interface with its addresses, MTU, and link state, sorted by name.
+///
+/// The addresses are masked when `network.mask_addresses` is set.
+pub fn list_interfaces() -> Vec<InterfaceInfoRaw> {
+    let mask = config::get().network.mask_addresses;
+    let networks = Networks::new_with_refreshed_list();
+
+    let mut interfaces = networks
+        .iter()
+        .map(|(name, network)| {
+            let mac = network.mac_address();
+            let mac = (!mac.is_unspecified()).then(|| {
+                let mac = mac.to_string();
+                if mask { mask_mac(&mac) } else { mac }
+            });
+            let mut ip_networks = network.ip_networks().to_vec();
+            ip_networks.sort_by_key(|ip| ip.addr);
+            let addresses = ip_networks
+                .iter()
+                .map(|ip| {
+                    let addr = if mask {
+                        mask_ip(ip.addr)
+                    } else {
+                        ip.addr.to_string()
+                    };
+                    format!("{addr}/{}", ip.prefix)
+                })
+                .collect();
+
+            InterfaceInfoRaw {
+                name: name.clone(),
+                mac,
+                addresses,
+                mtu: network.mtu(),
+                state: link_state(name),
+                rx_bytes: network.total_received(),
+                tx_bytes: network.total_transmitted(),
+            }
+        })
+        .collect::<Vec<_>>();
+    interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+
+    interfaces
+}
+
+fn link_state(name: &str) -> Option<String> {
+    std::fs::read_to_string(format!("/sys/class/net/{name}/operstate"))
+        .ok()
+        .map(|state| state.trim().to_string())
+}
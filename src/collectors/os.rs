@@ -0,0 +1,64 @@
+use async_trait::async_trait;
+use sysinfo::System;
+
+use super::Collector;
+use crate::sysgetter::{
+    BOARD, CACHED_HOST, FIRMWARE, KERNEL_LONG_VER, LineInfo, OS_NAME, OsInfoRaw, SystemInfoRaw,
+    VIRTUALIZATION, format_uptime,
+};
+
+/// Operating system, host model, board, firmware, virtualization, kernel, and uptime.
+pub struct OsCollector;
+
+#[async_trait]
+impl Collector for OsCollector {
+    fn name(&self) -> &'static str {
+        "os"
+    }
+
+    async fn collect(&self, raw: &mut SystemInfoRaw) {
+        let pc_host = CACHED_HOST.clone();
+        let machine = if pc_host.is_empty() {
+            None
+        } else {
+            Some(pc_host)
+        };
+
+        // a VM reports the board and firmware the hypervisor emulates, e.g. SeaBIOS
+        let bare_metal = VIRTUALIZATION.as_deref() == Some("Bare metal");
+
+        raw.os = Some(OsInfoRaw {
+            name: OS_NAME.clone(),
+            machine,
+            kernel: KERNEL_LONG_VER.clone(),
+            uptime_seconds: System::uptime(),
+            virtualization: VIRTUALIZATION.clone(),
+            board: BOARD.clone().filter(|_| bare_metal),
+            firmware: FIRMWARE.clone().filter(|_| bare_metal),
+        });
+    }
+
+    fn lines(&self, raw: &SystemInfoRaw) -> Vec<LineInfo> {
+        let Some(os) = &raw.os else {
+            return vec![];
+        };
+
+        let mut lines = vec![LineInfo::new("OS", &os.name)];
+        if let Some(machine) = &os.machine {
+            lines.push(LineInfo::new("Host", machine));
+        }
+        if let Some(board) = &os.board {
+            lines.push(LineInfo::new("Board", board));
+        }
+        if let Some(firmware) = &os.firmware {
+            lines.push(LineInfo::new("Firmware", firmware));
+        }
+        if let Some(virtualization) = &os.virtualization {
+            lines.push(LineInfo::new("Virtualization", virtualization));
+        }
+        lines.push(LineInfo::new("Kernel", &os.kernel));
+        lines.push(LineInfo::new("Uptime", format_uptime(os.uptime_seconds)));
+
+        lines
+    }
+}
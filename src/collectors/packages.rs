@@ -0,0 +1,148 @@
+use std::{
+    path::Path,
+    process::Command,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+
+use super::Collector;
+use crate::sysgetter::{LineInfo, PackageCountRaw, SystemInfoRaw};
+
+/// Counting packages can take a while (especially `rpm`), so only redo it occasionally.
+const CACHE_TTL: Duration = Duration::from_secs(600);
+
+/// Installed package counts per detected package manager, like neofetch's "Packages".
+#[derive(Default)]
+pub struct PackagesCollector {
+    cached: Mutex<Option<(Instant, Vec<PackageCountRaw>)>>,
+}
+
+#[async_trait]
+impl Collector for PackagesCollector {
+    fn name(&self) -> &'static str {
+        "packages"
+    }
+
+    async fn collect(&self, raw: &mut SystemInfoRaw) {
+        let cached = {
+            let cached = self.cached.lock().unwrap_or_else(|e| e.into_inner());
+            cached
+                .as_ref()
+                .filter(|(counted_at, _)| counted_at.elapsed() < CACHE_TTL)
+                .map(|(_, counts)| counts.clone())
+        };
+
+        let counts = match cached {
+            Some(counts) => counts,
+            None => {
+                let counts = tokio::task::spawn_blocking(count_packages)
+                    .await
+                    .unwrap_or_default();
+                *self.cached.lock().unwrap_or_else(|e| e.into_inner()) =
+                    Some((Instant::now(), counts.clone()));
+                counts
+            }
+        };
+
+        if !counts.is_empty() {
+            raw.packages = Some(counts);
+        }
+    }
+
+    fn lines(&self, raw: &SystemInfoRaw) -> Vec<LineInfo> {
+        let Some(packages) = &raw.packages else {
+            return vec![];
+        };
+
+        let counts = packages
+            .iter()
+            .map(|package| format!("{} ({})", package.count, package.manager))
+            .collect::<Vec<_>>();
+
+        vec![LineInfo::new("Packages", counts.join(", "))]
+    }
+}
+
+/// Returns `None` when the package manager is not installed.
+type PackageCounter = fn() -> Option<usize>;
+
+const PACKAGE_COUNTERS: [(&str, PackageCounter); 5] = [
+    ("dpkg", count_dpkg),
+    ("rpm", count_rpm),
+    ("pacman", count_pacman),
+    ("apk", count_apk),
+    ("brew", count_brew),
+];
+
+fn count_packages() -> Vec<PackageCountRaw> {
+    PACKAGE_COUNTERS
+        .into_iter()
+        .filter_map(|(manager, counter)| {
+            let count = counter().filter(|count| *count > 0)?;
+            Some(PackageCountRaw {
+                manager: manager.to_string(),
+                count,
+            })
+        })
+        .collect()
+}
+
+fn count_dpkg() -> Option<usize> {
+    let status = std::fs::read_to_string("/var/lib/dpkg/status").ok()?;
+    Some(
+        status
+            .lines()
+            .filter(|line| *line == "Status: install ok installed")
+            .count(),
+    )
+}
+
+fn count_rpm() -> Option<usize> {
+    if !Path::new("/var/lib/rpm").exists() && !Path::new("/usr/lib/sysimage/rpm").exists() {
+        return None;
+    }
+
+    let output = Command::new("rpm").arg("-qa").output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).lines().count())
+}
+
+fn count_pacman() -> Option<usize> {
+    // one directory per package, plus an `ALPM_DB_VERSION` file
+    let entries = std::fs::read_dir("/var/lib/pacman/local").ok()?;
+    Some(
+        entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_dir())
+            .count(),
+    )
+}
+
+fn count_apk() -> Option<usize> {
+    let installed = std::fs::read_to_string("/lib/apk/db/installed").ok()?;
+    Some(
+        installed
+            .lines()
+            .filter(|line| line.starts_with("P:"))
+            .count(),
+    )
+}
+
+fn count_brew() -> Option<usize> {
+    let prefix = ["/opt/homebrew", "/usr/local", "/home/linuxbrew/.linuxbrew"]
+        .into_iter()
+        .map(Path::new)
+        .find(|prefix| prefix.join("Cellar").is_dir())?;
+
+    let count_dir = |dir: &str| {
+        std::fs::read_dir(prefix.join(dir))
+            .map(|entries| entries.filter_map(|entry| entry.ok()).count())
+            .unwrap_or(0)
+    };
+
+    Some(count_dir("Cellar") + count_dir("Caskroom"))
+}
@@ -0,0 +1,74 @@
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System};
+
+use super::Collector;
+use crate::{
+    config,
+    sysgetter::{LineInfo, ProcessesInfoRaw, SystemInfoRaw},
+};
+
+/// Number of running processes, and their threads when `processes.threads` is set.
+pub struct ProcessCountCollector {
+    sys: Mutex<System>,
+}
+
+impl Default for ProcessCountCollector {
+    fn default() -> Self {
+        ProcessCountCollector {
+            sys: Mutex::new(System::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl Collector for ProcessCountCollector {
+    fn name(&self) -> &'static str {
+        "processes"
+    }
+
+    async fn collect(&self, raw: &mut SystemInfoRaw) {
+        let threads = config::get().processes.threads;
+        // listing the threads of every process is a lot slower, skip them unless needed
+        let refresh_kind = if threads {
+            ProcessRefreshKind::nothing()
+        } else {
+            ProcessRefreshKind::nothing().without_tasks()
+        };
+
+        let mut sys = self.sys.lock().unwrap_or_else(|e| e.into_inner());
+        sys.refresh_processes_specifics(ProcessesToUpdate::All, true, refresh_kind);
+
+        let mut count = 0;
+        let mut thread_count = 0;
+        for process in sys.processes().values() {
+            match process.thread_kind() {
+                None => {
+                    count += 1;
+                    thread_count += 1;
+                }
+                Some(sysinfo::ThreadKind::Userland) => thread_count += 1,
+                // kernel threads aren't processes anyone started
+                Some(sysinfo::ThreadKind::Kernel) => {}
+            }
+        }
+
+        raw.processes = Some(ProcessesInfoRaw {
+            count,
+            threads: threads.then_some(thread_count),
+        });
+    }
+
+    fn lines(&self, raw: &SystemInfoRaw) -> Vec<LineInfo> {
+        let Some(processes) = &raw.processes else {
+            return vec![];
+        };
+
+        let value = match processes.threads {
+            Some(threads) => format!("{} ({threads} threads)", processes.count),
+            None => processes.count.to_string(),
+        };
+        vec![LineInfo::new("Processes", value)]
+    }
+}
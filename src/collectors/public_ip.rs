@@ -0,0 +1,127 @@
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+
+use super::Collector;
+use crate::{
+    config,
+    sysgetter::{LineInfo, PublicIpRaw, SystemInfoRaw},
+};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Public IPv4 and IPv6 addresses as seen by an external HTTPS service.
+#[derive(Default)]
+pub struct PublicIpCollector {
+    cached: Mutex<Option<(Instant, PublicIpRaw)>>,
+}
+
+#[async_trait]
+impl Collector for PublicIpCollector {
+    fn name(&self) -> &'static str {
+        "public_ip"
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        false
+    }
+
+    async fn collect(&self, raw: &mut SystemInfoRaw) {
+        let public_ip_config = &config::get().public_ip;
+        let cache_ttl = Duration::from_secs(public_ip_config.cache_ttl);
+        let cached = {
+            let cached = self.cached.lock().unwrap_or_else(|e| e.into_inner());
+            cached
+                .as_ref()
+                .filter(|(resolved_at, _)| resolved_at.elapsed() < cache_ttl)
+                .map(|(_, public_ip)| public_ip.clone())
+        };
+
+        // failed lookups are cached too, so a down service is not hit on every refresh
+        let public_ip = match cached {
+            Some(public_ip) => public_ip,
+            None => {
+                let (ipv4, ipv6) = tokio::join!(
+                    resolve(
+                        &public_ip_config.ipv4_url,
+                        IpAddr::V4(Ipv4Addr::UNSPECIFIED)
+                    ),
+                    resolve(
+                        &public_ip_config.ipv6_url,
+                        IpAddr::V6(Ipv6Addr::UNSPECIFIED)
+                    ),
+                );
+                let public_ip = PublicIpRaw {
+                    ipv4: ipv4.and_then(|ip| match ip {
+                        IpAddr::V4(addr) => Some(addr),
+                        IpAddr::V6(_) => None,
+                    }),
+                    ipv6: ipv6.and_then(|ip| match ip {
+                        IpAddr::V6(addr) => Some(addr),
+                        IpAddr::V4(_) => None,
+                    }),
+                };
+                *self.cached.lock().unwrap_or_else(|e| e.into_inner()) =
+                    Some((Instant::now(), public_ip.clone()));
+                public_ip
+            }
+        };
+
+        if public_ip.ipv4.is_some() || public_ip.ipv6.is_some() {
+            raw.public_ip = Some(public_ip);
+        }
+    }
+
+    fn lines(&self, raw: &SystemInfoRaw) -> Vec<LineInfo> {
+        // shown next to the address counts in the network line when that one is available
+        if raw.network.is_some() {
+            return vec![];
+        }
+        let Some(public_ip) = &raw.public_ip else {
+            return vec![];
+        };
+
+        let addresses = [
+            public_ip.ipv4.map(IpAddr::V4),
+            public_ip.ipv6.map(IpAddr::V6),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|addr| addr.to_string())
+        .collect::<Vec<_>>();
+
+        vec![LineInfo::new("Public IP", addresses.join(", "))]
+    }
+}
+
+/// Ask `url` for our address, binding to `local_address` so only that IP family is used.
+async fn resolve(url: &str, local_address: IpAddr) -> Option<IpAddr> {
+    if url.is_empty() {
+        return None;
+    }
+
+    let client = reqwest::Client::builder()
+        .local_address(local_address)
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .ok()?;
+
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status());
+    let body = match response {
+        Ok(response) => response.text().await.ok()?,
+        Err(err) => {
+            tracing::warn!("Failed to resolve public IP from {url}: {err}");
+            return None;
+        }
+    };
+
+    body.trim().parse().ok()
+}
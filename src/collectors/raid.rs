@@ -0,0 +1,152 @@
+use async_trait::async_trait;
+
+use super::Collector;
+use crate::sysgetter::{LineInfo, LineStatus, RaidArrayRaw, SystemInfoRaw};
+
+const MDSTAT_PATH: &str = "/proc/mdstat";
+const SYNC_ACTIONS: [&str; 4] = ["resync", "recovery", "reshape", "check"];
+
+/// Linux software RAID (mdadm) array state.
+pub struct RaidCollector;
+
+#[async_trait]
+impl Collector for RaidCollector {
+    fn name(&self) -> &'static str {
+        "raid"
+    }
+
+    async fn collect(&self, raw: &mut SystemInfoRaw) {
+        let Ok(content) = std::fs::read_to_string(MDSTAT_PATH) else {
+            return;
+        };
+
+        let arrays = parse_mdstat(&content);
+        if !arrays.is_empty() {
+            raw.raid = Some(arrays);
+        }
+    }
+
+    fn lines(&self, raw: &SystemInfoRaw) -> Vec<LineInfo> {
+        let Some(arrays) = &raw.raid else {
+            return vec![];
+        };
+
+        arrays
+            .iter()
+            .map(|array| {
+                let key = if arrays.len() > 1 {
+                    format!("RAID ({})", array.name)
+                } else {
+                    "RAID".to_string()
+                };
+
+                let mut value = String::new();
+                if let Some(level) = &array.level {
+                    value.push_str(level);
+                    value.push(' ');
+                }
+                value.push_str(&array.state);
+                if array.devices_total > 0 {
+                    value.push_str(&format!(
+                        " [{}/{}]",
+                        array.devices_total, array.devices_active
+                    ));
+                }
+                if let (Some(action), Some(percent)) = (&array.sync_action, array.sync_percent) {
+                    value.push_str(&format!(", {action} {percent:.1}%"));
+                }
+
+                LineInfo::new(key, value).with_status(array_status(array))
+            })
+            .collect()
+    }
+}
+
+fn array_status(array: &RaidArrayRaw) -> Option<LineStatus> {
+    match array.state.as_str() {
+        "degraded" | "inactive" => Some(LineStatus::Critical),
+        "clean" => None,
+        // resyncing, rebuilding, etc.
+        _ => Some(LineStatus::Warning),
+    }
+}
+
+/// Parse the arrays out of `/proc/mdstat`.
+///
+/// Each array starts with `md0 : active raid1 sdb1[1] sda1[0]`, followed by indented
+/// lines with the device status (`[2/1] [U_]`) and optionally a sync progress bar.
+fn parse_mdstat(content: &str) -> Vec<RaidArrayRaw> {
+    let mut arrays: Vec<RaidArrayRaw> = vec![];
+
+    for line in content.lines() {
+        if let Some((name, rest)) = line.split_once(" : ")
+            && name.starts_with("md")
+        {
+            let mut fields = rest.split_whitespace();
+            let activity = fields.next().unwrap_or_default();
+            // `active (read-only) raid1 ...` puts a flag before the level
+            let level = fields.find(|field| field.starts_with("raid") || *field == "linear");
+
+            arrays.push(RaidArrayRaw {
+                name: name.trim().to_string(),
+                level: level.map(String::from),
+                state: if activity == "inactive" {
+                    "inactive".to_string()
+                } else {
+                    "clean".to_string()
+                },
+                devices_total: 0,
+                devices_active: 0,
+                sync_action: None,
+                sync_percent: None,
+            });
+            continue;
+        }
+
+        let Some(array) = arrays.last_mut() else {
+            continue;
+        };
+        if !line.starts_with(char::is_whitespace) {
+            continue;
+        }
+
+        // `[2/1] [U_]`, total and active devices
+        if let Some(counts) = line
+            .split_whitespace()
+            .find(|field| field.starts_with('[') && field.contains('/'))
+        {
+            let counts = counts.trim_matches(|c| c == '[' || c == ']');
+            if let Some((total, active)) = counts.split_once('/') {
+                array.devices_total = total.parse().unwrap_or(0);
+                array.devices_active = active.parse().unwrap_or(0);
+                if array.devices_active < array.devices_total && array.state == "clean" {
+                    array.state = "degraded".to_string();
+                }
+            }
+        }
+
+        // `[==>....]  recovery = 12.6% (...) finish=...`
+        for action in SYNC_ACTIONS {
+            let Some((_, progress)) = line.split_once(&format!("{action} =")) else {
+                continue;
+            };
+
+            array.sync_action = Some(action.to_string());
+            array.sync_percent = progress
+                .split_whitespace()
+                .next()
+                .and_then(|percent| percent.trim_end_matches('%').parse().ok());
+            if array.state == "clean" {
+                array.state = match action {
+                    "check" => "checking",
+                    "recovery" => "recovering",
+                    "reshape" => "reshaping",
+                    _ => "resyncing",
+                }
+                .to_string();
+            }
+        }
+    }
+
+    arrays
+}
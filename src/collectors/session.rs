@@ -0,0 +1,43 @@
+use async_trait::async_trait;
+
+use super::Collector;
+use crate::sysgetter::{
+    DESKTOP_ENVIRONMENT, INIT_SYSTEM, LineInfo, SHELL_NAME, SessionInfoRaw, SystemInfoRaw,
+};
+
+/// Default shell, init system, and desktop environment, detected once.
+pub struct SessionCollector;
+
+#[async_trait]
+impl Collector for SessionCollector {
+    fn name(&self) -> &'static str {
+        "session"
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        false
+    }
+
+    async fn collect(&self, raw: &mut SystemInfoRaw) {
+        raw.session = Some(SessionInfoRaw {
+            shell: SHELL_NAME.clone(),
+            init: INIT_SYSTEM.clone(),
+            desktop: DESKTOP_ENVIRONMENT.clone(),
+        });
+    }
+
+    fn lines(&self, raw: &SystemInfoRaw) -> Vec<LineInfo> {
+        let Some(session) = &raw.session else {
+            return vec![];
+        };
+
+        [
+            ("Shell", &session.shell),
+            ("Init", &session.init),
+            ("DE", &session.desktop),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| Some(LineInfo::new(key, value.as_ref()?)))
+        .collect()
+    }
+}
@@ -0,0 +1,140 @@
+use std::{
+    process::Command,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+use super::Collector;
+use crate::sysgetter::{LineInfo, LineStatus, SmartDiskRaw, SystemInfoRaw};
+
+/// Querying SMART can wake sleeping disks, so don't do it on every refresh.
+const CACHE_TTL: Duration = Duration::from_secs(300);
+/// ATA attribute for "Reallocated_Sector_Ct".
+const REALLOCATED_SECTORS_ID: u64 = 5;
+
+/// Disk health from `smartctl`, needs smartmontools and usually root.
+#[derive(Default)]
+pub struct SmartCollector {
+    cached: Mutex<Option<(Instant, Vec<SmartDiskRaw>)>>,
+}
+
+#[async_trait]
+impl Collector for SmartCollector {
+    fn name(&self) -> &'static str {
+        "smart"
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        false
+    }
+
+    async fn collect(&self, raw: &mut SystemInfoRaw) {
+        let cached = {
+            let cached = self.cached.lock().unwrap_or_else(|e| e.into_inner());
+            cached
+                .as_ref()
+                .filter(|(read_at, _)| read_at.elapsed() < CACHE_TTL)
+                .map(|(_, disks)| disks.clone())
+        };
+
+        let disks = match cached {
+            Some(disks) => disks,
+            None => {
+                let disks = tokio::task::spawn_blocking(read_smart_disks)
+                    .await
+                    .unwrap_or_default();
+                *self.cached.lock().unwrap_or_else(|e| e.into_inner()) =
+                    Some((Instant::now(), disks.clone()));
+                disks
+            }
+        };
+
+        if !disks.is_empty() {
+            raw.smart = Some(disks);
+        }
+    }
+
+    fn lines(&self, raw: &SystemInfoRaw) -> Vec<LineInfo> {
+        let Some(disks) = &raw.smart else {
+            return vec![];
+        };
+
+        disks
+            .iter()
+            .map(|disk| {
+                let key = if disks.len() > 1 {
+                    format!("SMART ({})", disk.device.trim_start_matches("/dev/"))
+                } else {
+                    "SMART".to_string()
+                };
+
+                let mut parts = vec![match disk.passed {
+                    Some(true) => "PASSED".to_string(),
+                    Some(false) => "FAILED".to_string(),
+                    None => "Unknown".to_string(),
+                }];
+                if let Some(reallocated) = disk.reallocated_sectors {
+                    parts.push(format!("{reallocated} reallocated"));
+                }
+                if let Some(temperature) = disk.temperature_celsius {
+                    parts.push(format!("{temperature}°C"));
+                }
+
+                LineInfo::new(key, parts.join(", ")).with_status(disk_status(disk))
+            })
+            .collect()
+    }
+}
+
+fn disk_status(disk: &SmartDiskRaw) -> Option<LineStatus> {
+    if disk.passed == Some(false) {
+        Some(LineStatus::Critical)
+    } else if disk.reallocated_sectors.is_some_and(|sectors| sectors > 0) {
+        Some(LineStatus::Warning)
+    } else {
+        None
+    }
+}
+
+fn read_smart_disks() -> Vec<SmartDiskRaw> {
+    let Some(scan) = smartctl(&["--scan-open", "--json"]) else {
+        return vec![];
+    };
+
+    let devices = scan["devices"].as_array().cloned().unwrap_or_default();
+    devices
+        .iter()
+        .filter_map(|device| {
+            let name = device["name"].as_str()?;
+            let device_type = device["type"].as_str().unwrap_or("auto");
+            let info = smartctl(&["--json", "-H", "-A", "-i", "-d", device_type, name])?;
+
+            Some(SmartDiskRaw {
+                device: name.to_string(),
+                model: info["model_name"].as_str().map(String::from),
+                passed: info["smart_status"]["passed"].as_bool(),
+                reallocated_sectors: reallocated_sectors(&info),
+                temperature_celsius: info["temperature"]["current"].as_i64(),
+            })
+        })
+        .collect()
+}
+
+fn reallocated_sectors(info: &Value) -> Option<u64> {
+    info["ata_smart_attributes"]["table"]
+        .as_array()?
+        .iter()
+        .find(|attribute| attribute["id"].as_u64() == Some(REALLOCATED_SECTORS_ID))?["raw"]["value"]
+        .as_u64()
+}
+
+/// Run smartctl and parse its JSON output.
+///
+/// The exit code is a bitmask that is non-zero for unhealthy disks too, so only the output is checked.
+fn smartctl(args: &[&str]) -> Option<Value> {
+    let output = Command::new("smartctl").args(args).output().ok()?;
+    serde_json::from_slice(&output.stdout).ok()
+}
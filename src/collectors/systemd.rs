@@ -0,0 +1,90 @@
+use std::{io, path::Path, time::Duration};
+
+use async_trait::async_trait;
+use tokio::process::Command;
+
+use super::Collector;
+use crate::sysgetter::{LineInfo, ServicesInfoRaw, SystemInfoRaw};
+
+const SYSTEMCTL_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Number of failed systemd units.
+pub struct SystemdCollector;
+
+#[async_trait]
+impl Collector for SystemdCollector {
+    fn name(&self) -> &'static str {
+        "systemd"
+    }
+
+    async fn collect(&self, raw: &mut SystemInfoRaw) {
+        if !is_systemd_booted() {
+            return;
+        }
+
+        match failed_units().await {
+            Ok(units) => {
+                raw.services = Some(ServicesInfoRaw {
+                    failed: units.len(),
+                });
+            }
+            Err(err) => tracing::warn!("Failed to query systemd: {err}"),
+        }
+    }
+
+    fn lines(&self, raw: &SystemInfoRaw) -> Vec<LineInfo> {
+        let Some(services) = &raw.services else {
+            return vec![];
+        };
+
+        let value = match services.failed {
+            0 => "No failed units".to_string(),
+            1 => "1 failed unit".to_string(),
+            failed => format!("{failed} failed units"),
+        };
+        vec![LineInfo::new("Services", value)]
+    }
+}
+
+/// Same check `sd_booted()` does.
+fn is_systemd_booted() -> bool {
+    Path::new("/run/systemd/system").is_dir()
+}
+
+/// Names of every unit systemd considers failed.
+pub async fn failed_units() -> io::Result<Vec<String>> {
+    if !is_systemd_booted() {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "systemd is not running",
+        ));
+    }
+
+    let output = Command::new("systemctl")
+        .args([
+            "list-units",
+            "--state=failed",
+            "--plain",
+            "--no-legend",
+            "--no-pager",
+        ])
+        .kill_on_drop(true)
+        .output();
+    let output = tokio::time::timeout(SYSTEMCTL_TIMEOUT, output)
+        .await
+        .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "systemctl did not respond"))??;
+
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "systemctl exited with {}",
+            output.status
+        )));
+    }
+
+    // each line looks like `foo.service loaded failed failed Some description`
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .map(String::from)
+        .collect())
+}
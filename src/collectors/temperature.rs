@@ -0,0 +1,79 @@
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use sysinfo::Components;
+
+use super::Collector;
+use crate::{
+    config::{self, TemperatureUnit},
+    sysgetter::{LineInfo, SystemInfoRaw, TemperatureInfoRaw},
+};
+
+/// Readings from hardware sensors, e.g. CPU package and motherboard temperatures.
+pub struct TemperatureCollector {
+    components: Mutex<Components>,
+}
+
+impl Default for TemperatureCollector {
+    fn default() -> Self {
+        TemperatureCollector {
+            components: Mutex::new(Components::new_with_refreshed_list()),
+        }
+    }
+}
+
+#[async_trait]
+impl Collector for TemperatureCollector {
+    fn name(&self) -> &'static str {
+        "temperature"
+    }
+
+    async fn collect(&self, raw: &mut SystemInfoRaw) {
+        let temperatures_config = &config::get().temperatures;
+        let mut components = self.components.lock().unwrap_or_else(|e| e.into_inner());
+        components.refresh(true);
+
+        let sensors: Vec<TemperatureInfoRaw> = components
+            .iter()
+            .filter(|component| temperatures_config.is_selected(component.label()))
+            .filter_map(|component| {
+                Some(TemperatureInfoRaw {
+                    label: component.label().to_string(),
+                    celsius: component.temperature()?,
+                    critical_celsius: component.critical(),
+                })
+            })
+            .collect();
+
+        if !sensors.is_empty() {
+            raw.temperatures = Some(sensors);
+        }
+    }
+
+    fn lines(&self, raw: &SystemInfoRaw) -> Vec<LineInfo> {
+        let Some(sensors) = &raw.temperatures else {
+            return vec![];
+        };
+
+        let unit = config::get().temperatures.unit;
+        let readings = sensors
+            .iter()
+            .map(|sensor| {
+                format!(
+                    "{} {}",
+                    sensor.label,
+                    format_temperature(sensor.celsius, unit)
+                )
+            })
+            .collect::<Vec<_>>();
+
+        vec![LineInfo::new("Temps", readings.join(", "))]
+    }
+}
+
+pub fn format_temperature(celsius: f32, unit: TemperatureUnit) -> String {
+    match unit {
+        TemperatureUnit::Celsius => format!("{celsius:.1}°C"),
+        TemperatureUnit::Fahrenheit => format!("{:.1}°F", celsius * 9.0 / 5.0 + 32.0),
+    }
+}
@@ -0,0 +1,88 @@
+use async_trait::async_trait;
+use tokio::process::Command;
+
+use super::Collector;
+use crate::sysgetter::{LineInfo, LineStatus, SystemInfoRaw, ThrottlingInfoRaw};
+
+/// Exposed by the Raspberry Pi firmware driver, the same value `vcgencmd get_throttled` prints.
+const GET_THROTTLED_PATH: &str = "/sys/devices/platform/soc/soc:firmware/get_throttled";
+
+/// The flags of `get_throttled`, the same bits shifted by 16 are set when it happened since boot.
+const FLAGS: [(u32, &str); 4] = [
+    (1 << 0, "under-voltage"),
+    (1 << 1, "frequency capped"),
+    (1 << 2, "throttled"),
+    (1 << 3, "soft temperature limit"),
+];
+
+/// Under-voltage and thermal throttling reported by the Raspberry Pi firmware.
+pub struct ThrottlingCollector;
+
+#[async_trait]
+impl Collector for ThrottlingCollector {
+    fn name(&self) -> &'static str {
+        "throttling"
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        false
+    }
+
+    async fn collect(&self, raw: &mut SystemInfoRaw) {
+        let Some(flags) = read_throttled().await else {
+            return;
+        };
+
+        let names = |shift: u32| {
+            FLAGS
+                .iter()
+                .filter(|(bit, _)| flags & (bit << shift) != 0)
+                .map(|(_, name)| name.to_string())
+                .collect::<Vec<_>>()
+        };
+        raw.throttling = Some(ThrottlingInfoRaw {
+            flags,
+            active: names(0),
+            since_boot: names(16),
+        });
+    }
+
+    fn lines(&self, raw: &SystemInfoRaw) -> Vec<LineInfo> {
+        let Some(throttling) = &raw.throttling else {
+            return vec![];
+        };
+
+        let (value, status) = if !throttling.active.is_empty() {
+            (throttling.active.join(", "), Some(LineStatus::Warning))
+        } else if !throttling.since_boot.is_empty() {
+            (
+                format!("None, {} since boot", throttling.since_boot.join(", ")),
+                None,
+            )
+        } else {
+            ("None".to_string(), None)
+        };
+        vec![LineInfo::new("Throttling", value).with_status(status)]
+    }
+}
+
+/// The `get_throttled` bits from sysfs, or `vcgencmd` on kernels without the sysfs entry.
+async fn read_throttled() -> Option<u32> {
+    let value = match std::fs::read_to_string(GET_THROTTLED_PATH) {
+        Ok(value) => value,
+        Err(_) => {
+            let output = Command::new("vcgencmd")
+                .arg("get_throttled")
+                .output()
+                .await
+                .ok()?;
+            // throttled=0x50005
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            stdout.trim().strip_prefix("throttled=")?.to_string()
+        }
+    };
+
+    let value = value.trim();
+    let hex = value.strip_prefix("0x").unwrap_or(value);
+    u32::from_str_radix(hex, 16).ok()
+}
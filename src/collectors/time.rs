@@ -0,0 +1,47 @@
+use async_trait::async_trait;
+
+use super::Collector;
+use crate::sysgetter::{LineInfo, SystemInfoRaw, TIMEZONE, TimeInfoRaw};
+
+/// Timezone and local time of the machine, the page keeps the time ticking between refreshes.
+pub struct TimeCollector;
+
+#[async_trait]
+impl Collector for TimeCollector {
+    fn name(&self) -> &'static str {
+        "time"
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        false
+    }
+
+    async fn collect(&self, raw: &mut SystemInfoRaw) {
+        let now = chrono::Local::now();
+
+        raw.time = Some(TimeInfoRaw {
+            timezone: TIMEZONE.clone(),
+            utc_offset_seconds: now.offset().local_minus_utc(),
+            local_time: now.format("%Y-%m-%d %H:%M:%S").to_string(),
+        });
+    }
+
+    fn lines(&self, raw: &SystemInfoRaw) -> Vec<LineInfo> {
+        let Some(time) = &raw.time else {
+            return vec![];
+        };
+
+        let offset = time.utc_offset_seconds;
+        let utc_offset = format!(
+            "UTC{}{:02}:{:02}",
+            if offset < 0 { '-' } else { '+' },
+            offset.abs() / 3600,
+            offset.abs() % 3600 / 60
+        );
+        let value = match &time.timezone {
+            Some(timezone) => format!("{} ({timezone}, {utc_offset})", time.local_time),
+            None => format!("{} ({utc_offset})", time.local_time),
+        };
+        vec![LineInfo::new("Local time", value).with_clock_offset(offset)]
+    }
+}
@@ -0,0 +1,100 @@
+use async_trait::async_trait;
+
+use super::Collector;
+use crate::{
+    config,
+    sysgetter::{LineInfo, SystemInfoRaw, UserSessionRaw, UsersInfoRaw},
+};
+
+const UTMP_PATH: &str = "/var/run/utmp";
+/// Size of `struct utmp` on Linux with glibc.
+const UTMP_RECORD_SIZE: usize = 384;
+/// `ut_type` of a normal login session.
+const USER_PROCESS: i32 = 7;
+
+/// Currently logged-in users and where they are logged in from.
+pub struct UsersCollector;
+
+#[async_trait]
+impl Collector for UsersCollector {
+    fn name(&self) -> &'static str {
+        "users"
+    }
+
+    async fn collect(&self, raw: &mut SystemInfoRaw) {
+        let Ok(content) = std::fs::read(UTMP_PATH) else {
+            return;
+        };
+
+        let sessions = parse_utmp(&content);
+        let mut users: Vec<&str> = sessions.iter().map(|s| s.user.as_str()).collect();
+        users.sort_unstable();
+        users.dedup();
+
+        let count = users.len();
+        let sessions = if config::get().users.count_only {
+            vec![]
+        } else {
+            sessions
+        };
+
+        raw.users = Some(UsersInfoRaw { count, sessions });
+    }
+
+    fn lines(&self, raw: &SystemInfoRaw) -> Vec<LineInfo> {
+        let Some(users) = &raw.users else {
+            return vec![];
+        };
+
+        if users.sessions.is_empty() {
+            return vec![LineInfo::new("Users", users.count.to_string())];
+        }
+
+        let sessions = users
+            .sessions
+            .iter()
+            .map(|session| match &session.host {
+                Some(host) => format!("{} ({}, {host})", session.user, session.tty),
+                None => format!("{} ({})", session.user, session.tty),
+            })
+            .collect::<Vec<_>>();
+
+        vec![LineInfo::new(
+            "Users",
+            format!("{}: {}", users.count, sessions.join(", ")),
+        )]
+    }
+}
+
+/// Parse the login sessions out of the raw utmp records.
+///
+/// Layout of `struct utmp`: `ut_type` (i32) at 0, `ut_line` ([u8; 32]) at 8,
+/// `ut_user` ([u8; 32]) at 44, and `ut_host` ([u8; 256]) at 76.
+fn parse_utmp(content: &[u8]) -> Vec<UserSessionRaw> {
+    content
+        .chunks_exact(UTMP_RECORD_SIZE)
+        .filter_map(|record| {
+            let ut_type = i32::from_ne_bytes(record[0..4].try_into().ok()?);
+            if ut_type != USER_PROCESS {
+                return None;
+            }
+
+            let user = c_string(&record[44..76]);
+            if user.is_empty() {
+                return None;
+            }
+            let host = c_string(&record[76..332]);
+
+            Some(UserSessionRaw {
+                user,
+                tty: c_string(&record[8..40]),
+                host: (!host.is_empty()).then_some(host),
+            })
+        })
+        .collect()
+}
+
+fn c_string(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).trim().to_string()
+}
@@ -0,0 +1,1445 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{i18n::Locale, sysgetter};
+
+const DEFAULT_CONFIG_FILE: &str = "helios.toml";
+
+/// Shared with whoever still holds the previous one after a reload, freed once they let go.
+static CONFIG: RwLock<Option<Arc<Config>>> = RwLock::new(None);
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Address the HTTP server listens on, IPv4 or IPv6.
+    pub bind: IpAddr,
+    pub port: u16,
+    /// Path prefix helios is served below when behind a reverse proxy, e.g. `/helios`.
+    pub base_path: String,
+    /// Reverse proxies whose `Forwarded` or `X-Forwarded-For` headers are trusted for the client
+    /// address.
+    pub trusted_proxies: Vec<Cidr>,
+    /// Seconds between background refreshes of the system info.
+    pub refresh_interval: u64,
+    /// Name shown before the `@` in the host header, defaults to the user running helios.
+    pub username: String,
+    /// Minimum level of the log output.
+    pub log_level: String,
+    /// Level every HTTP request is logged at, `off` to not log them.
+    pub access_log_level: String,
+    pub log_format: LogFormat,
+    /// Units of the sizes and rates on the page, the API always uses bytes.
+    pub byte_units: ByteUnits,
+    /// Files here replace the embedded assets of the same name, e.g. `style.css` or `index.html`.
+    pub asset_dir: Option<PathBuf>,
+    pub collectors: CollectorsConfig,
+    pub lines: LinesConfig,
+    pub disks: DisksConfig,
+    pub temperatures: TemperaturesConfig,
+    pub docker: DockerConfig,
+    pub systemd: SystemdConfig,
+    pub users: UsersConfig,
+    pub processes: ProcessesConfig,
+    pub cpu: CpuConfig,
+    pub memory: MemoryConfig,
+    pub network: NetworkConfig,
+    pub theme: ThemeConfig,
+    pub locale: LocaleConfig,
+    pub durations: DurationsConfig,
+    pub manifest: ManifestConfig,
+    pub robots: RobotsConfig,
+    pub security_txt: SecurityTxtConfig,
+    pub logo: LogoConfig,
+    pub history: HistoryConfig,
+    pub persistence: PersistenceConfig,
+    pub public_ip: PublicIpConfig,
+    pub monitors: MonitorsConfig,
+    pub federation: FederationConfig,
+    pub push: PushConfig,
+    pub influx: InfluxConfig,
+    pub statsd: StatsdConfig,
+    pub heartbeat: HeartbeatConfig,
+    pub otel: OtelConfig,
+    pub alerts: AlertsConfig,
+    pub startup: StartupConfig,
+    pub cors: CorsConfig,
+    pub access: AccessConfig,
+    pub auth: AuthConfig,
+    pub api: ApiConfig,
+    pub tls: TlsConfig,
+    /// Read the assets and templates from the source tree on every request, only set by `--dev`.
+    #[serde(skip)]
+    pub dev: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Human readable lines.
+    #[default]
+    Text,
+    /// One JSON object per line, for log shippers like Promtail or Filebeat.
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ByteUnits {
+    /// KiB, MiB, and GiB, powers of 1024.
+    #[default]
+    Iec,
+    /// kB, MB, and GB, powers of 1000.
+    Si,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct CollectorsConfig {
+    /// Opt-in collectors that should run, e.g. `["diskio"]`.
+    pub enabled: Vec<String>,
+    /// Collectors that never run, e.g. `["network"]`.
+    pub disabled: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct LinesConfig {
+    /// Only show these lines, an empty list shows everything.
+    pub show: Vec<String>,
+    /// Lines that should never be shown.
+    pub hide: Vec<String>,
+    /// User-defined lines shown after the collected ones.
+    pub custom: Vec<CustomLineConfig>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CustomLineConfig {
+    pub key: String,
+    /// Shown as is, unless `command` is set.
+    #[serde(default)]
+    pub value: String,
+    /// Run with `sh -c` on every refresh, its output becomes the value.
+    pub command: Option<String>,
+    /// Seconds the command may run before it's killed.
+    #[serde(default = "default_custom_line_timeout")]
+    pub timeout: u64,
+    /// Longer output is cut off at this many characters.
+    #[serde(default = "default_custom_line_max_length")]
+    pub max_length: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct DisksConfig {
+    /// File systems that are skipped entirely (e.g. `tmpfs`).
+    pub ignored_file_systems: Vec<String>,
+    /// Mount points that are skipped, including everything mounted below them.
+    pub ignored_mount_points: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct TemperaturesConfig {
+    /// Sensors to show, matched case-insensitively against part of the label.
+    ///
+    /// When empty, only CPU package and motherboard sensors are shown.
+    pub sensors: Vec<String>,
+    pub unit: TemperatureUnit,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TemperatureUnit {
+    #[default]
+    Celsius,
+    Fahrenheit,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct DockerConfig {
+    /// Path to the Docker daemon socket.
+    pub socket: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct SystemdConfig {
+    /// Bearer token required to list the failed unit names, same as an API token with `read:services`.
+    ///
+    /// The listing is disabled unless this or an API token can read it.
+    pub token: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct UsersConfig {
+    /// Only show how many users are logged in, without names, TTYs, or hosts.
+    pub count_only: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct CpuConfig {
+    /// Append the package temperature to the CPU line.
+    pub temperature: bool,
+    /// Sensor to read the temperature from, matched case-insensitively against part of the label.
+    ///
+    /// When unset, the first sensor that looks like the CPU package is used.
+    pub temperature_sensor: Option<String>,
+    /// Show the cgroup CPU quota inside a container, with the usage relative to it.
+    pub container_limit: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct MemoryConfig {
+    pub mode: MemoryMode,
+    /// Show a line for every swap device instead of their total, when there is more than one.
+    pub swap_devices: bool,
+    /// Use the cgroup memory limit as the total inside a container.
+    pub container_limit: bool,
+}
+
+/// How the used memory is counted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MemoryMode {
+    /// As reported by the OS, total minus available on Linux.
+    #[default]
+    Used,
+    /// Also leave out buffers, page cache, and reclaimable slab, like htop.
+    NoCache,
+    /// Total minus available, shown as the available memory.
+    Available,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct NetworkConfig {
+    /// List the global addresses in the Network line instead of counting them.
+    pub show_addresses: bool,
+    /// Hide the host part of IP addresses and the device part of MAC addresses, e.g. `203.0.x.x`.
+    pub mask_addresses: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct ProcessesConfig {
+    /// Also count the threads of every process.
+    pub threads: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct ThemeConfig {
+    /// Theme used when the page is requested without `?theme=`.
+    pub default: Theme,
+    /// Stylesheet loaded on top of the base one by the `custom` theme, read on every request.
+    pub custom_css: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct ManifestConfig {
+    /// Name of the installed web app and the page title.
+    pub name: String,
+    /// Browser UI color, as a CSS color.
+    pub theme_color: String,
+    /// Splash screen color of the installed web app, as a CSS color.
+    pub background_color: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct RobotsConfig {
+    /// Served as `/robots.txt`, disallows every crawler by default.
+    pub content: String,
+}
+
+/// Fields of `/.well-known/security.txt`, see RFC 9116.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct SecurityTxtConfig {
+    /// `mailto:`, `tel:`, or `https:` URIs, the file is a 404 without any.
+    pub contact: Vec<String>,
+    /// RFC 3339 timestamp after which the file is stale, a year from the request when unset.
+    pub expires: Option<String>,
+    pub encryption: Option<String>,
+    pub policy: Option<String>,
+    pub acknowledgments: Option<String>,
+    pub preferred_languages: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct LogoConfig {
+    /// Draw the distro logo next to the info on the page and in the plaintext output.
+    pub enabled: bool,
+    /// Use the logo of this distro instead of the detected one, e.g. `arch`.
+    pub distro: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct LocaleConfig {
+    /// Language of the line labels and the uptime, unless the request asks for another one.
+    pub default: Locale,
+    /// Follow the `Accept-Language` header of browsers when it names a supported language.
+    pub accept_language: bool,
+}
+
+/// How durations like the uptime are written, per output format.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct DurationsConfig {
+    /// The page and its live updates.
+    pub html: DurationStyle,
+    /// `/api/v1/lines`.
+    pub json: DurationStyle,
+    /// `/txt` and `/md`.
+    pub text: DurationStyle,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DurationStyle {
+    /// `3 days, 4 hours, 12 minutes`, in the locale of the request.
+    #[default]
+    Full,
+    /// `3d 4h 12m`.
+    Compact,
+    /// `P3DT4H12M5S`, to the second.
+    Iso8601,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    #[default]
+    Dark,
+    Light,
+    /// `theme.custom_css`, falls back to `dark` when unset.
+    Custom,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct HistoryConfig {
+    /// Number of snapshots kept in memory, one is taken every `refresh_interval`.
+    pub retention: usize,
+    /// Averages kept for longer than the snapshots, e.g. one per minute for a day.
+    pub tiers: Vec<HistoryTier>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct HistoryTier {
+    /// Seconds of snapshots averaged into one sample.
+    pub resolution: u64,
+    /// Seconds an averaged sample is kept.
+    pub retention: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct PersistenceConfig {
+    /// SQLite database the snapshots are stored in, persistence is disabled when unset.
+    ///
+    /// Only available when built with the `sqlite` feature.
+    pub path: Option<PathBuf>,
+    /// Seconds between stored snapshots.
+    pub interval: u64,
+    /// Days a stored snapshot is kept before it is pruned.
+    pub retention_days: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct PublicIpConfig {
+    /// HTTPS endpoint answering with the caller's IPv4 address as plain text, empty to skip IPv4.
+    pub ipv4_url: String,
+    /// HTTPS endpoint answering with the caller's IPv6 address as plain text, empty to skip IPv6.
+    pub ipv6_url: String,
+    /// Seconds a resolved address is reused before asking the endpoints again.
+    pub cache_ttl: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct MonitorsConfig {
+    /// Seconds between checks, the results of the last one are shown in between.
+    pub interval: u64,
+    /// Seconds before a host that didn't answer is down.
+    pub timeout: u64,
+    /// Checked all at once, shown as one line each in this order.
+    pub hosts: Vec<HostMonitorConfig>,
+    /// Checked with the hosts, shown in the `Services` section of the page.
+    pub http: Vec<HttpMonitorConfig>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct HostMonitorConfig {
+    /// Shown in the line's label, the host when unset.
+    pub name: Option<String>,
+    /// Name or address, e.g. `192.168.1.1` or `db.internal`.
+    pub host: String,
+    #[serde(default)]
+    pub check: HostCheck,
+    /// Connected to by the `tcp` check.
+    pub port: Option<u16>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct HttpMonitorConfig {
+    /// Shown in the `Services` section, the URL when unset.
+    pub name: Option<String>,
+    /// Requested with a GET, redirects are followed.
+    pub url: String,
+    /// Status the response must have, any 2xx when unset.
+    pub status: Option<u16>,
+    /// Text the response body must contain, e.g. to tell a page from an error page served with 200.
+    pub keyword: Option<String>,
+}
+
+/// How a monitored host is reached.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HostCheck {
+    /// An echo request sent with the system's `ping`.
+    #[default]
+    Icmp,
+    /// A TCP connection to `port`, for hosts or networks that drop pings.
+    Tcp,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct FederationConfig {
+    /// Other helios instances shown below this one.
+    pub peers: Vec<PeerConfig>,
+    /// Seconds between fetches of the peers.
+    pub interval: u64,
+    /// Seconds to wait for a peer before marking it unreachable.
+    pub timeout: u64,
+    /// Shared secret agents sign their pushes with, pushes are refused when unset.
+    pub push_secret: Option<String>,
+    /// Seconds without a push before an agent is marked unreachable.
+    pub push_timeout: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct PushConfig {
+    /// Hub push endpoint the snapshots are sent to, e.g. `https://hub.example.com/api/v1/push`.
+    pub url: Option<String>,
+    /// Must match `federation.push_secret` of the hub.
+    pub secret: Option<String>,
+    /// Seconds between pushes.
+    pub interval: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct InfluxConfig {
+    /// Write endpoint the metrics are POSTed to in line protocol, nothing is pushed when unset.
+    ///
+    /// e.g. `http://localhost:8086/api/v2/write?org=home&bucket=helios` for InfluxDB 2, or
+    /// `http://localhost:8428/write` for VictoriaMetrics.
+    pub url: Option<String>,
+    /// Sent as `Authorization: Token <token>`.
+    pub token: Option<String>,
+    /// Seconds between pushes.
+    pub interval: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct StatsdConfig {
+    /// `host:port` of the StatsD server the gauges are sent to on every refresh.
+    pub address: Option<String>,
+    /// Put before every metric name, `{host}` is replaced with the host name.
+    pub prefix: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct HeartbeatConfig {
+    /// Uptime Kuma push URL or healthchecks.io ping URL, nothing is pinged when unset.
+    pub url: Option<String>,
+    /// What `url` belongs to, which decides how the status and message are sent.
+    pub kind: HeartbeatKind,
+    /// Seconds between pings.
+    pub interval: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HeartbeatKind {
+    /// GET with `status=up` or `status=down` and a `msg` in the query.
+    #[default]
+    UptimeKuma,
+    /// POST the message to the URL, or to `<url>/fail` when down.
+    Healthchecks,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct OtelConfig {
+    /// OTLP/HTTP metrics endpoint, e.g. `http://localhost:4318/v1/metrics`, disabled when unset.
+    ///
+    /// Only available when built with the `otel` feature.
+    pub endpoint: Option<String>,
+    /// OTLP/HTTP traces endpoint, e.g. `http://localhost:4318/v1/traces`, disabled when unset.
+    ///
+    /// Every HTTP request and every collector's run becomes a span. Only available when built
+    /// with the `otel` feature.
+    pub traces_endpoint: Option<String>,
+    /// Extra request headers, e.g. an API key of a hosted collector.
+    pub headers: HashMap<String, String>,
+    /// Reported as the `service.name` resource attribute.
+    pub service_name: String,
+    /// Seconds between exports.
+    pub interval: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PeerConfig {
+    /// Where the peer is served, including its `base_path`, e.g. `https://nas.example.com/helios`.
+    pub url: String,
+    /// Shown before the `@` of the peer's block.
+    #[serde(default = "default_peer_username")]
+    pub username: String,
+    /// Sent as a bearer token when the peer requires API tokens.
+    pub token: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct AlertsConfig {
+    /// Evaluated against every snapshot.
+    pub rules: Vec<AlertRule>,
+    /// Seconds before a rule that fired is notified about again.
+    pub cooldown: u64,
+    /// URLs the alerts are POSTed to as JSON.
+    pub webhooks: Vec<String>,
+    /// Discord channel webhook URLs, the alerts are sent as embeds.
+    pub discord_webhooks: Vec<String>,
+    /// Slack incoming webhook URLs.
+    pub slack_webhooks: Vec<String>,
+    /// SMTP server the alerts are emailed through.
+    pub email: Option<EmailConfig>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct StartupConfig {
+    /// URLs POSTed to as JSON when helios starts.
+    pub webhooks: Vec<String>,
+    /// Only notify when the machine booted since the last run, not when helios alone restarted.
+    pub reboots_only: bool,
+    /// Where the boot time is remembered between runs, reboots can't be told apart without it.
+    pub state_file: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct EmailConfig {
+    pub host: String,
+    /// Defaults to 465 with `tls = "tls"`, 587 with `starttls`, and 25 with `none`.
+    pub port: Option<u16>,
+    #[serde(default)]
+    pub tls: SmtpTls,
+    /// Credentials are only sent when both are set.
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Sender address, e.g. `helios <helios@example.com>`.
+    pub from: String,
+    pub to: Vec<String>,
+}
+
+/// How the connection to the SMTP server is secured.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpTls {
+    /// TLS from the start, usually port 465.
+    Tls,
+    /// Upgrade a plain connection with `STARTTLS`, refusing servers that don't support it.
+    #[default]
+    Starttls,
+    /// Plain text, only for a relay on the same machine or network.
+    None,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct AlertRule {
+    /// Shown in the notifications, e.g. `memory > 90` when unset.
+    pub name: Option<String>,
+    pub metric: AlertMetric,
+    pub op: Comparison,
+    pub threshold: f64,
+}
+
+/// Value an alert rule checks, percentages unless noted otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertMetric {
+    Cpu,
+    Memory,
+    Swap,
+    /// Checked for every disk.
+    Disk,
+    /// Degrees Celsius, checked for every shown sensor.
+    Temperature,
+    /// Charge, checked for every battery.
+    Battery,
+    /// Number of failed systemd units.
+    FailedUnits,
+    /// Number of missing devices, checked for every RAID array.
+    DegradedRaid,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Comparison {
+    #[serde(rename = ">")]
+    Above,
+    #[serde(rename = ">=")]
+    AtLeast,
+    #[serde(rename = "<")]
+    Below,
+    #[serde(rename = "<=")]
+    AtMost,
+}
+
+/// Which client addresses may reach the page and the API.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct AccessConfig {
+    /// The HTML page, its assets, and the other human-facing routes.
+    pub page: AccessRules,
+    /// `/api/*`, `/metrics`, `/influx`, and the health probes.
+    pub api: AccessRules,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct AccessRules {
+    /// Only these ranges are let in, everyone is when empty.
+    pub allow: Vec<Cidr>,
+    /// These ranges are turned away, even when they're allowed.
+    pub deny: Vec<Cidr>,
+}
+
+/// An address range like `10.0.0.0/8`, a bare address is a range of one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Cidr {
+    address: IpAddr,
+    prefix: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct CorsConfig {
+    /// Origins allowed to read the JSON API from a browser, `"*"` allows any origin.
+    ///
+    /// Cross-origin requests are not allowed when empty.
+    pub allowed_origins: Vec<String>,
+    pub allowed_methods: Vec<String>,
+    /// Request headers the browser may send, `"*"` allows any header.
+    pub allowed_headers: Vec<String>,
+    /// Seconds a browser may cache the preflight response.
+    pub max_age: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct AuthConfig {
+    /// Basic auth username, the page and API are public unless both this and the hash are set.
+    pub username: Option<String>,
+    /// Argon2 PHC string of the password, generated with `helios --hash-password`.
+    pub password_hash: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct ApiConfig {
+    /// Static bearer tokens, the API requires one as soon as any is configured.
+    pub tokens: Vec<ApiToken>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ApiToken {
+    pub token: String,
+    pub scopes: Vec<Scope>,
+}
+
+/// What an API token is allowed to read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Scope {
+    /// System info, CPU usage, history, series, and containers.
+    #[serde(rename = "read:info")]
+    ReadInfo,
+    #[serde(rename = "read:processes")]
+    ReadProcesses,
+    /// Names of the failed systemd units.
+    #[serde(rename = "read:services")]
+    ReadServices,
+    /// Everything.
+    #[serde(rename = "admin")]
+    Admin,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct TlsConfig {
+    /// PEM encoded certificate chain, HTTPS is enabled once both paths are set.
+    pub cert: Option<PathBuf>,
+    /// PEM encoded private key.
+    pub key: Option<PathBuf>,
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(PathBuf, std::io::Error),
+    Parse(PathBuf, toml::de::Error),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(path, err) => write!(f, "failed to read {}: {err}", path.display()),
+            ConfigError::Parse(path, err) => {
+                write!(f, "failed to parse {}: {err}", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            bind: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            port: 7889,
+            base_path: String::new(),
+            trusted_proxies: vec![],
+            refresh_interval: 10,
+            username: sysgetter::current_username().unwrap_or_else(|| "helios".to_string()),
+            log_level: "info".to_string(),
+            access_log_level: "info".to_string(),
+            log_format: LogFormat::default(),
+            byte_units: ByteUnits::default(),
+            asset_dir: None,
+            collectors: CollectorsConfig::default(),
+            lines: LinesConfig::default(),
+            disks: DisksConfig::default(),
+            temperatures: TemperaturesConfig::default(),
+            docker: DockerConfig::default(),
+            systemd: SystemdConfig::default(),
+            users: UsersConfig::default(),
+            processes: ProcessesConfig::default(),
+            cpu: CpuConfig::default(),
+            memory: MemoryConfig::default(),
+            network: NetworkConfig::default(),
+            theme: ThemeConfig::default(),
+            locale: LocaleConfig::default(),
+            durations: DurationsConfig::default(),
+            manifest: ManifestConfig::default(),
+            robots: RobotsConfig::default(),
+            security_txt: SecurityTxtConfig::default(),
+            logo: LogoConfig::default(),
+            history: HistoryConfig::default(),
+            persistence: PersistenceConfig::default(),
+            public_ip: PublicIpConfig::default(),
+            monitors: MonitorsConfig::default(),
+            federation: FederationConfig::default(),
+            push: PushConfig::default(),
+            influx: InfluxConfig::default(),
+            statsd: StatsdConfig::default(),
+            heartbeat: HeartbeatConfig::default(),
+            otel: OtelConfig::default(),
+            alerts: AlertsConfig::default(),
+            startup: StartupConfig::default(),
+            cors: CorsConfig::default(),
+            access: AccessConfig::default(),
+            auth: AuthConfig::default(),
+            api: ApiConfig::default(),
+            tls: TlsConfig::default(),
+            dev: false,
+        }
+    }
+}
+
+impl Default for DisksConfig {
+    fn default() -> Self {
+        DisksConfig {
+            ignored_file_systems: [
+                "tmpfs",
+                "devtmpfs",
+                "overlay",
+                "squashfs",
+                // BSD pseudo file systems
+                "devfs",
+                "fdescfs",
+                "procfs",
+                "linprocfs",
+                "linsysfs",
+                "mfs",
+            ]
+            .into_iter()
+            .map(String::from)
+            .collect(),
+            ignored_mount_points: vec![],
+        }
+    }
+}
+
+impl Config {
+    /// Load the configuration from `path`, `HELIOS_CONFIG`, or `./helios.toml` in that order.
+    ///
+    /// A missing default file is not an error, the built-in defaults are used instead.
+    pub fn load(path: Option<PathBuf>) -> Result<Self, ConfigError> {
+        let explicit = path.or_else(|| std::env::var_os("HELIOS_CONFIG").map(PathBuf::from));
+
+        let mut config = match explicit {
+            Some(path) => Self::from_file(&path)?,
+            None if Path::new(DEFAULT_CONFIG_FILE).exists() => {
+                Self::from_file(Path::new(DEFAULT_CONFIG_FILE))?
+            }
+            None => Config::default(),
+        };
+
+        // keep the old `PORT` env var working
+        if let Some(port) = std::env::var("PORT").ok().and_then(|p| p.parse().ok()) {
+            config.port = port;
+        }
+        if let Ok(base_path) = std::env::var("HELIOS_BASE_PATH") {
+            config.base_path = base_path;
+        }
+        config.base_path = normalize_base_path(&config.base_path);
+        if let Ok(secret) = std::env::var("HELIOS_PUSH_SECRET") {
+            config.push.secret = Some(secret);
+        }
+        if let Ok(token) = std::env::var("HELIOS_INFLUX_TOKEN") {
+            config.influx.token = Some(token);
+        }
+        // the standard exporter variables, the generic one is the base URL of every signal
+        if let Ok(endpoint) = std::env::var("OTEL_EXPORTER_OTLP_METRICS_ENDPOINT") {
+            config.otel.endpoint = Some(endpoint);
+        } else if let Ok(endpoint) = std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT") {
+            config.otel.endpoint = Some(format!("{}/v1/metrics", endpoint.trim_end_matches('/')));
+        }
+        if let Ok(endpoint) = std::env::var("OTEL_EXPORTER_OTLP_TRACES_ENDPOINT") {
+            config.otel.traces_endpoint = Some(endpoint);
+        } else if let Ok(endpoint) = std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT") {
+            config.otel.traces_endpoint =
+                Some(format!("{}/v1/traces", endpoint.trim_end_matches('/')));
+        }
+        if let Ok(password) = std::env::var("HELIOS_SMTP_PASSWORD")
+            && let Some(email) = &mut config.alerts.email
+        {
+            email.password = Some(password);
+        }
+        if let Ok(username) = std::env::var("HELIOS_USERNAME")
+            && !username.trim().is_empty()
+        {
+            config.username = username;
+        }
+        match std::env::var("HELIOS_LOG_FORMAT").as_deref() {
+            Ok("json") => config.log_format = LogFormat::Json,
+            Ok("text") => config.log_format = LogFormat::Text,
+            Ok(format) => eprintln!("Ignoring invalid log format from environment: {format}"),
+            Err(_) => {}
+        }
+        if let Ok(bind) = std::env::var("BIND").or_else(|_| std::env::var("HOST")) {
+            match bind.parse() {
+                Ok(bind) => config.bind = bind,
+                Err(_) => eprintln!("Ignoring invalid bind address from environment: {bind}"),
+            }
+        }
+
+        Ok(config)
+    }
+
+    pub fn socket_addr(&self) -> SocketAddr {
+        SocketAddr::new(self.bind, self.port)
+    }
+
+    fn from_file(path: &Path) -> Result<Self, ConfigError> {
+        let content =
+            std::fs::read_to_string(path).map_err(|e| ConfigError::Io(path.to_path_buf(), e))?;
+        toml::from_str(&content).map_err(|e| ConfigError::Parse(path.to_path_buf(), e))
+    }
+}
+
+impl CollectorsConfig {
+    pub fn is_enabled(&self, name: &str, enabled_by_default: bool) -> bool {
+        let listed = |names: &Vec<String>| names.iter().any(|n| n.eq_ignore_ascii_case(name));
+
+        if listed(&self.disabled) {
+            false
+        } else {
+            enabled_by_default || listed(&self.enabled)
+        }
+    }
+}
+
+impl LinesConfig {
+    /// Check if a line should be displayed, `Disk (/home)` is matched as `Disk`.
+    pub fn is_shown(&self, key: &str) -> bool {
+        let base_key = key.split(" (").next().unwrap_or(key);
+        let matches =
+            |name: &String| name.eq_ignore_ascii_case(key) || name.eq_ignore_ascii_case(base_key);
+
+        if self.hide.iter().any(matches) {
+            return false;
+        }
+
+        self.show.is_empty() || self.show.iter().any(matches)
+    }
+}
+
+impl AccessRules {
+    /// Whether `ip` is let in, denied ranges win over allowed ones.
+    pub fn permits(&self, ip: IpAddr) -> bool {
+        // IPv4 clients of a dual-stack listener show up as `::ffff:a.b.c.d`
+        let ip = ip.to_canonical();
+        if self.deny.iter().any(|cidr| cidr.contains(ip)) {
+            return false;
+        }
+
+        self.allow.is_empty() || self.allow.iter().any(|cidr| cidr.contains(ip))
+    }
+}
+
+impl Cidr {
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.address, ip) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix as u32).unwrap_or(0);
+                u32::from(network) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix as u32).unwrap_or(0);
+                u128::from(network) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl TryFrom<String> for Cidr {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let (address, prefix) = match value.split_once('/') {
+            Some((address, prefix)) => (address, Some(prefix)),
+            None => (value.as_str(), None),
+        };
+        let address = address
+            .trim()
+            .parse::<IpAddr>()
+            .map_err(|_| format!("invalid address in `{value}`"))?;
+        let max_prefix = if address.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(prefix) => prefix
+                .trim()
+                .parse::<u8>()
+                .ok()
+                .filter(|prefix| *prefix <= max_prefix)
+                .ok_or_else(|| format!("invalid prefix length in `{value}`"))?,
+            None => max_prefix,
+        };
+
+        // clients are matched by their canonical address, so `::ffff:10.0.0.0/104` is `10.0.0.0/8`
+        match address.to_canonical() {
+            IpAddr::V4(v4) if address.is_ipv6() && prefix >= 96 => Ok(Cidr {
+                address: IpAddr::V4(v4),
+                prefix: prefix - 96,
+            }),
+            _ => Ok(Cidr { address, prefix }),
+        }
+    }
+}
+
+impl DisksConfig {
+    pub fn is_ignored(&self, file_system: &str, mount_point: &str) -> bool {
+        if self
+            .ignored_file_systems
+            .iter()
+            .any(|fs| fs.eq_ignore_ascii_case(file_system))
+        {
+            return true;
+        }
+
+        self.ignored_mount_points.iter().any(|ignored| {
+            let ignored = ignored.trim_end_matches('/');
+            if ignored.is_empty() {
+                // only the root itself, not everything below it
+                return mount_point == "/";
+            }
+
+            mount_point == ignored
+                || mount_point
+                    .strip_prefix(ignored)
+                    .is_some_and(|rest| rest.starts_with('/'))
+        })
+    }
+}
+
+impl Default for CpuConfig {
+    fn default() -> Self {
+        CpuConfig {
+            temperature: true,
+            temperature_sensor: None,
+            container_limit: true,
+        }
+    }
+}
+
+impl Default for MemoryConfig {
+    fn default() -> Self {
+        MemoryConfig {
+            mode: MemoryMode::default(),
+            swap_devices: false,
+            container_limit: true,
+        }
+    }
+}
+
+impl Default for DockerConfig {
+    fn default() -> Self {
+        DockerConfig {
+            socket: "/var/run/docker.sock".to_string(),
+        }
+    }
+}
+
+impl Default for RobotsConfig {
+    fn default() -> Self {
+        RobotsConfig {
+            content: "User-agent: *\nDisallow: /\n".to_string(),
+        }
+    }
+}
+
+impl Default for ManifestConfig {
+    fn default() -> Self {
+        ManifestConfig {
+            name: "Helios".to_string(),
+            theme_color: "#4040FF".to_string(),
+            background_color: "#13161a".to_string(),
+        }
+    }
+}
+
+impl Default for LogoConfig {
+    fn default() -> Self {
+        LogoConfig {
+            enabled: true,
+            distro: None,
+        }
+    }
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        HistoryConfig {
+            // an hour with the default refresh interval
+            retention: 360,
+            tiers: vec![
+                HistoryTier {
+                    resolution: 60,
+                    retention: 86_400,
+                },
+                HistoryTier {
+                    resolution: 900,
+                    retention: 30 * 86_400,
+                },
+            ],
+        }
+    }
+}
+
+impl Default for PersistenceConfig {
+    fn default() -> Self {
+        PersistenceConfig {
+            path: None,
+            interval: 60,
+            retention_days: 7,
+        }
+    }
+}
+
+impl Default for PublicIpConfig {
+    fn default() -> Self {
+        PublicIpConfig {
+            ipv4_url: "https://api.ipify.org".to_string(),
+            ipv6_url: "https://api6.ipify.org".to_string(),
+            cache_ttl: 3600,
+        }
+    }
+}
+
+impl Default for FederationConfig {
+    fn default() -> Self {
+        FederationConfig {
+            peers: vec![],
+            interval: 30,
+            timeout: 5,
+            push_secret: None,
+            push_timeout: 120,
+        }
+    }
+}
+
+impl Default for MonitorsConfig {
+    fn default() -> Self {
+        MonitorsConfig {
+            interval: 60,
+            timeout: 2,
+            hosts: vec![],
+            http: vec![],
+        }
+    }
+}
+
+impl Default for PushConfig {
+    fn default() -> Self {
+        PushConfig {
+            url: None,
+            secret: None,
+            interval: 30,
+        }
+    }
+}
+
+impl Default for InfluxConfig {
+    fn default() -> Self {
+        InfluxConfig {
+            url: None,
+            token: None,
+            interval: 30,
+        }
+    }
+}
+
+impl Default for HeartbeatConfig {
+    fn default() -> Self {
+        HeartbeatConfig {
+            url: None,
+            kind: HeartbeatKind::default(),
+            interval: 60,
+        }
+    }
+}
+
+impl Default for StatsdConfig {
+    fn default() -> Self {
+        StatsdConfig {
+            address: None,
+            prefix: "helios.{host}".to_string(),
+        }
+    }
+}
+
+impl Default for OtelConfig {
+    fn default() -> Self {
+        OtelConfig {
+            endpoint: None,
+            traces_endpoint: None,
+            headers: HashMap::new(),
+            service_name: "helios".to_string(),
+            interval: 30,
+        }
+    }
+}
+
+impl PeerConfig {
+    pub fn base_url(&self) -> &str {
+        self.url.trim_end_matches('/')
+    }
+}
+
+fn default_peer_username() -> String {
+    "helios".to_string()
+}
+
+fn default_custom_line_timeout() -> u64 {
+    5
+}
+
+fn default_custom_line_max_length() -> usize {
+    100
+}
+
+impl Default for AlertsConfig {
+    fn default() -> Self {
+        AlertsConfig {
+            rules: vec![],
+            cooldown: 3600,
+            webhooks: vec![],
+            discord_webhooks: vec![],
+            slack_webhooks: vec![],
+            email: None,
+        }
+    }
+}
+
+impl Comparison {
+    pub fn compare(&self, value: f64, threshold: f64) -> bool {
+        match self {
+            Comparison::Above => value > threshold,
+            Comparison::AtLeast => value >= threshold,
+            Comparison::Below => value < threshold,
+            Comparison::AtMost => value <= threshold,
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Comparison::Above => ">",
+            Comparison::AtLeast => ">=",
+            Comparison::Below => "<",
+            Comparison::AtMost => "<=",
+        }
+    }
+}
+
+impl AlertMetric {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AlertMetric::Cpu => "cpu",
+            AlertMetric::Memory => "memory",
+            AlertMetric::Swap => "swap",
+            AlertMetric::Disk => "disk",
+            AlertMetric::Temperature => "temperature",
+            AlertMetric::Battery => "battery",
+            AlertMetric::FailedUnits => "failed_units",
+            AlertMetric::DegradedRaid => "degraded_raid",
+        }
+    }
+
+    /// `value` with the unit of the metric, e.g. `93.2%` or `71.0°C`.
+    pub fn format_value(&self, value: f64) -> String {
+        match self {
+            AlertMetric::Cpu
+            | AlertMetric::Memory
+            | AlertMetric::Swap
+            | AlertMetric::Disk
+            | AlertMetric::Battery => format!("{value:.1}%"),
+            AlertMetric::Temperature => format!("{value:.1}°C"),
+            AlertMetric::FailedUnits | AlertMetric::DegradedRaid => format!("{value:.0}"),
+        }
+    }
+}
+
+impl Default for LocaleConfig {
+    fn default() -> Self {
+        LocaleConfig {
+            default: Locale::default(),
+            accept_language: true,
+        }
+    }
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        CorsConfig {
+            allowed_origins: vec![],
+            allowed_methods: vec!["GET".to_string()],
+            allowed_headers: vec![],
+            max_age: 3600,
+        }
+    }
+}
+
+impl TemperaturesConfig {
+    /// Label fragments of the usual CPU package and motherboard sensors.
+    const DEFAULT_SENSORS: [&str; 7] = [
+        "package",
+        "tctl",
+        "tdie",
+        "cpu",
+        "acpitz",
+        "systin",
+        "motherboard",
+    ];
+
+    pub fn is_selected(&self, label: &str) -> bool {
+        let label = label.to_ascii_lowercase();
+        if self.sensors.is_empty() {
+            Self::DEFAULT_SENSORS
+                .iter()
+                .any(|sensor| label.contains(sensor))
+        } else {
+            self.sensors
+                .iter()
+                .any(|sensor| label.contains(&sensor.to_ascii_lowercase()))
+        }
+    }
+}
+
+impl AuthConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.username.is_some() && self.password_hash.is_some()
+    }
+}
+
+impl TlsConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.cert.is_some() || self.key.is_some()
+    }
+}
+
+/// `helios/` and `/helios/` both become `/helios`, and `/` becomes empty.
+fn normalize_base_path(base_path: &str) -> String {
+    let trimmed = base_path.trim().trim_matches('/');
+    if trimmed.is_empty() {
+        String::new()
+    } else {
+        format!("/{trimmed}")
+    }
+}
+
+/// Install the loaded configuration, should be called once at startup.
+pub fn init(config: Config) {
+    let mut current = CONFIG.write().unwrap();
+    if current.is_some() {
+        eprintln!("Configuration was already initialized, ignoring");
+        return;
+    }
+    *current = Some(Arc::new(config));
+}
+
+/// Swap in a reloaded configuration, the following [`get`] calls return it.
+///
+/// Whoever holds the previous one keeps its values until they let go of it.
+pub fn replace(config: Config) {
+    *CONFIG.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(config));
+}
+
+/// Get the active configuration, falling back to defaults if [`init`] was never called.
+///
+/// Background tasks hold on to the one they started with, the others should get it again for
+/// every request or refresh so they pick up reloads.
+pub fn get() -> Arc<Config> {
+    if let Some(config) = &*CONFIG.read().unwrap_or_else(|e| e.into_inner()) {
+        return config.clone();
+    }
+
+    CONFIG
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .get_or_insert_with(|| Arc::new(Config::default()))
+        .clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cidr(value: &str) -> Cidr {
+        Cidr::try_from(value.to_string()).unwrap()
+    }
+
+    fn ip(value: &str) -> IpAddr {
+        value.parse().unwrap()
+    }
+
+    fn rules(allow: &[&str], deny: &[&str]) -> AccessRules {
+        AccessRules {
+            allow: allow.iter().map(|value| cidr(value)).collect(),
+            deny: deny.iter().map(|value| cidr(value)).collect(),
+        }
+    }
+
+    #[test]
+    fn parses_ranges_and_bare_addresses() {
+        assert_eq!(cidr("10.0.0.0/8"), cidr(" 10.0.0.0 / 8 "));
+        assert_eq!(cidr("10.1.2.3"), cidr("10.1.2.3/32"));
+        assert_eq!(cidr("fd00::1"), cidr("fd00::1/128"));
+    }
+
+    #[test]
+    fn rejects_invalid_ranges() {
+        for value in [
+            "",
+            "10.0.0.0/",
+            "10.0.0.0/33",
+            "10.0.0.0/-1",
+            "10.0.0.0/8/8",
+            "fd00::/129",
+            "10.0.0/8",
+            "example.com/24",
+        ] {
+            assert!(Cidr::try_from(value.to_string()).is_err(), "{value}");
+        }
+    }
+
+    #[test]
+    fn ipv4_prefix_edges() {
+        assert!(cidr("0.0.0.0/0").contains(ip("255.255.255.255")));
+        assert!(cidr("10.0.0.0/8").contains(ip("10.255.255.255")));
+        assert!(!cidr("10.0.0.0/8").contains(ip("11.0.0.0")));
+        assert!(cidr("10.1.2.3/32").contains(ip("10.1.2.3")));
+        assert!(!cidr("10.1.2.3/32").contains(ip("10.1.2.4")));
+        // host bits of the network address don't matter
+        assert!(cidr("10.1.2.3/8").contains(ip("10.200.0.1")));
+    }
+
+    #[test]
+    fn ipv6_prefix_edges() {
+        assert!(cidr("::/0").contains(ip("ffff::1")));
+        assert!(cidr("fd00::/8").contains(ip("fdff::1")));
+        assert!(!cidr("fd00::/8").contains(ip("fe00::1")));
+        assert!(cidr("fd00::1/128").contains(ip("fd00::1")));
+        assert!(!cidr("fd00::1/128").contains(ip("fd00::2")));
+    }
+
+    #[test]
+    fn families_never_match_each_other() {
+        assert!(!cidr("0.0.0.0/0").contains(ip("::1")));
+        assert!(!cidr("::/0").contains(ip("127.0.0.1")));
+    }
+
+    #[test]
+    fn ipv4_mapped_ranges_are_ipv4_ranges() {
+        assert_eq!(cidr("::ffff:10.0.0.0/104"), cidr("10.0.0.0/8"));
+        assert_eq!(cidr("::ffff:10.1.2.3"), cidr("10.1.2.3/32"));
+        assert_eq!(cidr("::ffff:0.0.0.0/96"), cidr("0.0.0.0/0"));
+    }
+
+    #[test]
+    fn ipv4_mapped_clients_are_matched_as_ipv4() {
+        let rules = rules(&["10.0.0.0/8"], &["10.0.0.1"]);
+
+        assert!(rules.permits(ip("::ffff:10.0.0.2")));
+        assert!(!rules.permits(ip("::ffff:10.0.0.1")));
+        assert!(!rules.permits(ip("::ffff:192.168.0.1")));
+    }
+
+    #[test]
+    fn deny_wins_over_allow() {
+        let rules = rules(&["10.0.0.0/8", "fd00::/8"], &["10.0.0.0/24"]);
+
+        assert!(rules.permits(ip("10.0.1.1")));
+        assert!(!rules.permits(ip("10.0.0.1")));
+        assert!(rules.permits(ip("fd00::1")));
+        assert!(!rules.permits(ip("192.168.0.1")));
+    }
+
+    #[test]
+    fn empty_allow_lets_everyone_in() {
+        assert!(rules(&[], &[]).permits(ip("203.0.113.1")));
+        assert!(rules(&[], &["0.0.0.0/0"]).permits(ip("::1")));
+        assert!(!rules(&[], &["0.0.0.0/0"]).permits(ip("::ffff:127.0.0.1")));
+    }
+}
@@ -0,0 +1,65 @@
+use std::time::Duration;
+
+use axum::http::{HeaderName, HeaderValue, Method};
+use tower_http::cors::{AllowHeaders, AllowOrigin, Any, CorsLayer};
+
+use crate::config::CorsConfig;
+
+/// CORS layer for the JSON API, `None` when no origin is allowed.
+///
+/// Invalid origins, methods, or headers are skipped with a warning.
+pub fn layer(cors_config: &CorsConfig) -> Option<CorsLayer> {
+    if cors_config.allowed_origins.is_empty() {
+        return None;
+    }
+
+    let allow_origin = if is_wildcard(&cors_config.allowed_origins) {
+        AllowOrigin::any()
+    } else {
+        AllowOrigin::list(parse_all(
+            &cors_config.allowed_origins,
+            "origin",
+            |origin| HeaderValue::from_str(origin.trim_end_matches('/')).ok(),
+        ))
+    };
+
+    let methods = parse_all(&cors_config.allowed_methods, "method", |method| {
+        Method::from_bytes(method.to_ascii_uppercase().as_bytes()).ok()
+    });
+
+    let allow_headers = if is_wildcard(&cors_config.allowed_headers) {
+        AllowHeaders::any()
+    } else {
+        AllowHeaders::list(parse_all(
+            &cors_config.allowed_headers,
+            "header",
+            |header| HeaderName::from_bytes(header.as_bytes()).ok(),
+        ))
+    };
+
+    Some(
+        CorsLayer::new()
+            .allow_origin(allow_origin)
+            .allow_methods(methods)
+            .allow_headers(allow_headers)
+            .expose_headers(Any)
+            .max_age(Duration::from_secs(cors_config.max_age)),
+    )
+}
+
+fn is_wildcard(values: &[String]) -> bool {
+    values.iter().any(|value| value.trim() == "*")
+}
+
+fn parse_all<T>(values: &[String], kind: &str, parse: impl Fn(&str) -> Option<T>) -> Vec<T> {
+    values
+        .iter()
+        .filter_map(|value| {
+            let parsed = parse(value.trim());
+            if parsed.is_none() {
+                tracing::warn!("Ignoring invalid CORS {kind}: {value}");
+            }
+            parsed
+        })
+        .collect()
+}
@@ -0,0 +1,164 @@
+use std::time::Duration;
+
+use axum::{Json, body::Bytes, extract::State, response::IntoResponse};
+use serde::Serialize;
+use tokio::task::JoinSet;
+
+use crate::{
+    config::{self, PeerConfig},
+    state::AppState,
+    sysgetter::{LineInfo, LineStatus, SystemInfo},
+};
+
+/// What we last heard from a remote helios instance.
+#[derive(Debug, Clone, Serialize)]
+pub struct PeerStatus {
+    /// Where the peer is pulled from, `None` when it pushes to us instead.
+    pub url: Option<String>,
+    /// Shown before the `@` of the peer's block.
+    pub username: String,
+    /// Latest info from the peer's `/api/v1/lines`, kept around while it is unreachable.
+    pub info: Option<SystemInfo>,
+    /// Unix timestamp in seconds of the last successful fetch.
+    pub last_seen: Option<i64>,
+    /// Why the last fetch failed, `None` when it succeeded.
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct PeersResponse {
+    peers: Vec<PeerStatus>,
+}
+
+impl PeerStatus {
+    pub fn new(peer: &PeerConfig) -> Self {
+        PeerStatus {
+            url: Some(peer.base_url().to_string()),
+            username: peer.username.clone(),
+            info: None,
+            last_seen: None,
+            error: None,
+        }
+    }
+
+    /// A peer that pushed `info` to us.
+    pub fn pushed(username: String, info: SystemInfo) -> Self {
+        PeerStatus {
+            url: None,
+            username,
+            info: Some(info),
+            last_seen: Some(chrono::Utc::now().timestamp()),
+            error: None,
+        }
+    }
+
+    /// Host of the peer, its URL until it answered once.
+    pub fn host(&self) -> &str {
+        match &self.info {
+            Some(info) => info.host(),
+            None => self.url.as_deref().unwrap_or_default(),
+        }
+    }
+
+    /// The peer's lines, followed by a highlighted one when it could not be reached.
+    pub fn lines(&self) -> Vec<LineInfo> {
+        let mut lines = self
+            .info
+            .as_ref()
+            .map(|info| info.lines().to_vec())
+            .unwrap_or_default();
+
+        if let Some(error) = &self.error {
+            let status = match self.last_seen {
+                Some(_) => LineStatus::Warning,
+                None => LineStatus::Critical,
+            };
+            lines.push(
+                LineInfo::new("Status", format!("Unreachable ({error})")).with_status(Some(status)),
+            );
+        }
+
+        lines
+    }
+}
+
+/// Periodically pull the display lines of every configured peer into `state`.
+pub fn spawn_federation_task(state: AppState) {
+    let federation_config = &config::get().federation;
+    if federation_config.peers.is_empty() {
+        return;
+    }
+
+    let client = match reqwest::Client::builder()
+        .timeout(Duration::from_secs(federation_config.timeout.max(1)))
+        .build()
+    {
+        Ok(client) => client,
+        Err(err) => {
+            tracing::error!("Failed to create the federation HTTP client: {err}");
+            return;
+        }
+    };
+    let period = Duration::from_secs(federation_config.interval.max(1));
+    // the peers in `state` are the ones from startup, changing them needs a restart
+    let peers = federation_config.peers.clone();
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+
+            let mut fetches = JoinSet::new();
+            for (index, peer) in peers.iter().enumerate() {
+                let client = client.clone();
+                let peer = peer.clone();
+                fetches.spawn(async move { (index, fetch_peer(&client, &peer).await) });
+            }
+
+            while let Some(joined) = fetches.join_next().await {
+                let Ok((index, result)) = joined else {
+                    continue;
+                };
+                state.update_peer(index, result);
+            }
+        }
+    });
+}
+
+async fn fetch_peer(client: &reqwest::Client, peer: &PeerConfig) -> Result<SystemInfo, String> {
+    let body = match fetch_lines(client, peer, "/api/v1/lines").await {
+        // peers from before the versioned API only have `/s`
+        Err(err) if err.status() == Some(reqwest::StatusCode::NOT_FOUND) => {
+            fetch_lines(client, peer, "/s").await
+        }
+        result => result,
+    }
+    .map_err(|err| match err.status() {
+        Some(status) => format!("HTTP {status}"),
+        None if err.is_timeout() => "timed out".to_string(),
+        None => "connection failed".to_string(),
+    })?;
+
+    serde_json::from_slice(&body).map_err(|_| "invalid response".to_string())
+}
+
+async fn fetch_lines(
+    client: &reqwest::Client,
+    peer: &PeerConfig,
+    path: &str,
+) -> Result<Bytes, reqwest::Error> {
+    let mut request = client.get(format!("{}{path}", peer.base_url()));
+    if let Some(token) = &peer.token {
+        request = request.bearer_auth(token);
+    }
+
+    request.send().await?.error_for_status()?.bytes().await
+}
+
+pub async fn peers_handler(State(state): State<AppState>) -> impl IntoResponse {
+    Json(PeersResponse {
+        peers: state.peers(),
+    })
+}
@@ -0,0 +1,113 @@
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::sysgetter::SystemInfoRaw;
+
+/// Sections of `/api/v1/info`, `host` is always included.
+const SECTIONS: [&str; 27] = [
+    "os",
+    "packages",
+    "session",
+    "cpu",
+    "memory",
+    "swap",
+    "swap_devices",
+    "disks",
+    "disk_io",
+    "smart",
+    "raid",
+    "network",
+    "public_ip",
+    "temperatures",
+    "batteries",
+    "containers",
+    "services",
+    "users",
+    "processes",
+    "time",
+    "entropy",
+    "throttling",
+    "container_limits",
+    "monitors",
+    "http_monitors",
+    "custom",
+    "helios",
+];
+
+/// `?fields=cpu,memory` to only include those sections, `?exclude=network` to leave them out.
+#[derive(Debug, Deserialize)]
+pub struct FieldsQuery {
+    fields: Option<String>,
+    exclude: Option<String>,
+}
+
+impl FieldsQuery {
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_none() && self.exclude.is_none()
+    }
+}
+
+/// The sections of `raw` selected by `query`, or the unknown section that was asked for.
+///
+/// The snapshot is collected in the background, so leaving a section out only skips serializing it.
+pub fn select(raw: &SystemInfoRaw, query: &FieldsQuery) -> Result<Value, String> {
+    let included = match &query.fields {
+        Some(fields) => parse(fields)?,
+        None => SECTIONS.to_vec(),
+    };
+    let excluded = match &query.exclude {
+        Some(exclude) => parse(exclude)?,
+        None => vec![],
+    };
+
+    let Ok(Value::Object(mut object)) = serde_json::to_value(raw) else {
+        return Ok(Value::Object(Map::new()));
+    };
+    object.retain(|key, _| {
+        key == "host" || (included.contains(&key.as_str()) && !excluded.contains(&key.as_str()))
+    });
+
+    Ok(Value::Object(object))
+}
+
+/// Split a comma separated list into sections, accepting the collector names too.
+fn parse(list: &str) -> Result<Vec<&'static str>, String> {
+    let mut sections = vec![];
+    for name in list
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+    {
+        match section_names(name) {
+            Some(names) => sections.extend_from_slice(names),
+            None => {
+                return Err(format!(
+                    "Unknown field `{name}`, expected one of: {}",
+                    SECTIONS.join(", ")
+                ));
+            }
+        }
+    }
+
+    Ok(sections)
+}
+
+/// The sections a field stands for, e.g. the `memory` collector fills `memory`, `swap`, and `swap_devices`.
+fn section_names(name: &str) -> Option<&'static [&'static str]> {
+    let names: &'static [&'static str] = match name {
+        "memory" => &["memory", "swap", "swap_devices"],
+        "disk" => &["disks"],
+        "diskio" => &["disk_io"],
+        "temperature" => &["temperatures"],
+        "battery" => &["batteries"],
+        "docker" => &["containers"],
+        "systemd" => &["services"],
+        "monitors" => &["monitors", "http_monitors"],
+        _ => {
+            let index = SECTIONS.iter().position(|section| *section == name)?;
+            &SECTIONS[index..=index]
+        }
+    };
+
+    Some(names)
+}
@@ -0,0 +1,89 @@
+use std::sync::LazyLock;
+
+use async_graphql::{Context, EmptyMutation, EmptySubscription, Object, Schema};
+use axum::{Json, extract::State};
+
+use crate::{
+    history::HistorySample,
+    state::{AppState, Snapshot},
+    sysgetter::{
+        CpuInfoRaw, DiskInfoRaw, DiskIoInfoRaw, NetworkInfoRaw, OsInfoRaw, TemperatureInfoRaw,
+        UsageInfoRaw,
+    },
+};
+
+/// The schema is the same for every request, only the snapshot differs.
+static SCHEMA: LazyLock<Schema<Query, EmptyMutation, EmptySubscription>> =
+    LazyLock::new(|| Schema::build(Query, EmptyMutation, EmptySubscription).finish());
+
+/// Sections of disabled collectors resolve to `null`, like they're left out of `/api/v1/info`.
+pub struct Query;
+
+#[Object]
+impl Query {
+    async fn host(&self, ctx: &Context<'_>) -> String {
+        snapshot(ctx).raw.host.clone()
+    }
+
+    /// Unix timestamp of when the snapshot was collected.
+    async fn collected_at(&self, ctx: &Context<'_>) -> i64 {
+        snapshot(ctx).collected_at
+    }
+
+    async fn os(&self, ctx: &Context<'_>) -> Option<OsInfoRaw> {
+        snapshot(ctx).raw.os.clone()
+    }
+
+    async fn cpu(&self, ctx: &Context<'_>) -> Option<CpuInfoRaw> {
+        snapshot(ctx).raw.cpu.clone()
+    }
+
+    async fn memory(&self, ctx: &Context<'_>) -> Option<UsageInfoRaw> {
+        snapshot(ctx).raw.memory.clone()
+    }
+
+    async fn swap(&self, ctx: &Context<'_>) -> Option<UsageInfoRaw> {
+        snapshot(ctx).raw.swap.clone()
+    }
+
+    async fn disks(&self, ctx: &Context<'_>) -> Option<Vec<DiskInfoRaw>> {
+        snapshot(ctx).raw.disks.clone()
+    }
+
+    async fn disk_io(&self, ctx: &Context<'_>) -> Option<DiskIoInfoRaw> {
+        snapshot(ctx).raw.disk_io.clone()
+    }
+
+    async fn network(&self, ctx: &Context<'_>) -> Option<NetworkInfoRaw> {
+        snapshot(ctx).raw.network.clone()
+    }
+
+    async fn temperatures(&self, ctx: &Context<'_>) -> Option<Vec<TemperatureInfoRaw>> {
+        snapshot(ctx).raw.temperatures.clone()
+    }
+
+    /// In-memory samples between two unix timestamps, like `/api/v1/history`.
+    async fn history(
+        &self,
+        ctx: &Context<'_>,
+        from: Option<i64>,
+        to: Option<i64>,
+    ) -> Vec<HistorySample> {
+        ctx.data_unchecked::<AppState>().history(from, to)
+    }
+}
+
+/// Execute a GraphQL query against the latest snapshot.
+pub async fn graphql_handler(
+    State(state): State<AppState>,
+    Json(request): Json<async_graphql::Request>,
+) -> Json<async_graphql::Response> {
+    // every field of one query reads the same snapshot
+    let request = request.data(state.latest()).data(state);
+
+    Json(SCHEMA.execute(request).await)
+}
+
+fn snapshot<'a>(ctx: &Context<'a>) -> &'a Snapshot {
+    ctx.data_unchecked::<Snapshot>()
+}
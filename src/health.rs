@@ -0,0 +1,58 @@
+use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use serde::Serialize;
+
+use crate::{live, state::AppState};
+
+/// Ready while the snapshot is younger than this many refresh intervals.
+const READY_INTERVALS: u64 = 2;
+/// Past this many refresh intervals the refresh task is considered stuck or dead.
+const LIVE_INTERVALS: u64 = 5;
+
+#[derive(Debug, Serialize)]
+struct HealthResponse {
+    status: &'static str,
+    message: String,
+    /// Unix timestamp in seconds of the latest snapshot.
+    last_refresh: i64,
+    age_seconds: u64,
+}
+
+/// Liveness, fails once the background refresh stopped producing snapshots entirely.
+pub async fn livez(State(state): State<AppState>) -> impl IntoResponse {
+    check(&state, LIVE_INTERVALS)
+}
+
+/// Readiness, fails as soon as the latest snapshot is stale.
+pub async fn readyz(State(state): State<AppState>) -> impl IntoResponse {
+    check(&state, READY_INTERVALS)
+}
+
+fn check(state: &AppState, max_intervals: u64) -> (StatusCode, Json<HealthResponse>) {
+    let last_refresh = state.collected_at();
+    let age_seconds = chrono::Utc::now()
+        .timestamp()
+        .saturating_sub(last_refresh)
+        .max(0) as u64;
+    let interval = live::refresh_interval().as_secs();
+    let max_age = interval.saturating_mul(max_intervals);
+
+    let (status_code, status, message) = if age_seconds < max_age {
+        (StatusCode::OK, "ok", "Helios is running".to_string())
+    } else {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "error",
+            format!("The latest snapshot is {age_seconds}s old, one is expected every {interval}s"),
+        )
+    };
+
+    (
+        status_code,
+        Json(HealthResponse {
+            status,
+            message,
+            last_refresh,
+            age_seconds,
+        }),
+    )
+}
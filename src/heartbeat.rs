@@ -0,0 +1,118 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    config::{self, HeartbeatKind},
+    sysgetter::{LineStatus, SystemInfo},
+};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Pings an external uptime monitor with the state of the snapshots, so it notices both helios
+/// going quiet and lines turning critical.
+pub struct Heartbeat {
+    url: String,
+    kind: HeartbeatKind,
+    interval: Duration,
+    client: reqwest::Client,
+    last_ping: Option<Instant>,
+}
+
+impl Heartbeat {
+    /// `None` when no `heartbeat.url` is configured.
+    pub fn from_config() -> Option<Self> {
+        let heartbeat_config = &config::get().heartbeat;
+        let url = heartbeat_config.url.as_ref()?;
+
+        let client = match reqwest::Client::builder().timeout(REQUEST_TIMEOUT).build() {
+            Ok(client) => client,
+            Err(err) => {
+                tracing::error!("Failed to create the heartbeat HTTP client: {err}");
+                return None;
+            }
+        };
+        let interval = Duration::from_secs(heartbeat_config.interval.max(1));
+
+        tracing::info!("Pinging {url} every {}s", interval.as_secs());
+        Some(Heartbeat {
+            url: url.clone(),
+            kind: heartbeat_config.kind,
+            interval,
+            client,
+            last_ping: None,
+        })
+    }
+
+    /// Ping in the background once `heartbeat.interval` passed since the last ping.
+    pub fn ping(&mut self, info: &SystemInfo) {
+        let now = Instant::now();
+        if self
+            .last_ping
+            .is_some_and(|last| now.duration_since(last) < self.interval)
+        {
+            return;
+        }
+        self.last_ping = Some(now);
+
+        let critical = flagged(info, LineStatus::Critical);
+        let warning = flagged(info, LineStatus::Warning);
+        let up = critical.is_empty();
+        let message = match (critical.is_empty(), warning.is_empty()) {
+            (true, true) => "OK".to_string(),
+            (true, false) => format!("Warning: {warning}"),
+            (false, true) => format!("Critical: {critical}"),
+            (false, false) => format!("Critical: {critical}; Warning: {warning}"),
+        };
+
+        let request = match self.kind {
+            HeartbeatKind::UptimeKuma => {
+                let mut url = match reqwest::Url::parse(&self.url) {
+                    Ok(url) => url,
+                    Err(err) => {
+                        tracing::warn!("Not pinging the invalid heartbeat URL {}: {err}", self.url);
+                        return;
+                    }
+                };
+                // the push URL Uptime Kuma shows comes with placeholder parameters
+                let params = url
+                    .query_pairs()
+                    .filter(|(name, _)| !matches!(name.as_ref(), "status" | "msg" | "ping"))
+                    .map(|(name, value)| (name.into_owned(), value.into_owned()))
+                    .collect::<Vec<_>>();
+                url.query_pairs_mut()
+                    .clear()
+                    .extend_pairs(params)
+                    .append_pair("status", if up { "up" } else { "down" })
+                    .append_pair("msg", &message);
+                self.client.get(url)
+            }
+            HeartbeatKind::Healthchecks => {
+                let url = if up {
+                    self.url.clone()
+                } else {
+                    format!("{}/fail", self.url.trim_end_matches('/'))
+                };
+                self.client.post(url).body(message)
+            }
+        };
+
+        tokio::spawn(async move {
+            let result = request
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+            if let Err(err) = result {
+                tracing::warn!("Failed to send the heartbeat: {err}");
+            }
+        });
+    }
+}
+
+/// The keys of the lines with `status`, e.g. `Disk (/), Memory`.
+fn flagged(info: &SystemInfo, status: LineStatus) -> String {
+    info.lines()
+        .iter()
+        .filter(|line| line.status() == Some(status))
+        .map(|line| line.key())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,650 @@
+use std::{collections::VecDeque, fmt::Write};
+
+use axum::{
+    Json,
+    body::Body,
+    extract::{Query, State},
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, mpsc};
+use tokio_stream::wrappers::ReceiverStream;
+
+use crate::{
+    config::HistoryTier,
+    live,
+    state::AppState,
+    sysgetter::{SystemInfoRaw, usage_percent},
+};
+
+/// Default `window` of `/api/v1/series`, an hour.
+const DEFAULT_SERIES_WINDOW: i64 = 3600;
+const DEFAULT_SERIES_POINTS: usize = 60;
+const MAX_SERIES_POINTS: usize = 1000;
+/// Samples of `/api/v1/history.csv` and `/api/v1/history.ndjson` written at a time.
+const EXPORT_CHUNK_ROWS: usize = 500;
+/// Every column of `/api/v1/history.csv` without a `metric`.
+const CSV_COLUMNS: &str = "timestamp,time,cpu_usage_percent,memory_used_bytes,\
+memory_usage_percent,disk_used_bytes,disk_total_bytes,network_rx_bytes_per_sec,\
+network_tx_bytes_per_sec";
+
+/// The most recent samples, oldest first, dropping the oldest once `capacity` is reached.
+///
+/// Every sample is also averaged into the tiers, which keep coarser samples for longer.
+#[derive(Debug, Clone)]
+pub struct History {
+    samples: VecDeque<HistorySample>,
+    capacity: usize,
+    /// Finest resolution first.
+    tiers: Vec<Tier>,
+}
+
+/// Averages of `resolution` seconds of samples, kept for `retention` seconds.
+#[derive(Debug, Clone)]
+struct Tier {
+    resolution: i64,
+    retention: i64,
+    samples: VecDeque<HistorySample>,
+    /// The bucket the newest samples fall into, averaged once a sample lands in the next one.
+    pending: Option<Bucket>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Bucket {
+    start: i64,
+    cpu_usage_percent: Mean,
+    memory_used_bytes: Mean,
+    memory_usage_percent: Mean,
+    disk_used_bytes: Mean,
+    disk_total_bytes: Mean,
+    network_rx_bytes_per_sec: Mean,
+    network_tx_bytes_per_sec: Mean,
+}
+
+/// Mean of the values that were known, `None` when none were.
+#[derive(Debug, Clone, Copy, Default)]
+struct Mean {
+    sum: f64,
+    count: u32,
+}
+
+/// The numbers worth graphing from one snapshot.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
+pub struct HistorySample {
+    /// Unix timestamp in seconds.
+    pub timestamp: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu_usage_percent: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_used_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_usage_percent: Option<f64>,
+    /// Used space across every shown disk.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disk_used_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disk_total_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network_rx_bytes_per_sec: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network_tx_bytes_per_sec: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HistoryQuery {
+    /// Only samples at or after this unix timestamp.
+    pub from: Option<i64>,
+    /// Only samples at or before this unix timestamp.
+    pub to: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+struct HistoryResponse {
+    samples: Vec<HistorySample>,
+}
+
+/// A value that can be graphed over time.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SeriesMetric {
+    /// Usage percentage across every core.
+    Cpu,
+    /// Memory usage percentage.
+    Memory,
+    /// Usage percentage across every shown disk.
+    Disk,
+    /// Download rate in bytes per second.
+    NetworkRx,
+    /// Upload rate in bytes per second.
+    NetworkTx,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SeriesQuery {
+    metric: SeriesMetric,
+    /// How far back to look, e.g. `90s`, `15m`, `1h`, or `1d`.
+    window: Option<String>,
+    /// Maximum number of points, the samples are averaged into this many buckets.
+    points: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CsvQuery {
+    /// Only this metric, every column when unset.
+    metric: Option<SeriesMetric>,
+    /// Only samples at or after this unix timestamp.
+    from: Option<i64>,
+    /// Only samples at or before this unix timestamp.
+    to: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NdjsonQuery {
+    /// Only samples at or after this unix timestamp.
+    from: Option<i64>,
+    /// Only samples at or before this unix timestamp.
+    to: Option<i64>,
+    /// Keep the response open and add a line for every new snapshot, `1` or `true`.
+    #[serde(default, deserialize_with = "deserialize_flag")]
+    follow: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct SeriesPoint {
+    /// Unix timestamp in seconds of the start of the bucket.
+    pub timestamp: i64,
+    pub value: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct SeriesResponse {
+    metric: SeriesMetric,
+    window_seconds: i64,
+    points: Vec<SeriesPoint>,
+}
+
+impl History {
+    pub fn new(capacity: usize, tiers: &[HistoryTier]) -> Self {
+        let mut tiers = tiers
+            .iter()
+            .filter(|tier| tier.resolution > 0 && tier.retention > 0)
+            .map(|tier| Tier {
+                resolution: tier.resolution as i64,
+                retention: tier.retention as i64,
+                samples: VecDeque::new(),
+                pending: None,
+            })
+            .collect::<Vec<_>>();
+        tiers.sort_by_key(|tier| tier.resolution);
+
+        History {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+            tiers,
+        }
+    }
+
+    pub fn push(&mut self, sample: HistorySample) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        for tier in &mut self.tiers {
+            tier.push(&sample);
+        }
+        while self.samples.len() >= self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    /// Samples between `from` and `to` (both inclusive), oldest first.
+    ///
+    /// The finest samples kept for a time are used, the tiers only fill in before them.
+    pub fn range(&self, from: Option<i64>, to: Option<i64>) -> Vec<HistorySample> {
+        let mut until = self
+            .samples
+            .front()
+            .map_or(i64::MAX, |sample| sample.timestamp);
+        let mut older = Vec::new();
+        for tier in &self.tiers {
+            let covered = tier
+                .samples
+                .iter()
+                .take_while(|sample| sample.timestamp < until)
+                .collect::<Vec<_>>();
+            if let Some(first) = covered.first() {
+                until = first.timestamp;
+            }
+            older.push(covered);
+        }
+
+        older
+            .into_iter()
+            .rev()
+            .flatten()
+            .chain(&self.samples)
+            .filter(|sample| from.is_none_or(|from| sample.timestamp >= from))
+            .filter(|sample| to.is_none_or(|to| sample.timestamp <= to))
+            .cloned()
+            .collect()
+    }
+}
+
+impl Tier {
+    fn push(&mut self, sample: &HistorySample) {
+        let start = sample.timestamp - sample.timestamp.rem_euclid(self.resolution);
+        if self
+            .pending
+            .as_ref()
+            .is_some_and(|bucket| bucket.start != start)
+            && let Some(bucket) = self.pending.take()
+        {
+            self.samples.push_back(bucket.average());
+        }
+        self.pending
+            .get_or_insert_with(|| Bucket {
+                start,
+                ..Bucket::default()
+            })
+            .add(sample);
+
+        let oldest = sample.timestamp.saturating_sub(self.retention);
+        while self
+            .samples
+            .front()
+            .is_some_and(|sample| sample.timestamp < oldest)
+        {
+            self.samples.pop_front();
+        }
+    }
+}
+
+impl Bucket {
+    fn add(&mut self, sample: &HistorySample) {
+        self.cpu_usage_percent
+            .add(sample.cpu_usage_percent.map(f64::from));
+        self.memory_used_bytes
+            .add(sample.memory_used_bytes.map(|bytes| bytes as f64));
+        self.memory_usage_percent.add(sample.memory_usage_percent);
+        self.disk_used_bytes
+            .add(sample.disk_used_bytes.map(|bytes| bytes as f64));
+        self.disk_total_bytes
+            .add(sample.disk_total_bytes.map(|bytes| bytes as f64));
+        self.network_rx_bytes_per_sec
+            .add(sample.network_rx_bytes_per_sec.map(|rate| rate as f64));
+        self.network_tx_bytes_per_sec
+            .add(sample.network_tx_bytes_per_sec.map(|rate| rate as f64));
+    }
+
+    /// One sample at the start of the bucket.
+    fn average(&self) -> HistorySample {
+        let bytes = |mean: Mean| mean.get().map(|value| value.round() as u64);
+
+        HistorySample {
+            timestamp: self.start,
+            cpu_usage_percent: self.cpu_usage_percent.get().map(|value| value as f32),
+            memory_used_bytes: bytes(self.memory_used_bytes),
+            memory_usage_percent: self.memory_usage_percent.get(),
+            disk_used_bytes: bytes(self.disk_used_bytes),
+            disk_total_bytes: bytes(self.disk_total_bytes),
+            network_rx_bytes_per_sec: bytes(self.network_rx_bytes_per_sec),
+            network_tx_bytes_per_sec: bytes(self.network_tx_bytes_per_sec),
+        }
+    }
+}
+
+impl Mean {
+    fn add(&mut self, value: Option<f64>) {
+        if let Some(value) = value {
+            self.sum += value;
+            self.count += 1;
+        }
+    }
+
+    fn get(self) -> Option<f64> {
+        (self.count > 0).then(|| self.sum / self.count as f64)
+    }
+}
+
+impl SeriesMetric {
+    fn name(&self) -> &'static str {
+        match self {
+            SeriesMetric::Cpu => "cpu",
+            SeriesMetric::Memory => "memory",
+            SeriesMetric::Disk => "disk",
+            SeriesMetric::NetworkRx => "network_rx",
+            SeriesMetric::NetworkTx => "network_tx",
+        }
+    }
+
+    fn value(&self, sample: &HistorySample) -> Option<f64> {
+        match self {
+            SeriesMetric::Cpu => sample.cpu_usage_percent.map(f64::from),
+            SeriesMetric::Memory => sample.memory_usage_percent,
+            SeriesMetric::Disk => {
+                let total = sample.disk_total_bytes.filter(|total| *total > 0)?;
+                Some(usage_percent(sample.disk_used_bytes?, total))
+            }
+            SeriesMetric::NetworkRx => sample.network_rx_bytes_per_sec.map(|rate| rate as f64),
+            SeriesMetric::NetworkTx => sample.network_tx_bytes_per_sec.map(|rate| rate as f64),
+        }
+    }
+}
+
+/// Average `samples` into at most `points` equally sized buckets covering `from..=to`.
+///
+/// Buckets without any sample are left out rather than reported as zero.
+pub fn downsample(
+    samples: &[HistorySample],
+    metric: SeriesMetric,
+    from: i64,
+    to: i64,
+    points: usize,
+) -> Vec<SeriesPoint> {
+    let span = (to - from).max(1);
+    let points = points.max(1);
+    let bucket_width = (span as f64 / points as f64).max(1.0);
+
+    let mut buckets: Vec<(f64, usize)> = vec![(0.0, 0); points];
+    for sample in samples {
+        let Some(value) = metric.value(sample) else {
+            continue;
+        };
+        let offset = (sample.timestamp - from) as f64;
+        let index = ((offset / bucket_width) as usize).min(points - 1);
+        buckets[index].0 += value;
+        buckets[index].1 += 1;
+    }
+
+    buckets
+        .into_iter()
+        .enumerate()
+        .filter(|(_, (_, count))| *count > 0)
+        .map(|(index, (sum, count))| SeriesPoint {
+            timestamp: from + (index as f64 * bucket_width) as i64,
+            value: sum / count as f64,
+        })
+        .collect()
+}
+
+fn deserialize_flag<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+    match String::deserialize(deserializer)?.as_str() {
+        "1" | "true" => Ok(true),
+        "0" | "false" => Ok(false),
+        other => Err(serde::de::Error::custom(format!(
+            "expected `1` or `0`, got `{other}`"
+        ))),
+    }
+}
+
+/// Parse a window like `90s`, `15m`, `1h`, `1d`, or a bare number of seconds.
+pub fn parse_window(window: &str) -> Option<i64> {
+    let window = window.trim();
+    let (amount, unit) = match window.find(|c: char| !c.is_ascii_digit()) {
+        Some(index) => window.split_at(index),
+        None => (window, "s"),
+    };
+
+    let multiplier = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86_400,
+        _ => return None,
+    };
+
+    amount
+        .parse::<i64>()
+        .ok()
+        .filter(|amount| *amount > 0)?
+        .checked_mul(multiplier)
+}
+
+impl HistorySample {
+    pub fn from_raw(timestamp: i64, raw: &SystemInfoRaw) -> Self {
+        let disks = raw.disks.as_ref();
+        let network = raw.network.as_ref();
+
+        HistorySample {
+            timestamp,
+            cpu_usage_percent: raw.cpu.as_ref().map(|cpu| cpu.usage_percent),
+            memory_used_bytes: raw.memory.as_ref().map(|memory| memory.used_bytes),
+            memory_usage_percent: raw.memory.as_ref().map(|memory| memory.usage_percent),
+            disk_used_bytes: disks.map(|disks| disks.iter().map(|disk| disk.used_bytes).sum()),
+            disk_total_bytes: disks.map(|disks| disks.iter().map(|disk| disk.total_bytes).sum()),
+            network_rx_bytes_per_sec: network.and_then(|network| network.rx_bytes_per_sec),
+            network_tx_bytes_per_sec: network.and_then(|network| network.tx_bytes_per_sec),
+        }
+    }
+}
+
+pub async fn history_handler(
+    State(state): State<AppState>,
+    Query(query): Query<HistoryQuery>,
+) -> impl IntoResponse {
+    let samples = state.history(query.from, query.to);
+
+    Json(HistoryResponse { samples })
+}
+
+pub async fn series_handler(
+    State(state): State<AppState>,
+    Query(query): Query<SeriesQuery>,
+) -> Response {
+    let window_seconds = match query.window.as_deref().map(parse_window) {
+        None => DEFAULT_SERIES_WINDOW,
+        Some(Some(window)) => window,
+        Some(None) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "status": "error",
+                    "message": "Invalid window, expected something like `15m`, `1h`, or `1d`"
+                })),
+            )
+                .into_response();
+        }
+    };
+    let points = query
+        .points
+        .unwrap_or(DEFAULT_SERIES_POINTS)
+        .clamp(1, MAX_SERIES_POINTS);
+
+    let to = chrono::Utc::now().timestamp();
+    let from = to.saturating_sub(window_seconds);
+    let samples = state.history(Some(from), Some(to));
+
+    Json(SeriesResponse {
+        metric: query.metric,
+        window_seconds,
+        points: downsample(&samples, query.metric, from, to, points),
+    })
+    .into_response()
+}
+
+/// The stored samples as CSV when persistence is enabled, the in-memory ones otherwise.
+pub async fn history_csv_handler(
+    State(state): State<AppState>,
+    Query(query): Query<CsvQuery>,
+) -> Response {
+    let metric = query.metric;
+    let header = match metric {
+        Some(metric) => format!("timestamp,time,{}\n", metric.name()),
+        None => format!("{CSV_COLUMNS}\n"),
+    };
+    let body = export_body(state, query.from, query.to, false, header, move |samples| {
+        csv_rows(samples, metric)
+    });
+
+    (
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"history.csv\"",
+            ),
+        ],
+        body,
+    )
+        .into_response()
+}
+
+/// The stored samples as one JSON object per line, like `/api/v1/history.csv`.
+pub async fn history_ndjson_handler(
+    State(state): State<AppState>,
+    Query(query): Query<NdjsonQuery>,
+) -> Response {
+    let body = export_body(
+        state,
+        query.from,
+        query.to,
+        query.follow,
+        String::new(),
+        |samples| {
+            samples
+                .iter()
+                .filter_map(|sample| serde_json::to_string(sample).ok())
+                .map(|line| line + "\n")
+                .collect()
+        },
+    );
+
+    ([(header::CONTENT_TYPE, "application/x-ndjson")], body).into_response()
+}
+
+/// A response body written `EXPORT_CHUNK_ROWS` samples at a time, so a long range never sits
+/// in memory as a whole.
+///
+/// With `follow`, the body stays open and gets a sample of every new snapshot until `to`.
+fn export_body(
+    state: AppState,
+    from: Option<i64>,
+    to: Option<i64>,
+    follow: bool,
+    header: String,
+    format: impl Fn(&[HistorySample]) -> String + Send + Sync + 'static,
+) -> Body {
+    let (sender, receiver) = mpsc::channel::<std::io::Result<String>>(4);
+
+    tokio::spawn(async move {
+        // before reading the range, so no snapshot slips through between the two
+        let mut updates = follow.then(live::subscribe);
+        if !header.is_empty() && sender.send(Ok(header)).await.is_err() {
+            return;
+        }
+        let Ok(mut after) = send_range(&state, from, to, &sender, &format).await else {
+            return;
+        };
+
+        let Some(updates) = &mut updates else {
+            return;
+        };
+        loop {
+            // lagged receivers only need to know there is something new
+            if let Err(broadcast::error::RecvError::Closed) = updates.recv().await {
+                return;
+            }
+
+            let snapshot = state.latest();
+            if to.is_some_and(|to| snapshot.collected_at > to) {
+                return;
+            }
+            if after.is_some_and(|after| snapshot.collected_at <= after) {
+                continue;
+            }
+            after = Some(snapshot.collected_at);
+
+            let sample = HistorySample::from_raw(snapshot.collected_at, &snapshot.raw);
+            if sender.send(Ok(format(&[sample]))).await.is_err() {
+                return;
+            }
+        }
+    });
+
+    Body::from_stream(ReceiverStream::new(receiver))
+}
+
+/// Send the samples between `from` and `to`, the stored ones when persistence is enabled and
+/// the in-memory ones otherwise.
+///
+/// Returns the timestamp newer samples come after, `Err` once the client went away or the store
+/// failed, which cuts the response short rather than ending it like a complete one.
+async fn send_range(
+    state: &AppState,
+    from: Option<i64>,
+    to: Option<i64>,
+    sender: &mpsc::Sender<std::io::Result<String>>,
+    format: &(impl Fn(&[HistorySample]) -> String + Sync),
+) -> Result<Option<i64>, ()> {
+    let after = from.map(|from| from.saturating_sub(1));
+
+    #[cfg(feature = "sqlite")]
+    if let Some(store) = state.store() {
+        let mut after = after;
+        loop {
+            let store = store.clone();
+            let page =
+                tokio::task::spawn_blocking(move || store.page(after, to, EXPORT_CHUNK_ROWS))
+                    .await
+                    .map_err(std::io::Error::other)
+                    .and_then(|page| page.map_err(std::io::Error::other));
+            let samples = match page {
+                Ok(samples) => samples,
+                Err(err) => {
+                    tracing::error!("Failed to read stored history: {err}");
+                    let _ = sender.send(Err(err)).await;
+                    return Err(());
+                }
+            };
+            let Some(last) = samples.last() else {
+                return Ok(after);
+            };
+            after = Some(last.timestamp);
+
+            sender.send(Ok(format(&samples))).await.map_err(drop)?;
+            if samples.len() < EXPORT_CHUNK_ROWS {
+                return Ok(after);
+            }
+        }
+    }
+
+    let samples = state.history(from, to);
+    for chunk in samples.chunks(EXPORT_CHUNK_ROWS) {
+        sender.send(Ok(format(chunk))).await.map_err(drop)?;
+    }
+
+    Ok(samples.last().map(|sample| sample.timestamp).or(after))
+}
+
+/// One line per sample, unknown values are left empty.
+fn csv_rows(samples: &[HistorySample], metric: Option<SeriesMetric>) -> String {
+    fn cell(value: Option<impl std::fmt::Display>) -> String {
+        value.map(|value| value.to_string()).unwrap_or_default()
+    }
+
+    let mut rows = String::new();
+    for sample in samples {
+        let time = chrono::DateTime::from_timestamp(sample.timestamp, 0)
+            .map(|time| time.to_rfc3339_opts(chrono::SecondsFormat::Secs, true));
+        let _ = write!(rows, "{},{}", sample.timestamp, cell(time));
+        let _ = match metric {
+            Some(metric) => writeln!(rows, ",{}", cell(metric.value(sample))),
+            None => writeln!(
+                rows,
+                ",{},{},{},{},{},{},{}",
+                cell(sample.cpu_usage_percent),
+                cell(sample.memory_used_bytes),
+                cell(sample.memory_usage_percent),
+                cell(sample.disk_used_bytes),
+                cell(sample.disk_total_bytes),
+                cell(sample.network_rx_bytes_per_sec),
+                cell(sample.network_tx_bytes_per_sec),
+            ),
+        };
+    }
+
+    rows
+}
@@ -0,0 +1,240 @@
+//! Translations of the line labels and the uptime.
+//!
+//! Snapshots are rendered in English, the labels the `lines` config and the API match on.
+//! [`with_locale`] renders them again in another language for the people reading the page.
+
+use std::{borrow::Cow, cell::Cell};
+
+use serde::Deserialize;
+
+use crate::config;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    De,
+    Es,
+    Fr,
+    Id,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeUnit {
+    Day,
+    Hour,
+    Minute,
+    Second,
+}
+
+/// The labels of the built-in lines in German, Spanish, French, and Indonesian.
+///
+/// Acronyms and names like `CPU`, `RAID`, `Shell`, or `Swap` read the same in every language.
+const LABELS: [(&str, [&str; 4]); 23] = [
+    ("Battery", ["Akku", "Batería", "Batterie", "Baterai"]),
+    (
+        "Board",
+        ["Mainboard", "Placa base", "Carte mère", "Papan induk"],
+    ),
+    (
+        "Containers",
+        ["Container", "Contenedores", "Conteneurs", "Kontainer"],
+    ),
+    ("Disk", ["Datenträger", "Disco", "Disque", "Disk"]),
+    (
+        "Disk I/O",
+        ["Datenträger-E/A", "E/S de disco", "E/S disque", "I/O disk"],
+    ),
+    ("Entropy", ["Entropie", "Entropía", "Entropie", "Entropi"]),
+    (
+        "Firmware",
+        ["Firmware", "Firmware", "Micrologiciel", "Firmware"],
+    ),
+    ("Host", ["Host", "Equipo", "Hôte", "Host"]),
+    ("Kernel", ["Kernel", "Núcleo", "Noyau", "Kernel"]),
+    (
+        "Local time",
+        ["Ortszeit", "Hora local", "Heure locale", "Waktu lokal"],
+    ),
+    (
+        "Memory",
+        ["Arbeitsspeicher", "Memoria", "Mémoire", "Memori"],
+    ),
+    ("Network", ["Netzwerk", "Red", "Réseau", "Jaringan"]),
+    ("Packages", ["Pakete", "Paquetes", "Paquets", "Paket"]),
+    ("Processes", ["Prozesse", "Procesos", "Processus", "Proses"]),
+    (
+        "Public IP",
+        ["Öffentliche IP", "IP pública", "IP publique", "IP publik"],
+    ),
+    ("Services", ["Dienste", "Servicios", "Services", "Layanan"]),
+    ("Status", ["Status", "Estado", "État", "Status"]),
+    (
+        "Temps",
+        ["Temperaturen", "Temperaturas", "Températures", "Suhu"],
+    ),
+    (
+        "Throttling",
+        ["Drosselung", "Limitación", "Bridage", "Pembatasan"],
+    ),
+    (
+        "Traffic",
+        ["Datenverkehr", "Tráfico", "Trafic", "Lalu lintas"],
+    ),
+    (
+        "Uptime",
+        [
+            "Laufzeit",
+            "Tiempo activo",
+            "Temps de fonctionnement",
+            "Waktu aktif",
+        ],
+    ),
+    (
+        "Users",
+        ["Benutzer", "Usuarios", "Utilisateurs", "Pengguna"],
+    ),
+    (
+        "Virtualization",
+        [
+            "Virtualisierung",
+            "Virtualización",
+            "Virtualisation",
+            "Virtualisasi",
+        ],
+    ),
+];
+
+thread_local! {
+    static CURRENT: Cell<Locale> = const { Cell::new(Locale::En) };
+}
+
+/// Run `render` with the labels and durations in `locale`, e.g. around
+/// [`CollectorRegistry::render`](crate::collectors::CollectorRegistry::render).
+pub fn with_locale<T>(locale: Locale, render: impl FnOnce() -> T) -> T {
+    let previous = CURRENT.replace(locale);
+    let result = render();
+    CURRENT.set(previous);
+    result
+}
+
+/// The locale of the current [`with_locale`], English outside of one.
+pub fn current() -> Locale {
+    CURRENT.get()
+}
+
+/// The locale of a request, the configured one unless `Accept-Language` asks for another.
+pub fn negotiate(accept_language: Option<&str>) -> Locale {
+    let locale_config = &config::get().locale;
+    if !locale_config.accept_language {
+        return locale_config.default;
+    }
+
+    accept_language
+        .and_then(Locale::from_accept_language)
+        .unwrap_or(locale_config.default)
+}
+
+impl Locale {
+    /// The most preferred supported language of an `Accept-Language` header, e.g.
+    /// `de-CH, fr;q=0.9, en;q=0.8`.
+    pub fn from_accept_language(header: &str) -> Option<Locale> {
+        let mut languages = header
+            .split(',')
+            .filter_map(|language| {
+                let mut parts = language.split(';');
+                let tag = parts.next()?.trim();
+                let quality = parts
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .map_or(Some(1.0), |quality| quality.trim().parse::<f32>().ok())?;
+                // only the primary subtag, `de-CH` is `de`
+                let primary = tag.split('-').next()?;
+                (quality > 0.0).then_some((primary, quality))
+            })
+            .collect::<Vec<_>>();
+        // stable, so equal weights keep the order of the header
+        languages.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        languages
+            .into_iter()
+            .find_map(|(primary, _)| Locale::from_code(primary))
+    }
+
+    /// The language tag, e.g. for the `lang` attribute of the page.
+    pub fn code(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::De => "de",
+            Locale::Es => "es",
+            Locale::Fr => "fr",
+            Locale::Id => "id",
+        }
+    }
+
+    fn from_code(code: &str) -> Option<Locale> {
+        match code.to_ascii_lowercase().as_str() {
+            "en" => Some(Locale::En),
+            "de" => Some(Locale::De),
+            "es" => Some(Locale::Es),
+            "fr" => Some(Locale::Fr),
+            "id" => Some(Locale::Id),
+            _ => None,
+        }
+    }
+
+    /// `label` in this language, `Disk (/)` becomes `Datenträger (/)`, unknown ones are kept.
+    pub fn label(self, label: &str) -> Cow<'_, str> {
+        let index = match self {
+            Locale::En => return Cow::Borrowed(label),
+            Locale::De => 0,
+            Locale::Es => 1,
+            Locale::Fr => 2,
+            Locale::Id => 3,
+        };
+        // `Disk (/)`, `Battery (BAT0)`, and friends
+        let (base, suffix) = match label.find(" (") {
+            Some(at) => label.split_at(at),
+            None => (label, ""),
+        };
+
+        match LABELS.iter().find(|(english, _)| *english == base) {
+            Some((_, translations)) => Cow::Owned(format!("{}{suffix}", translations[index])),
+            None => Cow::Borrowed(label),
+        }
+    }
+
+    /// `count` of `unit`, pluralized, e.g. `3 days` or `1 Stunde`.
+    pub fn duration(self, count: u64, unit: TimeUnit) -> String {
+        let (singular, plural) = match (self, unit) {
+            (Locale::En, TimeUnit::Day) => ("day", "days"),
+            (Locale::En, TimeUnit::Hour) => ("hour", "hours"),
+            (Locale::En, TimeUnit::Minute) => ("minute", "minutes"),
+            (Locale::En, TimeUnit::Second) => ("second", "seconds"),
+            (Locale::De, TimeUnit::Day) => ("Tag", "Tage"),
+            (Locale::De, TimeUnit::Hour) => ("Stunde", "Stunden"),
+            (Locale::De, TimeUnit::Minute) => ("Minute", "Minuten"),
+            (Locale::De, TimeUnit::Second) => ("Sekunde", "Sekunden"),
+            (Locale::Es, TimeUnit::Day) => ("día", "días"),
+            (Locale::Es, TimeUnit::Hour) => ("hora", "horas"),
+            (Locale::Es, TimeUnit::Minute) => ("minuto", "minutos"),
+            (Locale::Es, TimeUnit::Second) => ("segundo", "segundos"),
+            (Locale::Fr, TimeUnit::Day) => ("jour", "jours"),
+            (Locale::Fr, TimeUnit::Hour) => ("heure", "heures"),
+            (Locale::Fr, TimeUnit::Minute) => ("minute", "minutes"),
+            (Locale::Fr, TimeUnit::Second) => ("seconde", "secondes"),
+            // Indonesian nouns don't change with the count
+            (Locale::Id, TimeUnit::Day) => ("hari", "hari"),
+            (Locale::Id, TimeUnit::Hour) => ("jam", "jam"),
+            (Locale::Id, TimeUnit::Minute) => ("menit", "menit"),
+            (Locale::Id, TimeUnit::Second) => ("detik", "detik"),
+        };
+        // French counts zero as singular too
+        let is_singular = match self {
+            Locale::Fr => count <= 1,
+            _ => count == 1,
+        };
+
+        format!("{count} {}", if is_singular { singular } else { plural })
+    }
+}
@@ -0,0 +1,280 @@
+use std::{fmt::Write, time::Duration};
+
+use axum::{
+    extract::State,
+    http::{HeaderValue, header},
+    response::IntoResponse,
+};
+
+use crate::{config, state::AppState, sysgetter::SystemInfoRaw};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A field value, integers get the `i` suffix and strings are quoted.
+enum Field {
+    Float(f64),
+    Integer(i64),
+    String(String),
+}
+
+/// Builds the points of one snapshot in line protocol.
+struct LineProtocol<'a> {
+    text: String,
+    host: &'a str,
+    /// Nanoseconds, the default precision of the write endpoints.
+    timestamp: i64,
+}
+
+/// The latest snapshot in InfluxDB line protocol, one point per measurement and device.
+pub async fn influx_handler(State(state): State<AppState>) -> impl IntoResponse {
+    let snapshot = state.latest();
+
+    (
+        [(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("text/plain; charset=utf-8"),
+        )],
+        render(&snapshot.raw, snapshot.collected_at),
+    )
+}
+
+/// Periodically write the latest snapshot to `influx.url` when one is configured.
+pub fn spawn_influx_task(state: AppState) {
+    // the task keeps the settings it started with, changing them needs a restart
+    let influx_config = config::get().influx.clone();
+    let Some(url) = influx_config.url.clone() else {
+        return;
+    };
+
+    let client = match reqwest::Client::builder().timeout(REQUEST_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(err) => {
+            tracing::error!("Failed to create the InfluxDB HTTP client: {err}");
+            return;
+        }
+    };
+    let period = Duration::from_secs(influx_config.interval.max(1));
+    tracing::info!("Writing metrics to {url} every {}s", period.as_secs());
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+
+            let snapshot = state.latest();
+            let mut request = client
+                .post(&url)
+                .header(reqwest::header::CONTENT_TYPE, "text/plain; charset=utf-8")
+                .body(render(&snapshot.raw, snapshot.collected_at));
+            if let Some(token) = &influx_config.token {
+                request = request.header(reqwest::header::AUTHORIZATION, format!("Token {token}"));
+            }
+
+            let result = request
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+            if let Err(err) = result {
+                tracing::warn!("Failed to write the metrics to {url}: {err}");
+            }
+        }
+    });
+}
+
+fn render(raw: &SystemInfoRaw, collected_at: i64) -> String {
+    let mut points = LineProtocol {
+        text: String::new(),
+        host: &raw.host,
+        timestamp: collected_at * 1_000_000_000,
+    };
+
+    if let Some(os) = &raw.os {
+        let mut fields = vec![("uptime_seconds", Field::Integer(os.uptime_seconds as i64))];
+        if let Some(users) = &raw.users {
+            fields.push(("users", Field::Integer(users.count as i64)));
+        }
+        points.point("system", &[], &fields);
+    }
+
+    if let Some(cpu) = &raw.cpu {
+        points.point(
+            "cpu",
+            &[],
+            &[
+                ("usage_percent", Field::Float(f64::from(cpu.usage_percent))),
+                ("cores", Field::Integer(cpu.cores as i64)),
+                ("frequency_mhz", Field::Integer(cpu.frequency_mhz as i64)),
+            ],
+        );
+    }
+
+    for (measurement, usage) in [("mem", &raw.memory), ("swap", &raw.swap)] {
+        if let Some(usage) = usage {
+            points.point(
+                measurement,
+                &[],
+                &[
+                    ("total_bytes", Field::Integer(usage.total_bytes as i64)),
+                    ("used_bytes", Field::Integer(usage.used_bytes as i64)),
+                    ("used_percent", Field::Float(usage.usage_percent)),
+                ],
+            );
+        }
+    }
+
+    for disk in raw.disks.iter().flatten() {
+        points.point(
+            "disk",
+            &[
+                ("mount", &disk.mount_point),
+                ("device", &disk.name),
+                ("fs", &disk.file_system),
+            ],
+            &[
+                ("total_bytes", Field::Integer(disk.total_bytes as i64)),
+                ("used_bytes", Field::Integer(disk.used_bytes as i64)),
+                (
+                    "available_bytes",
+                    Field::Integer(disk.available_bytes as i64),
+                ),
+                ("used_percent", Field::Float(disk.usage_percent)),
+            ],
+        );
+    }
+
+    for device in raw.disk_io.iter().flat_map(|disk_io| &disk_io.devices) {
+        points.point(
+            "diskio",
+            &[("device", &device.name)],
+            &[
+                ("read_bytes", Field::Integer(device.read_bytes as i64)),
+                ("write_bytes", Field::Integer(device.written_bytes as i64)),
+            ],
+        );
+    }
+
+    for interface in raw.network.iter().flat_map(|network| &network.interfaces) {
+        points.point(
+            "net",
+            &[("interface", &interface.name)],
+            &[
+                ("bytes_recv", Field::Integer(interface.rx_bytes as i64)),
+                ("bytes_sent", Field::Integer(interface.tx_bytes as i64)),
+            ],
+        );
+    }
+
+    for sensor in raw.temperatures.iter().flatten() {
+        points.point(
+            "temperature",
+            &[("sensor", &sensor.label)],
+            &[("celsius", Field::Float(f64::from(sensor.celsius)))],
+        );
+    }
+
+    for battery in raw.batteries.iter().flatten() {
+        points.point(
+            "battery",
+            &[("battery", &battery.name)],
+            &[
+                (
+                    "capacity_percent",
+                    Field::Integer(i64::from(battery.capacity_percent)),
+                ),
+                ("status", Field::String(battery.status.clone())),
+            ],
+        );
+    }
+
+    if let Some(containers) = &raw.containers {
+        points.point(
+            "containers",
+            &[],
+            &[
+                ("running", Field::Integer(containers.running as i64)),
+                ("total", Field::Integer(containers.total as i64)),
+            ],
+        );
+    }
+
+    if let Some(services) = &raw.services {
+        points.point(
+            "systemd",
+            &[],
+            &[("failed_units", Field::Integer(services.failed as i64))],
+        );
+    }
+
+    for array in raw.raid.iter().flatten() {
+        points.point(
+            "raid",
+            &[("array", &array.name)],
+            &[
+                ("devices", Field::Integer(i64::from(array.devices_total))),
+                (
+                    "devices_active",
+                    Field::Integer(i64::from(array.devices_active)),
+                ),
+                ("state", Field::String(array.state.clone())),
+            ],
+        );
+    }
+
+    points.text
+}
+
+impl LineProtocol<'_> {
+    /// Append a point tagged with the host, tags with an empty value are left out.
+    ///
+    /// A point needs at least one field, so it's skipped when all of them are left out.
+    fn point(&mut self, measurement: &str, tags: &[(&str, &String)], fields: &[(&str, Field)]) {
+        let mut field_set = String::new();
+        for (key, value) in fields {
+            let value = match value {
+                Field::Float(value) if value.is_finite() => value.to_string(),
+                // line protocol has no NaN or infinity
+                Field::Float(_) => continue,
+                Field::Integer(value) => format!("{value}i"),
+                Field::String(value) => format!("\"{}\"", escape(value, "\"\\")),
+            };
+            if !field_set.is_empty() {
+                field_set.push(',');
+            }
+            let _ = write!(field_set, "{}={value}", escape(key, ",= "));
+        }
+        if field_set.is_empty() {
+            return;
+        }
+
+        let _ = write!(
+            self.text,
+            "{},host={}",
+            escape(measurement, ", "),
+            escape(self.host, ",= ")
+        );
+        for (key, value) in tags.iter().filter(|(_, value)| !value.is_empty()) {
+            let _ = write!(
+                self.text,
+                ",{}={}",
+                escape(key, ",= "),
+                escape(value, ",= ")
+            );
+        }
+
+        let _ = writeln!(self.text, " {field_set} {}", self.timestamp);
+    }
+}
+
+/// Backslash escape the `special` characters.
+fn escape(text: &str, special: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        if special.contains(ch) {
+            escaped.push('\\');
+        }
+        escaped.push(ch);
+    }
+    escaped
+}
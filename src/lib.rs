@@ -0,0 +1,24 @@
+//! The system information collection behind helios, usable without its HTTP server.
+//!
+//! [`collectors::registry`] runs the collectors enabled in the active [`config`] and renders their
+//! results into the lines helios displays:
+//!
+//! ```no_run
+//! # async fn run() {
+//! // optional, the defaults are used when the config is never initialized
+//! helios::config::init(helios::config::Config::default());
+//!
+//! let registry = helios::collectors::registry();
+//! let raw = registry.collect().await;
+//! println!("{}", registry.render(&raw).as_text_info(false));
+//! # }
+//! ```
+//!
+//! [`sysgetter::SystemInfoRaw`] holds the structured values, and a custom set of collectors can
+//! be built with [`collectors::CollectorRegistry::new`] and `register`.
+
+pub mod collectors;
+pub mod config;
+pub mod i18n;
+pub mod logo;
+pub mod sysgetter;
@@ -0,0 +1,139 @@
+use std::{convert::Infallible, sync::LazyLock, time::Duration};
+
+use axum::{
+    extract::{Query, State},
+    http::HeaderMap,
+    response::{
+        IntoResponse,
+        sse::{Event, KeepAlive, Sse},
+    },
+};
+use tokio::{
+    sync::{Notify, broadcast},
+    time::{Instant, Interval},
+};
+use tokio_stream::{StreamExt, wrappers::BroadcastStream};
+
+use crate::{
+    alerts::Alerts,
+    config::{self, DurationStyle},
+    heartbeat::Heartbeat,
+    i18n::Locale,
+    notify,
+    state::{self, AppState, DurationQuery, Snapshot},
+    statsd::Statsd,
+    sysgetter::SystemInfo,
+};
+
+/// How often the background task collects and broadcasts a fresh snapshot.
+pub fn refresh_interval() -> Duration {
+    Duration::from_secs(config::get().refresh_interval.max(1))
+}
+
+/// Wakes the refresh task up to pick up the interval, alert rules, statsd, and heartbeat
+/// settings of a reloaded config.
+static CONFIG_RELOADED: Notify = Notify::const_new();
+
+static SYSTEM_INFO_CHANNEL: LazyLock<broadcast::Sender<SystemInfo>> =
+    LazyLock::new(|| broadcast::channel(4).0);
+
+/// Keep refreshing the snapshot in `state` in the background.
+///
+/// Request handlers only read the latest snapshot, they never collect themselves.
+/// The systemd watchdog is pinged from here too, so a stuck refresh gets helios restarted.
+pub fn spawn_refresh_task(state: AppState) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(refresh_interval());
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        // the first tick completes immediately, and `AppState::new` just collected
+        interval.tick().await;
+
+        let mut watchdog = notify::watchdog_interval().map(tokio::time::interval);
+        let mut alerts = Alerts::from_config();
+        let mut statsd = Statsd::from_config();
+        let mut heartbeat = Heartbeat::from_config();
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {}
+                Some(_) = tick(watchdog.as_mut()) => {
+                    notify::watchdog();
+                    continue;
+                }
+                _ = CONFIG_RELOADED.notified() => {
+                    if interval.period() != refresh_interval() {
+                        interval = tokio::time::interval_at(
+                            Instant::now() + refresh_interval(),
+                            refresh_interval(),
+                        );
+                        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                    }
+                    alerts = Alerts::from_config();
+                    statsd = Statsd::from_config();
+                    heartbeat = Heartbeat::from_config();
+                    // and refresh right away, so the displayed lines follow the new config
+                }
+            }
+
+            let snapshot = Snapshot::collect().await;
+            if let Some(alerts) = &mut alerts {
+                alerts.evaluate(&snapshot.raw);
+            }
+            if let Some(statsd) = &statsd {
+                statsd.send(&snapshot.raw);
+            }
+            if let Some(heartbeat) = &mut heartbeat {
+                heartbeat.ping(&snapshot.info);
+            }
+            let system_info = snapshot.info.clone();
+            state.replace(snapshot);
+
+            // only fails when nobody is subscribed
+            let _ = SYSTEM_INFO_CHANNEL.send(system_info);
+        }
+    });
+}
+
+/// Apply a reloaded config to the refresh task.
+pub fn config_reloaded() {
+    CONFIG_RELOADED.notify_one();
+}
+
+/// Tick an optional interval, `None` right away when there is none, which disables the `select!` branch.
+async fn tick(interval: Option<&mut Interval>) -> Option<Instant> {
+    Some(interval?.tick().await)
+}
+
+/// Subscribe to the live system info updates.
+pub fn subscribe() -> broadcast::Receiver<SystemInfo> {
+    SYSTEM_INFO_CHANNEL.subscribe()
+}
+
+pub async fn events(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(duration_query): Query<DurationQuery>,
+) -> impl IntoResponse {
+    let locale = state::request_locale(&headers);
+    let durations = duration_query
+        .duration
+        .unwrap_or(config::get().durations.html);
+    let stream = BroadcastStream::new(subscribe()).filter_map(move |message| {
+        // lagged receivers simply skip the snapshots they missed
+        let system_info = message.ok()?;
+        // the broadcast lines are English and full, the latest snapshot is the one they came from
+        let system_info = if locale == Locale::En && durations == DurationStyle::Full {
+            system_info
+        } else {
+            state.latest().info_in(locale, durations)
+        };
+        let event = Event::default()
+            .event("system-info")
+            .json_data(&system_info)
+            .ok()?;
+
+        Some(Ok::<_, Infallible>(event))
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
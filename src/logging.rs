@@ -0,0 +1,129 @@
+use std::time::Duration;
+
+use axum::{
+    Router,
+    body::Body,
+    extract::{MatchedPath, Request},
+    http::Response,
+};
+use tower_http::trace::TraceLayer;
+use tracing::{Level, Span};
+use tracing_subscriber::{EnvFilter, Layer, layer::SubscriberExt, util::SubscriberInitExt};
+
+use crate::{
+    access,
+    config::{self, LogFormat},
+};
+
+/// Emit an event or span at a level only known at runtime, the tracing macros need a constant.
+macro_rules! dyn_level {
+    ($level:expr, $macro_error:ident, $macro_warn:ident, $macro_info:ident, $macro_debug:ident, $macro_trace:ident, $($args:tt)*) => {
+        match $level {
+            Level::ERROR => tracing::$macro_error!($($args)*),
+            Level::WARN => tracing::$macro_warn!($($args)*),
+            Level::INFO => tracing::$macro_info!($($args)*),
+            Level::DEBUG => tracing::$macro_debug!($($args)*),
+            Level::TRACE => tracing::$macro_trace!($($args)*),
+        }
+    };
+}
+
+/// Install the global subscriber, `RUST_LOG` takes precedence over the configured level.
+pub fn init(log_level: &str, log_format: LogFormat) {
+    let filter = EnvFilter::try_from_default_env()
+        .or_else(|_| EnvFilter::try_new(log_level))
+        .unwrap_or_else(|err| {
+            eprintln!("Ignoring invalid log level `{log_level}`: {err}");
+            EnvFilter::new("info")
+        });
+
+    // the filter only applies to the log, the traced spans are recorded at every level
+    let registry = tracing_subscriber::registry();
+    #[cfg(feature = "otel")]
+    let registry = registry.with(crate::otel::trace_layer());
+
+    let layer = tracing_subscriber::fmt::layer();
+    match log_format {
+        LogFormat::Text => registry.with(layer.with_filter(filter)).init(),
+        // the request fields end up in `span`, the status and latency at the top level
+        LogFormat::Json => registry
+            .with(
+                layer
+                    .json()
+                    .flatten_event(true)
+                    .with_current_span(true)
+                    .with_span_list(false)
+                    .with_filter(filter),
+            )
+            .init(),
+    }
+}
+
+/// Log every request with its method, route, path, status, latency, and client IP.
+///
+/// Nothing is logged when `access_log_level` is `off`.
+pub fn with_request_logging(app: Router) -> Router {
+    let Some(level) = access_log_level() else {
+        return app;
+    };
+
+    app.layer(
+        TraceLayer::new_for_http()
+            .make_span_with(move |request: &Request| {
+                let client_ip = access::client_ip(request)
+                    .map(|ip| ip.to_string())
+                    .unwrap_or_default();
+                // the route pattern like `/api/v1/info`, unmatched requests fall back to the path
+                let route = request
+                    .extensions()
+                    .get::<MatchedPath>()
+                    .map_or(request.uri().path(), MatchedPath::as_str);
+
+                dyn_level!(
+                    level,
+                    error_span,
+                    warn_span,
+                    info_span,
+                    debug_span,
+                    trace_span,
+                    "request",
+                    method = %request.method(),
+                    route = %route,
+                    path = %request.uri().path(),
+                    client_ip = %client_ip,
+                )
+            })
+            .on_request(())
+            .on_response(
+                move |response: &Response<Body>, latency: Duration, _: &Span| {
+                    dyn_level!(
+                        level,
+                        error,
+                        warn,
+                        info,
+                        debug,
+                        trace,
+                        status = response.status().as_u16(),
+                        latency_ms = latency.as_micros() as f64 / 1000.0,
+                        "finished request"
+                    )
+                },
+            )
+            .on_failure(()),
+    )
+}
+
+fn access_log_level() -> Option<Level> {
+    let access_log_level = &config::get().access_log_level;
+    if access_log_level.eq_ignore_ascii_case("off") {
+        return None;
+    }
+
+    match access_log_level.parse() {
+        Ok(level) => Some(level),
+        Err(_) => {
+            tracing::warn!("Ignoring invalid access log level `{access_log_level}`, using `info`");
+            Some(Level::INFO)
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use std::sync::LazyLock;
+
+use sysinfo::System;
+
+use crate::config;
+
+/// ASCII art of the detected distro, `None` when disabled in the config.
+pub static LOGO: LazyLock<Option<&'static Logo>> = LazyLock::new(|| {
+    let logo_config = &config::get().logo;
+    if !logo_config.enabled {
+        return None;
+    }
+
+    let distro = logo_config
+        .distro
+        .clone()
+        .unwrap_or_else(System::distribution_id);
+    Some(find_logo(&distro))
+});
+
+/// A small neofetch-style distro logo.
+#[derive(Debug)]
+pub struct Logo {
+    /// Matched against `System::distribution_id()` or the `logo.distro` config.
+    ids: &'static [&'static str],
+    color: (u8, u8, u8),
+    art: &'static str,
+}
+
+static LOGOS: [Logo; 7] = [
+    Logo {
+        ids: &["arch", "archarm", "endeavouros", "manjaro"],
+        color: (0x17, 0x93, 0xd1),
+        art: r#"      /\
+     /  \
+    /\   \
+   /      \
+  /   ,,   \
+ /   |  |  -\
+/_-''    ''-_\"#,
+    },
+    Logo {
+        ids: &["debian", "raspbian"],
+        color: (0xd7, 0x0a, 0x53),
+        art: r#"  _____
+ /  __ \
+|  /    |
+|  \___-
+-_
+  --_"#,
+    },
+    Logo {
+        ids: &["ubuntu", "pop", "linuxmint"],
+        color: (0xe9, 0x54, 0x20),
+        art: r#"         _
+     ---(_)
+ _/  ---  \
+(_) |   |
+  \  --- _/
+     ---(_)"#,
+    },
+    Logo {
+        ids: &["fedora", "rhel", "centos", "rocky", "almalinux"],
+        color: (0x51, 0xa2, 0xda),
+        art: r#"      _____
+     /   __)\
+     |  /  \ \
+  ___|  |__/ /
+ / (_    _)_/
+/ /  |  |
+\ \__/  |
+ \(_____/"#,
+    },
+    Logo {
+        ids: &["alpine"],
+        color: (0x0d, 0x59, 0x7f),
+        art: r#"   /\ /\
+  // \  \
+ //   \  \
+///    \  \
+//      \  \
+         \"#,
+    },
+    Logo {
+        ids: &["macos", "darwin"],
+        color: (0xac, 0xac, 0xfc),
+        art: r#"        .:'
+    __ :'__
+ .'`  `-'  ``.
+:          .-'
+:         :
+ :         `-;
+  `.__.-.__.'"#,
+    },
+    // generic tux, used when nothing else matches
+    Logo {
+        ids: &["linux"],
+        color: (0xe3, 0xb3, 0x54),
+        art: r#"    ___
+   (.. |
+   (<> |
+  / __  \
+ ( /  \ /|
+_/\ __)/_)
+\/-____\/"#,
+    },
+];
+
+impl Logo {
+    pub fn lines(&self) -> impl Iterator<Item = &'static str> {
+        self.art.lines()
+    }
+
+    /// Width of the widest line, in characters.
+    pub fn width(&self) -> usize {
+        self.lines()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn art(&self) -> &'static str {
+        self.art
+    }
+
+    pub fn ansi_color(&self) -> String {
+        let (r, g, b) = self.color;
+        format!("\x1b[1;38;2;{r};{g};{b}m")
+    }
+
+    pub fn css_color(&self) -> String {
+        let (r, g, b) = self.color;
+        format!("#{r:02x}{g:02x}{b:02x}")
+    }
+}
+
+/// Logo for a distribution id, falling back to the generic Linux one.
+fn find_logo(distro: &str) -> &'static Logo {
+    let distro = distro.trim().to_ascii_lowercase();
+    LOGOS
+        .iter()
+        .find(|logo| logo.ids.contains(&distro.as_str()))
+        .unwrap_or(&LOGOS[LOGOS.len() - 1])
+}
@@ -1,29 +1,119 @@
+use std::net::SocketAddr;
+
 use axum::{
     Json, Router,
-    http::HeaderValue,
-    response::{Html, IntoResponse},
+    extract::{Query, State},
+    http::{HeaderMap, HeaderValue, StatusCode},
+    response::{Html, IntoResponse, Response},
 };
-use std::sync::OnceLock;
+use clap::Parser;
+use serde::{Deserialize, Serialize};
 use tokio::net::TcpListener;
 
-use crate::sysgetter::{SystemInfo, get_system_info_by_lines_unlocked};
+// the collection lives in the library, see `lib.rs`
+use helios::{collectors, config, i18n, logo, sysgetter};
+
+use crate::cli::Cli;
+use crate::config::{Config, Theme};
+use crate::state::{AppState, DurationQuery};
 
-mod sysgetter;
+mod access;
+mod alerts;
+mod assets;
+mod auth;
+mod badge;
+mod card;
+mod cli;
+mod cors;
+mod federation;
+mod fields;
+#[cfg(feature = "graphql")]
+mod graphql;
+mod health;
+mod heartbeat;
+mod history;
+mod influx;
+mod live;
+mod logging;
+mod manifest;
+mod metrics;
+mod notify;
+mod openapi;
+#[cfg(feature = "otel")]
+mod otel;
+#[cfg(feature = "sqlite")]
+mod persistence;
+mod processes;
+mod push;
+#[cfg(unix)]
+mod reload;
+mod robots;
+mod startup;
+mod state;
+mod statsd;
+mod templates;
+mod tls;
+mod ws;
 
-const HELIOS_IMAGE: &[u8; 57693] = include_bytes!("../assets/helios.png");
-const HELIOS_BANNER: &[u8; 38773] = include_bytes!("../assets/helios-img.png");
-const HELIOS_BANNER_WEBP: &[u8; 35086] = include_bytes!("../assets/helios-img.webp");
+const HELIOS_IMAGE: &[u8] = include_bytes!("../assets/helios.png");
+const HELIOS_BANNER: &[u8] = include_bytes!("../assets/helios-img.png");
+const HELIOS_BANNER_WEBP: &[u8] = include_bytes!("../assets/helios-img.webp");
 const HELIOS_JS: &str = include_str!("../assets/scriptlet.js");
 const HELIOS_CSS: &str = include_str!("../assets/style.css");
-const HELIOS_HTML: &str = include_str!("../assets/index.html");
-
-static FIRST_TIME_DATA: OnceLock<SystemInfo> = OnceLock::new();
+const HELIOS_LIGHT_CSS: &str = include_str!("../assets/theme-light.css");
 
 #[tokio::main]
 async fn main() {
-    let port_at = std::env::var("PORT").unwrap_or_else(|_| "7889".to_string());
+    let cli = Cli::parse();
+    if cli.hash_password {
+        print_password_hash();
+        return;
+    }
+
+    let mut config = match Config::load(cli.config.clone()) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("Invalid configuration: {err}");
+            std::process::exit(1);
+        }
+    };
+    cli.apply(&mut config);
+    if let Err(err) = auth::validate(&config.auth) {
+        eprintln!("Invalid configuration: {err}");
+        std::process::exit(1);
+    }
 
-    let app: Router = Router::new()
+    let bind_at = config.socket_addr();
+    let tls_config = config.tls.clone();
+    config::init(config);
+    logging::init(&config::get().log_level, config::get().log_format);
+    #[cfg(unix)]
+    reload::spawn_reload_task(cli);
+
+    let state = AppState::new().await;
+    startup::spawn_startup_task(&state);
+    live::spawn_refresh_task(state.clone());
+    federation::spawn_federation_task(state.clone());
+    push::spawn_push_task(state.clone());
+    influx::spawn_influx_task(state.clone());
+    #[cfg(feature = "sqlite")]
+    persistence::spawn_persist_task(state.clone());
+    #[cfg(not(feature = "sqlite"))]
+    if config::get().persistence.path.is_some() {
+        tracing::warn!(
+            "Ignoring `persistence.path`, helios was built without the `sqlite` feature"
+        );
+    }
+    #[cfg(feature = "otel")]
+    otel::spawn_otel_task(state.clone());
+    #[cfg(not(feature = "otel"))]
+    if config::get().otel.endpoint.is_some() || config::get().otel.traces_endpoint.is_some() {
+        tracing::warn!(
+            "Ignoring `otel.endpoint` and `otel.traces_endpoint`, helios was built without the `otel` feature"
+        );
+    }
+
+    let app: Router<AppState> = Router::new()
         .route("/", axum::routing::get(root))
         .route("/assets/helios.png", axum::routing::get(helios_image))
         .route(
@@ -34,95 +124,584 @@ async fn main() {
             "/assets/helios-img.webp",
             axum::routing::get(helios_image_banner_webp),
         )
+        .route("/favicon.ico", axum::routing::get(manifest::favicon))
+        .route(
+            "/assets/icons/icon-32.png",
+            axum::routing::get(manifest::icon_32),
+        )
+        .route(
+            "/assets/icons/icon-180.png",
+            axum::routing::get(manifest::icon_180),
+        )
+        .route(
+            "/assets/icons/icon-192.png",
+            axum::routing::get(manifest::icon_192),
+        )
+        .route("/manifest.json", axum::routing::get(manifest::manifest))
+        .route("/robots.txt", axum::routing::get(robots::robots_txt))
+        .route(
+            "/.well-known/security.txt",
+            axum::routing::get(robots::security_txt),
+        )
         .route("/assets/scriptlet.js", axum::routing::get(helios_js))
         .route("/assets/style.css", axum::routing::get(helios_css))
-        .route("/__heartbeat__", axum::routing::get(status))
-        .route("/s", axum::routing::get(update_status));
+        .route(
+            "/assets/theme-light.css",
+            axum::routing::get(helios_light_css),
+        )
+        .route("/assets/custom.css", axum::routing::get(custom_css))
+        .route("/txt", axum::routing::get(text_status))
+        .route("/md", axum::routing::get(markdown_status))
+        .route("/badge/{file}", axum::routing::get(badge::badge_handler))
+        .route("/card.png", axum::routing::get(card::card_handler))
+        .route("/events", axum::routing::get(live::events))
+        .route("/ws", axum::routing::get(ws::ws_handler));
+
+    // machine-readable endpoints, the ones external dashboards may fetch cross-origin
+    let api: Router<AppState> = Router::new()
+        .route("/livez", axum::routing::get(health::livez))
+        .route("/readyz", axum::routing::get(health::readyz))
+        // kept for monitors set up before the probes existed
+        .route("/__heartbeat__", axum::routing::get(health::readyz))
+        .route("/metrics", axum::routing::get(metrics::metrics_handler))
+        .route("/influx", axum::routing::get(influx::influx_handler))
+        // the display lines before the API was versioned, see `deprecated_status`
+        .route("/s", axum::routing::get(deprecated_status))
+        .route("/api/docs", axum::routing::get(openapi::explorer_handler))
+        .route(
+            "/api/v1/openapi.json",
+            axum::routing::get(openapi::openapi_handler),
+        )
+        .route("/api/v1/lines", axum::routing::get(update_status))
+        .route("/api/v1/info", axum::routing::get(structured_status))
+        .route("/api/v1/info/delta", axum::routing::get(info_delta))
+        .route("/api/v1/cpu", axum::routing::get(cpu_usage))
+        .route("/api/v1/self", axum::routing::get(self_usage))
+        .route("/api/v1/os", axum::routing::get(os_status))
+        .route("/api/v1/memory", axum::routing::get(memory_status))
+        .route("/api/v1/disks", axum::routing::get(disks_status))
+        .route("/api/v1/network", axum::routing::get(network_status))
+        .route("/api/v1/monitors", axum::routing::get(monitors_status))
+        .route(
+            "/api/v1/monitors/http",
+            axum::routing::get(http_monitors_status),
+        )
+        .route("/api/v1/interfaces", axum::routing::get(interfaces))
+        .route(
+            "/api/v1/history",
+            axum::routing::get(history::history_handler),
+        )
+        .route(
+            "/api/v1/history.csv",
+            axum::routing::get(history::history_csv_handler),
+        )
+        .route(
+            "/api/v1/history.ndjson",
+            axum::routing::get(history::history_ndjson_handler),
+        )
+        .route(
+            "/api/v1/series",
+            axum::routing::get(history::series_handler),
+        )
+        .route("/api/v1/containers", axum::routing::get(containers))
+        .route(
+            "/api/v1/peers",
+            axum::routing::get(federation::peers_handler),
+        )
+        .route("/api/v1/push", axum::routing::post(push::push_handler))
+        .route(
+            "/api/v1/services/failed",
+            axum::routing::get(failed_services),
+        )
+        .route(
+            "/api/v1/processes",
+            axum::routing::get(processes::processes_handler),
+        );
+    #[cfg(feature = "sqlite")]
+    let api = api.route(
+        "/api/v1/history/stored",
+        axum::routing::get(persistence::stored_history_handler),
+    );
+    #[cfg(feature = "graphql")]
+    let api = api.route("/graphql", axum::routing::post(graphql::graphql_handler));
+    let api = api.layer(axum::middleware::from_fn(auth::api_auth));
+    let api = match cors::layer(&config::get().cors) {
+        Some(cors) => api.layer(cors),
+        None => api,
+    };
+
+    // checked before the credentials, a denied client doesn't get to try any
+    let api = api.layer(axum::middleware::from_fn(access::api_access));
+
+    let app = app
+        .layer(axum::middleware::from_fn(auth::basic_auth))
+        .layer(axum::middleware::from_fn(access::page_access))
+        .merge(api);
+    let base_path = &config::get().base_path;
+    let app = if base_path.is_empty() {
+        app
+    } else {
+        // `nest` only matches the prefix without the trailing slash, proxies usually add one
+        Router::new().nest(base_path, app).route(
+            &format!("{base_path}/"),
+            axum::routing::get(root)
+                .layer(axum::middleware::from_fn(auth::basic_auth))
+                .layer(axum::middleware::from_fn(access::page_access)),
+        )
+    };
+    let app = app
+        .with_state(state)
+        .layer(axum::middleware::from_fn(metrics::track_requests));
+    #[cfg(feature = "otel")]
+    let app = otel::with_request_tracing(app);
+    let app = logging::with_request_logging(app);
 
     // run it
-    let listener = TcpListener::bind(format!("127.0.0.1:{port_at}"))
-        .await
-        .unwrap();
-    println!("Listening on http://127.0.0.1:{port_at}");
-    axum::serve(listener, app).await.unwrap()
+    if tls_config.is_enabled() {
+        if let Err(err) = tls::serve(app, bind_at, &tls_config).await {
+            tracing::error!("Failed to start HTTPS server: {err}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let listener = TcpListener::bind(bind_at).await.unwrap();
+    tracing::info!("Listening on http://{bind_at}");
+    notify::ready();
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    .unwrap()
+}
+
+fn print_password_hash() {
+    let mut password = String::new();
+    if let Err(err) = std::io::stdin().read_line(&mut password) {
+        eprintln!("Failed to read the password: {err}");
+        std::process::exit(1);
+    }
+
+    let password = password.trim_end_matches(['\r', '\n']);
+    if password.is_empty() {
+        eprintln!("The password cannot be empty");
+        std::process::exit(1);
+    }
+
+    match auth::hash_password(password) {
+        Ok(hash) => println!("{hash}"),
+        Err(err) => {
+            eprintln!("Failed to hash the password: {err}");
+            std::process::exit(1);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ColorMode {
+    Never,
+    Always,
+    /// Colored only for terminal clients like curl.
+    #[default]
+    Auto,
 }
 
-async fn root() -> impl IntoResponse {
-    // get the first time data if it exists
-    let first_time_data = FIRST_TIME_DATA.get_or_init(get_system_info_by_lines_unlocked);
+#[derive(Debug, Default, Deserialize)]
+struct TextQuery {
+    #[serde(default)]
+    color: ColorMode,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ThemeQuery {
+    theme: Option<Theme>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeltaQuery {
+    /// `collected_at` of the last response the client has.
+    since: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+struct DeltaResponse {
+    collected_at: i64,
+    /// Every line is in `changed`, the client should drop the lines it has.
+    full: bool,
+    #[serde(flatten)]
+    delta: sysgetter::SystemInfoDelta,
+}
+
+async fn root(
+    state: State<AppState>,
+    headers: HeaderMap,
+    query: Query<TextQuery>,
+    Query(theme_query): Query<ThemeQuery>,
+    duration_query: Query<DurationQuery>,
+) -> Response {
+    if accepts_markdown(&headers) {
+        return markdown_status(state, headers, duration_query)
+            .await
+            .into_response();
+    }
+    if is_terminal_client(&headers) {
+        return text_status(state, headers, query, duration_query)
+            .await
+            .into_response();
+    }
 
-    Html(HELIOS_HTML.replace("{{first_time_html}}", &first_time_data.as_html_info()))
+    let theme = theme_query.theme.unwrap_or(config::get().theme.default);
+    let locale = state::request_locale(&headers);
+    let durations = duration_query
+        .duration
+        .unwrap_or(config::get().durations.html);
+    let snapshot = state.latest();
+    let info = snapshot.info_in(locale, durations);
+    let services = snapshot.raw.http_monitors.as_deref().unwrap_or_default();
+    match templates::render_index(&info, services, &state.peers(), theme, locale, durations) {
+        Ok(html) => Html(html).into_response(),
+        Err(err) => {
+            tracing::error!("Failed to render the page: {err}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
 }
 
-async fn helios_image() -> impl IntoResponse {
-    // server the helios image with content type
+/// Command line HTTP clients get the plaintext version instead of the HTML page.
+fn is_terminal_client(headers: &HeaderMap) -> bool {
+    const TERMINAL_AGENTS: [&str; 4] = ["curl/", "wget/", "httpie/", "xh/"];
+
+    headers
+        .get(axum::http::header::USER_AGENT)
+        .and_then(|agent| agent.to_str().ok())
+        .map(|agent| agent.to_ascii_lowercase())
+        .is_some_and(|agent| {
+            TERMINAL_AGENTS
+                .iter()
+                .any(|prefix| agent.starts_with(prefix))
+        })
+}
+
+/// Scripts can ask for the Markdown version of the page with `Accept: text/markdown`.
+fn accepts_markdown(headers: &HeaderMap) -> bool {
+    headers
+        .get(axum::http::header::ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .is_some_and(|accept| {
+            accept.split(',').any(|media_type| {
+                media_type.split(';').next().is_some_and(|media_type| {
+                    media_type.trim().eq_ignore_ascii_case("text/markdown")
+                })
+            })
+        })
+}
+
+async fn text_status(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<TextQuery>,
+    Query(duration_query): Query<DurationQuery>,
+) -> impl IntoResponse {
+    let durations = duration_query
+        .duration
+        .unwrap_or(config::get().durations.text);
+    let system_info = state
+        .latest()
+        .info_in(state::request_locale(&headers), durations);
+    let ansi = match query.color {
+        ColorMode::Never => false,
+        ColorMode::Always => true,
+        ColorMode::Auto => is_terminal_client(&headers),
+    };
+
     (
         [(
             axum::http::header::CONTENT_TYPE,
-            HeaderValue::from_static("image/png"),
+            HeaderValue::from_static("text/plain; charset=utf-8"),
         )],
-        HELIOS_IMAGE.to_vec(),
+        system_info.as_text_info(ansi),
     )
-        .into_response()
 }
 
-async fn helios_image_banner() -> impl IntoResponse {
-    // server the helios image with content type
+async fn markdown_status(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(duration_query): Query<DurationQuery>,
+) -> impl IntoResponse {
+    let durations = duration_query
+        .duration
+        .unwrap_or(config::get().durations.text);
     (
         [(
             axum::http::header::CONTENT_TYPE,
-            HeaderValue::from_static("image/png"),
+            HeaderValue::from_static("text/markdown; charset=utf-8"),
         )],
-        HELIOS_BANNER.to_vec(),
+        state
+            .latest()
+            .info_in(state::request_locale(&headers), durations)
+            .as_markdown(),
     )
-        .into_response()
 }
 
-async fn helios_image_banner_webp() -> impl IntoResponse {
-    // server the helios image with content type
-    (
-        [(
-            axum::http::header::CONTENT_TYPE,
-            HeaderValue::from_static("image/webp"),
-        )],
-        HELIOS_BANNER_WEBP.to_vec(),
+async fn helios_image(headers: HeaderMap) -> Response {
+    assets::serve_embedded(&headers, "image/png", "helios.png", HELIOS_IMAGE)
+}
+
+async fn helios_image_banner(headers: HeaderMap) -> Response {
+    assets::serve_embedded(&headers, "image/png", "helios-img.png", HELIOS_BANNER)
+}
+
+async fn helios_image_banner_webp(headers: HeaderMap) -> Response {
+    assets::serve_embedded(
+        &headers,
+        "image/webp",
+        "helios-img.webp",
+        HELIOS_BANNER_WEBP,
     )
-        .into_response()
 }
 
-async fn helios_js() -> impl IntoResponse {
-    // server the helios js with content type
-    (
-        [(
-            axum::http::header::CONTENT_TYPE,
-            HeaderValue::from_static("text/javascript"),
-        )],
-        HELIOS_JS.to_string(),
+async fn helios_js(headers: HeaderMap) -> Response {
+    assets::serve_embedded(
+        &headers,
+        "text/javascript",
+        "scriptlet.js",
+        HELIOS_JS.as_bytes(),
+    )
+}
+
+async fn helios_css(headers: HeaderMap) -> Response {
+    assets::serve_embedded(&headers, "text/css", "style.css", HELIOS_CSS.as_bytes())
+}
+
+async fn helios_light_css(headers: HeaderMap) -> Response {
+    assets::serve_embedded(
+        &headers,
+        "text/css",
+        "theme-light.css",
+        HELIOS_LIGHT_CSS.as_bytes(),
     )
-        .into_response()
 }
 
-async fn helios_css() -> impl IntoResponse {
-    // server the helios css with content type
+/// The user supplied stylesheet of the `custom` theme, read from disk so edits show up immediately.
+async fn custom_css(headers: HeaderMap) -> Response {
+    let Some(path) = &config::get().theme.custom_css else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    match tokio::fs::read(path).await {
+        Ok(css) => assets::serve(&headers, "text/css", css, assets::REVALIDATE_CACHE_CONTROL),
+        Err(err) => {
+            tracing::warn!("Failed to read {}: {err}", path.display());
+            StatusCode::NOT_FOUND.into_response()
+        }
+    }
+}
+
+async fn update_status(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(duration_query): Query<DurationQuery>,
+) -> Response {
+    let snapshot = state.latest();
+    let durations = duration_query
+        .duration
+        .unwrap_or(config::get().durations.json);
+    let info = snapshot.info_in(state::request_locale(&headers), durations);
+    assets::serve_snapshot(&headers, &info, snapshot.collected_at)
+}
+
+/// Lines that changed since the snapshot collected at `since`.
+///
+/// Everything is sent when `since` is missing, older than the kept snapshots, or another host.
+async fn info_delta(
+    State(state): State<AppState>,
+    Query(query): Query<DeltaQuery>,
+) -> impl IntoResponse {
+    let latest = state.latest();
+    let previous = query
+        .since
+        .and_then(|since| state.info_at(since))
+        .filter(|previous| previous.host() == latest.info.host());
+
+    let full = previous.is_none();
+    let previous = previous
+        .unwrap_or_else(|| sysgetter::SystemInfo::new(latest.info.host().to_string(), vec![]));
+    Json(DeltaResponse {
+        collected_at: latest.collected_at,
+        full,
+        delta: latest.info.diff(&previous),
+    })
+}
+
+/// `/s`, the same as `/api/v1/lines` but pointing scrapers to it with the deprecation headers.
+async fn deprecated_status(
+    state: State<AppState>,
+    headers: HeaderMap,
+    duration_query: Query<DurationQuery>,
+) -> impl IntoResponse {
+    let successor = format!(
+        "<{}/api/v1/lines>; rel=\"successor-version\"",
+        config::get().base_path
+    );
+
     (
-        [(
-            axum::http::header::CONTENT_TYPE,
-            HeaderValue::from_static("text/css"),
-        )],
-        HELIOS_CSS.to_string(),
+        [
+            (
+                axum::http::header::HeaderName::from_static("deprecation"),
+                HeaderValue::from_static("true"),
+            ),
+            (
+                axum::http::header::LINK,
+                HeaderValue::from_str(&successor)
+                    .unwrap_or_else(|_| HeaderValue::from_static("</api/v1/lines>")),
+            ),
+        ],
+        update_status(state, headers, duration_query).await,
     )
-        .into_response()
 }
 
-async fn status() -> impl IntoResponse {
-    Json(serde_json::json!({
-        "status": "ok",
-        "message": "Helios is running"
-    }))
+async fn structured_status(
+    State(state): State<AppState>,
+    Query(query): Query<fields::FieldsQuery>,
+) -> Response {
+    if query.is_empty() {
+        return Json(state.latest().raw).into_response();
+    }
+
+    match fields::select(&state.latest().raw, &query) {
+        Ok(info) => Json(info).into_response(),
+        Err(message) => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "status": "error",
+                "message": message
+            })),
+        )
+            .into_response(),
+    }
+}
+
+async fn os_status(State(state): State<AppState>) -> Response {
+    section_response("os", state.latest().raw.os)
+}
+
+async fn memory_status(State(state): State<AppState>) -> Response {
+    section_response("memory", state.latest().raw.memory)
+}
+
+async fn disks_status(State(state): State<AppState>) -> Response {
+    section_response("disk", state.latest().raw.disks)
+}
+
+async fn network_status(State(state): State<AppState>) -> Response {
+    section_response("network", state.latest().raw.network)
+}
+
+async fn monitors_status(State(state): State<AppState>) -> Response {
+    section_response("monitors", state.latest().raw.monitors)
+}
+
+async fn http_monitors_status(State(state): State<AppState>) -> Response {
+    section_response("monitors", state.latest().raw.http_monitors)
+}
+
+/// One section of the info, or a 404 when its `collector` is disabled.
+fn section_response<T: serde::Serialize>(collector: &str, section: Option<T>) -> Response {
+    match section {
+        Some(section) => Json(section).into_response(),
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({
+                "status": "error",
+                "message": format!("The {collector} collector is disabled")
+            })),
+        )
+            .into_response(),
+    }
+}
+
+async fn cpu_usage() -> Response {
+    match tokio::task::spawn_blocking(collectors::sample_cpu_usage).await {
+        Ok(Some(usage)) => Json(usage).into_response(),
+        _ => StatusCode::SERVICE_UNAVAILABLE.into_response(),
+    }
+}
+
+async fn interfaces() -> Response {
+    match tokio::task::spawn_blocking(collectors::list_interfaces).await {
+        Ok(interfaces) => Json(serde_json::json!({ "interfaces": interfaces })).into_response(),
+        Err(_) => StatusCode::SERVICE_UNAVAILABLE.into_response(),
+    }
 }
 
-async fn update_status() -> impl IntoResponse {
-    let system_info = get_system_info_by_lines_unlocked();
+async fn self_usage() -> Response {
+    match tokio::task::spawn_blocking(collectors::self_usage).await {
+        Ok(Some(usage)) => Json(usage).into_response(),
+        _ => StatusCode::SERVICE_UNAVAILABLE.into_response(),
+    }
+}
+
+async fn containers() -> Response {
+    match collectors::list_containers().await {
+        Ok(containers) => Json(containers).into_response(),
+        Err(err) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(serde_json::json!({
+                "status": "error",
+                "message": format!("Docker is not available: {err}")
+            })),
+        )
+            .into_response(),
+    }
+}
+
+/// Only reachable with a token that can read the services, see [`auth::api_auth`].
+async fn failed_services() -> Response {
+    match collectors::failed_units().await {
+        Ok(units) => Json(serde_json::json!({ "failed": units })).into_response(),
+        Err(err) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(serde_json::json!({
+                "status": "error",
+                "message": format!("systemd is not available: {err}")
+            })),
+        )
+            .into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::to_bytes, http::Uri};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn selected_fields_are_read_from_the_latest_snapshot() {
+        let state = AppState::new().await;
+        let before = state.latest();
+        let uri = "/api/v1/info?fields=cpu,network".parse::<Uri>().unwrap();
+        let query = Query::<fields::FieldsQuery>::try_from_uri(&uri).unwrap();
+
+        let response = structured_status(State(state.clone()), query).await;
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let info = serde_json::from_slice::<serde_json::Value>(&body).unwrap();
 
-    Json(system_info)
+        // nothing was collected for the request, so the refresh task's baselines for the CPU
+        // usage and the network rates are left alone
+        let after = state.latest();
+        assert_eq!(after.collected_at, before.collected_at);
+        assert_eq!(
+            serde_json::to_value(&after.raw).unwrap(),
+            serde_json::to_value(&before.raw).unwrap()
+        );
+        assert_eq!(info["cpu"], serde_json::to_value(&before.raw.cpu).unwrap());
+        assert_eq!(
+            info["network"],
+            serde_json::to_value(&before.raw.network).unwrap()
+        );
+        assert!(info.get("memory").is_none());
+    }
 }
@@ -0,0 +1,83 @@
+use axum::{
+    Json,
+    http::{HeaderMap, HeaderValue, header},
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+
+use crate::{assets, config};
+
+const FAVICON: &[u8] = include_bytes!("../assets/favicon.ico");
+const ICON_32: &[u8] = include_bytes!("../assets/icons/icon-32.png");
+const ICON_180: &[u8] = include_bytes!("../assets/icons/icon-180.png");
+const ICON_192: &[u8] = include_bytes!("../assets/icons/icon-192.png");
+
+/// Web app manifest, so the page can be installed as a PWA.
+#[derive(Debug, Serialize)]
+struct WebManifest {
+    name: String,
+    short_name: String,
+    start_url: String,
+    display: &'static str,
+    theme_color: String,
+    background_color: String,
+    icons: [ManifestIcon; 2],
+}
+
+#[derive(Debug, Serialize)]
+struct ManifestIcon {
+    src: String,
+    sizes: &'static str,
+    #[serde(rename = "type")]
+    mime_type: &'static str,
+}
+
+pub async fn manifest() -> impl IntoResponse {
+    let manifest_config = &config::get().manifest;
+    let base_path = &config::get().base_path;
+    let manifest = WebManifest {
+        name: manifest_config.name.clone(),
+        short_name: manifest_config.name.clone(),
+        start_url: format!("{base_path}/"),
+        display: "standalone",
+        theme_color: manifest_config.theme_color.clone(),
+        background_color: manifest_config.background_color.clone(),
+        icons: [
+            ManifestIcon {
+                src: format!("{base_path}/assets/icons/icon-192.png"),
+                sizes: "192x192",
+                mime_type: "image/png",
+            },
+            // the original image is already 512x512
+            ManifestIcon {
+                src: format!("{base_path}/assets/helios.png"),
+                sizes: "512x512",
+                mime_type: "image/png",
+            },
+        ],
+    };
+
+    (
+        [(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/manifest+json"),
+        )],
+        Json(manifest),
+    )
+}
+
+pub async fn favicon(headers: HeaderMap) -> Response {
+    assets::serve_embedded(&headers, "image/x-icon", "favicon.ico", FAVICON)
+}
+
+pub async fn icon_32(headers: HeaderMap) -> Response {
+    assets::serve_embedded(&headers, "image/png", "icons/icon-32.png", ICON_32)
+}
+
+pub async fn icon_180(headers: HeaderMap) -> Response {
+    assets::serve_embedded(&headers, "image/png", "icons/icon-180.png", ICON_180)
+}
+
+pub async fn icon_192(headers: HeaderMap) -> Response {
+    assets::serve_embedded(&headers, "image/png", "icons/icon-192.png", ICON_192)
+}
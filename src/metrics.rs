@@ -0,0 +1,588 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    sync::{LazyLock, Mutex},
+    time::Instant,
+};
+
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::{HeaderMap, HeaderValue, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::{state::AppState, sysgetter::SystemInfoRaw};
+
+const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+const OPENMETRICS_CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+/// Upper bounds in seconds of the request duration buckets, the Prometheus client defaults.
+const DURATION_BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Requests served since startup, see [`track_requests`].
+static HTTP_METRICS: LazyLock<Mutex<HttpMetrics>> = LazyLock::new(Default::default);
+
+/// Exposition format, picked from the `Accept` header of the scraper.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Prometheus,
+    OpenMetrics,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Gauge,
+    Counter,
+    /// Constant `1` carrying its labels, a gauge in the Prometheus format.
+    Info,
+    /// Cumulative `_bucket` samples with their `_sum` and `_count`.
+    Histogram,
+}
+
+/// Every sample of one metric, with its metadata.
+struct Family {
+    /// Without the `_total` or `_info` suffix of the samples.
+    name: &'static str,
+    help: &'static str,
+    kind: Kind,
+    /// The name must end with it, OpenMetrics only.
+    unit: Option<&'static str>,
+    samples: Vec<Sample>,
+}
+
+struct Sample {
+    /// Appended to the name, the `_bucket`, `_sum`, and `_count` of a histogram.
+    suffix: &'static str,
+    labels: Vec<(&'static str, String)>,
+    value: f64,
+    /// Unix timestamp of when the counter started, OpenMetrics only.
+    created: Option<f64>,
+}
+
+/// Metrics of the latest snapshot for Prometheus.
+///
+/// Scrapers asking for `application/openmetrics-text` get the OpenMetrics format, with units
+/// and the `_created` series of the counters.
+pub async fn metrics_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let format = if accepts_openmetrics(&headers) {
+        Format::OpenMetrics
+    } else {
+        Format::Prometheus
+    };
+    let snapshot = state.latest();
+    let mut families = families(&snapshot.raw, snapshot.collected_at);
+    families.extend(http_families());
+    let body = render(&families, format);
+    let content_type = match format {
+        Format::Prometheus => PROMETHEUS_CONTENT_TYPE,
+        Format::OpenMetrics => OPENMETRICS_CONTENT_TYPE,
+    };
+
+    (
+        [(header::CONTENT_TYPE, HeaderValue::from_static(content_type))],
+        body,
+    )
+}
+
+fn accepts_openmetrics(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .is_some_and(|accept| accept.contains("application/openmetrics-text"))
+}
+
+impl Family {
+    fn new(name: &'static str, help: &'static str, kind: Kind, unit: Option<&'static str>) -> Self {
+        Family {
+            name,
+            help,
+            kind,
+            unit,
+            samples: vec![],
+        }
+    }
+
+    fn sample(&mut self, labels: Vec<(&'static str, String)>, value: f64) {
+        self.counter(labels, value, None);
+    }
+
+    /// A sample with the time the counter started from zero, when known.
+    fn counter(&mut self, labels: Vec<(&'static str, String)>, value: f64, created: Option<f64>) {
+        self.samples.push(Sample {
+            suffix: "",
+            labels,
+            value,
+            created,
+        });
+    }
+
+    /// The samples of one histogram series.
+    fn histogram(&mut self, labels: Vec<(&'static str, String)>, histogram: &Histogram) {
+        let mut cumulative = 0;
+        let bounds = DURATION_BUCKETS.iter().map(|bound| format_value(*bound));
+        for (bound, count) in bounds.chain(["+Inf".to_string()]).zip(&histogram.buckets) {
+            cumulative += count;
+            let mut labels = labels.clone();
+            labels.push(("le", bound));
+            self.suffixed("_bucket", labels, cumulative as f64);
+        }
+        self.suffixed("_sum", labels.clone(), histogram.sum);
+        self.suffixed("_count", labels, cumulative as f64);
+    }
+
+    fn suffixed(&mut self, suffix: &'static str, labels: Vec<(&'static str, String)>, value: f64) {
+        self.samples.push(Sample {
+            suffix,
+            labels,
+            value,
+            created: None,
+        });
+    }
+}
+
+#[derive(Debug, Default)]
+struct HttpMetrics {
+    /// By method, route, and status code.
+    requests: BTreeMap<(String, String, u16), RequestCount>,
+    /// By method and route.
+    durations: BTreeMap<(String, String), Histogram>,
+}
+
+#[derive(Debug)]
+struct RequestCount {
+    count: u64,
+    /// Unix timestamp of the first of these requests, when the counter started.
+    created: f64,
+}
+
+#[derive(Debug, Default)]
+struct Histogram {
+    /// Per bucket of [`DURATION_BUCKETS`] and the `+Inf` one, not cumulative.
+    buckets: [u64; DURATION_BUCKETS.len() + 1],
+    sum: f64,
+}
+
+/// Count every request and its duration for `/metrics`, by method, route, and status.
+pub async fn track_requests(request: Request, next: Next) -> Response {
+    let method = request.method().to_string();
+    // the route pattern keeps the label values bounded, unlike the path
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or("unmatched", MatchedPath::as_str)
+        .to_string();
+    let started = Instant::now();
+
+    let response = next.run(request).await;
+
+    let seconds = started.elapsed().as_secs_f64();
+    let status = response.status().as_u16();
+    let mut metrics = HTTP_METRICS.lock().unwrap_or_else(|e| e.into_inner());
+    metrics
+        .requests
+        .entry((method.clone(), route.clone(), status))
+        .or_insert_with(|| RequestCount {
+            count: 0,
+            created: chrono::Utc::now().timestamp() as f64,
+        })
+        .count += 1;
+    let histogram = metrics.durations.entry((method, route)).or_default();
+    let bucket = DURATION_BUCKETS
+        .iter()
+        .position(|bound| seconds <= *bound)
+        .unwrap_or(DURATION_BUCKETS.len());
+    histogram.buckets[bucket] += 1;
+    histogram.sum += seconds;
+
+    response
+}
+
+fn families(raw: &SystemInfoRaw, collected_at: i64) -> Vec<Family> {
+    let mut families = vec![];
+    let boot_time = raw
+        .os
+        .as_ref()
+        .map(|os| (collected_at - os.uptime_seconds as i64) as f64);
+
+    let mut info = Family::new(
+        "helios_host",
+        "Host the metrics are collected on.",
+        Kind::Info,
+        None,
+    );
+    let mut labels = vec![("host", raw.host.clone())];
+    if let Some(os) = &raw.os {
+        labels.push(("os", os.name.clone()));
+        labels.push(("kernel", os.kernel.clone()));
+    }
+    info.sample(labels, 1.0);
+    families.push(info);
+
+    if let Some(os) = &raw.os {
+        let mut uptime = Family::new(
+            "helios_uptime_seconds",
+            "Time since the system booted.",
+            Kind::Counter,
+            Some("seconds"),
+        );
+        uptime.counter(vec![], os.uptime_seconds as f64, boot_time);
+        families.push(uptime);
+    }
+
+    if let Some(cpu) = &raw.cpu {
+        let mut usage = Family::new(
+            "helios_cpu_usage_ratio",
+            "CPU usage across every core.",
+            Kind::Gauge,
+            Some("ratio"),
+        );
+        usage.sample(vec![], f64::from(cpu.usage_percent) / 100.0);
+        let mut cores = Family::new(
+            "helios_cpu_cores",
+            "Number of logical CPU cores.",
+            Kind::Gauge,
+            None,
+        );
+        cores.sample(vec![], cpu.cores as f64);
+        let mut frequency = Family::new(
+            "helios_cpu_frequency_hertz",
+            "Current CPU frequency.",
+            Kind::Gauge,
+            Some("hertz"),
+        );
+        frequency.sample(vec![], cpu.frequency_mhz as f64 * 1_000_000.0);
+        families.extend([usage, cores, frequency]);
+    }
+
+    if let Some(memory) = &raw.memory {
+        let mut total = Family::new(
+            "helios_memory_total_bytes",
+            "Total physical memory.",
+            Kind::Gauge,
+            Some("bytes"),
+        );
+        total.sample(vec![], memory.total_bytes as f64);
+        let mut used = Family::new(
+            "helios_memory_used_bytes",
+            "Used physical memory.",
+            Kind::Gauge,
+            Some("bytes"),
+        );
+        used.sample(vec![], memory.used_bytes as f64);
+        families.extend([total, used]);
+    }
+
+    if let Some(swap) = &raw.swap {
+        let mut total = Family::new(
+            "helios_swap_total_bytes",
+            "Total swap space.",
+            Kind::Gauge,
+            Some("bytes"),
+        );
+        total.sample(vec![], swap.total_bytes as f64);
+        let mut used = Family::new(
+            "helios_swap_used_bytes",
+            "Used swap space.",
+            Kind::Gauge,
+            Some("bytes"),
+        );
+        used.sample(vec![], swap.used_bytes as f64);
+        families.extend([total, used]);
+    }
+
+    if let Some(disks) = &raw.disks {
+        let mut total = Family::new(
+            "helios_disk_total_bytes",
+            "Size of the file system.",
+            Kind::Gauge,
+            Some("bytes"),
+        );
+        let mut used = Family::new(
+            "helios_disk_used_bytes",
+            "Used space of the file system.",
+            Kind::Gauge,
+            Some("bytes"),
+        );
+        let mut available = Family::new(
+            "helios_disk_available_bytes",
+            "Space available to unprivileged users.",
+            Kind::Gauge,
+            Some("bytes"),
+        );
+        for disk in disks {
+            let labels = || {
+                vec![
+                    ("mount", disk.mount_point.clone()),
+                    ("device", disk.name.clone()),
+                    ("fs", disk.file_system.clone()),
+                ]
+            };
+            total.sample(labels(), disk.total_bytes as f64);
+            used.sample(labels(), disk.used_bytes as f64);
+            available.sample(labels(), disk.available_bytes as f64);
+        }
+        families.extend([total, used, available]);
+    }
+
+    if let Some(disk_io) = &raw.disk_io {
+        let mut read = Family::new(
+            "helios_disk_read_bytes",
+            "Bytes read from the device since boot.",
+            Kind::Counter,
+            Some("bytes"),
+        );
+        let mut written = Family::new(
+            "helios_disk_written_bytes",
+            "Bytes written to the device since boot.",
+            Kind::Counter,
+            Some("bytes"),
+        );
+        for device in &disk_io.devices {
+            let labels = || vec![("device", device.name.clone())];
+            read.counter(labels(), device.read_bytes as f64, boot_time);
+            written.counter(labels(), device.written_bytes as f64, boot_time);
+        }
+        families.extend([read, written]);
+    }
+
+    if let Some(network) = &raw.network {
+        // the interfaces can come up after boot, so there's no reliable creation time
+        let mut received = Family::new(
+            "helios_network_receive_bytes",
+            "Bytes received since the interface came up.",
+            Kind::Counter,
+            Some("bytes"),
+        );
+        let mut transmitted = Family::new(
+            "helios_network_transmit_bytes",
+            "Bytes sent since the interface came up.",
+            Kind::Counter,
+            Some("bytes"),
+        );
+        for interface in &network.interfaces {
+            let labels = || vec![("interface", interface.name.clone())];
+            received.counter(labels(), interface.rx_bytes as f64, None);
+            transmitted.counter(labels(), interface.tx_bytes as f64, None);
+        }
+        families.extend([received, transmitted]);
+    }
+
+    if let Some(temperatures) = &raw.temperatures {
+        let mut temperature = Family::new(
+            "helios_temperature_celsius",
+            "Temperature reported by the sensor.",
+            Kind::Gauge,
+            Some("celsius"),
+        );
+        for sensor in temperatures {
+            temperature.sample(
+                vec![("sensor", sensor.label.clone())],
+                f64::from(sensor.celsius),
+            );
+        }
+        families.push(temperature);
+    }
+
+    if let Some(batteries) = &raw.batteries {
+        let mut charge = Family::new(
+            "helios_battery_charge_ratio",
+            "Charge of the battery.",
+            Kind::Gauge,
+            Some("ratio"),
+        );
+        for battery in batteries {
+            charge.sample(
+                vec![
+                    ("battery", battery.name.clone()),
+                    ("status", battery.status.clone()),
+                ],
+                f64::from(battery.capacity_percent) / 100.0,
+            );
+        }
+        families.push(charge);
+    }
+
+    if let Some(containers) = &raw.containers {
+        let mut running = Family::new(
+            "helios_containers_running",
+            "Number of running containers.",
+            Kind::Gauge,
+            None,
+        );
+        running.sample(vec![], containers.running as f64);
+        let mut total = Family::new(
+            "helios_containers",
+            "Number of containers.",
+            Kind::Gauge,
+            None,
+        );
+        total.sample(vec![], containers.total as f64);
+        families.extend([running, total]);
+    }
+
+    if let Some(services) = &raw.services {
+        let mut failed = Family::new(
+            "helios_systemd_failed_units",
+            "Number of failed systemd units.",
+            Kind::Gauge,
+            None,
+        );
+        failed.sample(vec![], services.failed as f64);
+        families.push(failed);
+    }
+
+    if let Some(raid) = &raw.raid {
+        let mut total = Family::new(
+            "helios_raid_devices",
+            "Number of devices in the RAID array.",
+            Kind::Gauge,
+            None,
+        );
+        let mut active = Family::new(
+            "helios_raid_devices_active",
+            "Number of active devices in the RAID array.",
+            Kind::Gauge,
+            None,
+        );
+        for array in raid {
+            let labels = || vec![("array", array.name.clone())];
+            total.sample(labels(), f64::from(array.devices_total));
+            active.sample(labels(), f64::from(array.devices_active));
+        }
+        families.extend([total, active]);
+    }
+
+    if let Some(users) = &raw.users {
+        let mut sessions = Family::new(
+            "helios_user_sessions",
+            "Number of logged in user sessions.",
+            Kind::Gauge,
+            None,
+        );
+        sessions.sample(vec![], users.count as f64);
+        families.push(sessions);
+    }
+
+    families
+}
+
+/// The requests helios itself served, to monitor the monitor.
+fn http_families() -> Vec<Family> {
+    let metrics = HTTP_METRICS.lock().unwrap_or_else(|e| e.into_inner());
+    let mut requests = Family::new(
+        "helios_http_requests",
+        "HTTP requests served by helios.",
+        Kind::Counter,
+        None,
+    );
+    for ((method, route, status), request_count) in &metrics.requests {
+        requests.counter(
+            vec![
+                ("method", method.clone()),
+                ("route", route.clone()),
+                ("status", status.to_string()),
+            ],
+            request_count.count as f64,
+            Some(request_count.created),
+        );
+    }
+    let mut duration = Family::new(
+        "helios_http_request_duration_seconds",
+        "Time taken to answer HTTP requests.",
+        Kind::Histogram,
+        Some("seconds"),
+    );
+    for ((method, route), histogram) in &metrics.durations {
+        duration.histogram(
+            vec![("method", method.clone()), ("route", route.clone())],
+            histogram,
+        );
+    }
+
+    vec![requests, duration]
+}
+
+fn render(families: &[Family], format: Format) -> String {
+    let mut text = String::new();
+
+    for family in families {
+        let (family_name, type_name, suffix) = match (family.kind, format) {
+            (Kind::Gauge, _) => (family.name.to_string(), "gauge", ""),
+            (Kind::Counter, Format::OpenMetrics) => (family.name.to_string(), "counter", "_total"),
+            // the Prometheus format names the family after its samples
+            (Kind::Counter, Format::Prometheus) => {
+                (format!("{}_total", family.name), "counter", "")
+            }
+            (Kind::Info, Format::OpenMetrics) => (family.name.to_string(), "info", "_info"),
+            (Kind::Info, Format::Prometheus) => (format!("{}_info", family.name), "gauge", ""),
+            (Kind::Histogram, _) => (family.name.to_string(), "histogram", ""),
+        };
+
+        let _ = writeln!(text, "# HELP {family_name} {}", family.help);
+        let _ = writeln!(text, "# TYPE {family_name} {type_name}");
+        if format == Format::OpenMetrics
+            && let Some(unit) = family.unit
+        {
+            let _ = writeln!(text, "# UNIT {family_name} {unit}");
+        }
+
+        for sample in &family.samples {
+            let labels = format_labels(&sample.labels);
+            let _ = writeln!(
+                text,
+                "{family_name}{suffix}{}{labels} {}",
+                sample.suffix,
+                format_value(sample.value)
+            );
+            if format == Format::OpenMetrics
+                && let Some(created) = sample.created
+            {
+                let _ = writeln!(
+                    text,
+                    "{family_name}_created{labels} {}",
+                    format_value(created)
+                );
+            }
+        }
+    }
+
+    if format == Format::OpenMetrics {
+        text.push_str("# EOF\n");
+    }
+
+    text
+}
+
+fn format_labels(labels: &[(&str, String)]) -> String {
+    if labels.is_empty() {
+        return String::new();
+    }
+
+    let labels = labels
+        .iter()
+        .map(|(name, value)| {
+            let value = value
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('\n', "\\n");
+            format!("{name}=\"{value}\"")
+        })
+        .collect::<Vec<_>>();
+
+    format!("{{{}}}", labels.join(","))
+}
+
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "+Inf" } else { "-Inf" }.to_string()
+    } else {
+        value.to_string()
+    }
+}
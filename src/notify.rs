@@ -0,0 +1,56 @@
+use std::time::Duration;
+
+/// Tell systemd the service finished starting up, for `Type=notify` units.
+pub fn ready() {
+    send("READY=1");
+}
+
+/// Keep the systemd watchdog from restarting the service.
+pub fn watchdog() {
+    send("WATCHDOG=1");
+}
+
+/// How often [`watchdog`] should be called, half the `WatchdogSec=` of the unit.
+///
+/// `None` when the watchdog is not enabled for this process.
+pub fn watchdog_interval() -> Option<Duration> {
+    if let Ok(pid) = std::env::var("WATCHDOG_PID")
+        && pid.parse() != Ok(std::process::id())
+    {
+        return None;
+    }
+
+    let usec: u64 = std::env::var("WATCHDOG_USEC").ok()?.parse().ok()?;
+    (usec > 0).then(|| Duration::from_micros(usec / 2))
+}
+
+/// Send a state to `NOTIFY_SOCKET`, doing nothing when helios was not started by systemd.
+#[cfg(unix)]
+fn send(state: &str) {
+    use std::os::unix::net::UnixDatagram;
+
+    let Some(path) = std::env::var_os("NOTIFY_SOCKET") else {
+        return;
+    };
+
+    let result = UnixDatagram::unbound().and_then(|socket| {
+        let path = path.to_string_lossy();
+        match path.strip_prefix('@') {
+            #[cfg(target_os = "linux")]
+            Some(name) => {
+                use std::os::linux::net::SocketAddrExt;
+
+                let addr = std::os::unix::net::SocketAddr::from_abstract_name(name)?;
+                socket.send_to_addr(state.as_bytes(), &addr)
+            }
+            _ => socket.send_to(state.as_bytes(), path.as_ref()),
+        }
+    });
+
+    if let Err(err) = result {
+        tracing::warn!("Failed to notify systemd with {state}: {err}");
+    }
+}
+
+#[cfg(not(unix))]
+fn send(_state: &str) {}
@@ -0,0 +1,93 @@
+use std::sync::LazyLock;
+
+use axum::{
+    http::{HeaderMap, StatusCode},
+    response::{Html, IntoResponse, Response},
+};
+
+use crate::{assets, config, templates};
+
+const OPENAPI_JSON: &str = include_str!("../assets/openapi.json");
+
+/// The OpenAPI document with the `base_path` as its server, and the routes of enabled features.
+static DOCUMENT: LazyLock<String> = LazyLock::new(|| {
+    let mut document: serde_json::Value =
+        serde_json::from_str(OPENAPI_JSON).expect("openapi.json should be valid JSON");
+
+    let base_path = &config::get().base_path;
+    let server = if base_path.is_empty() { "/" } else { base_path };
+    document["servers"] = serde_json::json!([{ "url": server }]);
+
+    #[cfg(feature = "sqlite")]
+    {
+        document["paths"]["/api/v1/history/stored"] = serde_json::json!({
+            "get": {
+                "tags": ["history"],
+                "summary": "Samples from the SQLite database",
+                "parameters": [
+                    {
+                        "name": "from",
+                        "in": "query",
+                        "description": "Unix timestamp of the first sample",
+                        "schema": { "type": "integer", "format": "int64" }
+                    },
+                    {
+                        "name": "to",
+                        "in": "query",
+                        "description": "Unix timestamp of the last sample",
+                        "schema": { "type": "integer", "format": "int64" }
+                    }
+                ],
+                "responses": {
+                    "200": { "description": "The samples" },
+                    "404": { "description": "`persistence.path` is unset" }
+                }
+            }
+        });
+    }
+    #[cfg(feature = "graphql")]
+    {
+        document["paths"]["/graphql"] = serde_json::json!({
+            "post": {
+                "tags": ["info"],
+                "summary": "The info and history over GraphQL",
+                "requestBody": {
+                    "required": true,
+                    "content": {
+                        "application/json": {
+                            "schema": {
+                                "type": "object",
+                                "required": ["query"],
+                                "properties": { "query": { "type": "string" } }
+                            }
+                        }
+                    }
+                },
+                "responses": { "200": { "description": "The GraphQL response" } }
+            }
+        });
+    }
+
+    document.to_string()
+});
+
+/// `/api/v1/openapi.json`, the routes of the API.
+pub async fn openapi_handler(headers: HeaderMap) -> Response {
+    assets::serve(
+        &headers,
+        "application/json",
+        DOCUMENT.as_str(),
+        assets::REVALIDATE_CACHE_CONTROL,
+    )
+}
+
+/// `/api/docs`, a page to try the routes of the OpenAPI document from the browser.
+pub async fn explorer_handler() -> Response {
+    match templates::render_api_docs() {
+        Ok(html) => Html(html).into_response(),
+        Err(err) => {
+            tracing::error!("Failed to render the API explorer: {err}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
@@ -0,0 +1,691 @@
+use std::{
+    hash::{BuildHasher, RandomState},
+    sync::{
+        LazyLock, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use axum::{
+    Router,
+    extract::{MatchedPath, Request},
+    middleware::Next,
+    response::Response,
+};
+use serde_json::{Value, json};
+use tracing::{
+    Instrument, Subscriber,
+    field::{Field, Visit},
+    span::{Attributes, Id, Record},
+};
+use tracing_subscriber::{Layer, filter::filter_fn, layer::Context, registry::LookupSpan};
+
+use crate::{config, state::AppState, sysgetter::SystemInfoRaw};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// The spans exported as traces, the other spans only end up in the log.
+const TRACED_SPANS: [&str; 3] = ["http.request", "collect", "collector"];
+/// Spans kept between two exports, the newer ones are dropped when the collector is unreachable.
+const MAX_BUFFERED_SPANS: usize = 4096;
+/// `SPAN_KIND_INTERNAL` and `SPAN_KIND_SERVER`.
+const SPAN_KIND_INTERNAL: u8 = 1;
+const SPAN_KIND_SERVER: u8 = 2;
+/// `STATUS_CODE_ERROR`.
+const STATUS_CODE_ERROR: u8 = 2;
+/// `AGGREGATION_TEMPORALITY_CUMULATIVE`, the values are totals since the start time.
+const CUMULATIVE: u8 = 2;
+
+/// Builds the metrics of one snapshot in the OTLP JSON encoding.
+struct Metrics {
+    metrics: Vec<Value>,
+    /// Nanoseconds since the epoch, a string like every 64-bit integer in OTLP JSON.
+    time: String,
+    /// Start of the counters reset at boot.
+    boot_time: Option<String>,
+}
+
+/// Periodically export the latest snapshot to `otel.endpoint`, and the recorded spans to
+/// `otel.traces_endpoint`, when they are configured.
+pub fn spawn_otel_task(state: AppState) {
+    let otel_config = config::get().otel.clone();
+    if otel_config.endpoint.is_none() && otel_config.traces_endpoint.is_none() {
+        return;
+    }
+
+    let client = match reqwest::Client::builder().timeout(REQUEST_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(err) => {
+            tracing::error!("Failed to create the OTLP HTTP client: {err}");
+            return;
+        }
+    };
+    let period = Duration::from_secs(otel_config.interval.max(1));
+
+    if let Some(endpoint) = otel_config.endpoint {
+        tracing::info!(
+            "Exporting metrics to {endpoint} every {}s",
+            period.as_secs()
+        );
+        let client = client.clone();
+        let state = state.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+            loop {
+                interval.tick().await;
+
+                let snapshot = state.latest();
+                let request = export_request(&snapshot.raw, snapshot.collected_at);
+                if let Err(err) = export(&client, &endpoint, &request).await {
+                    tracing::warn!("Failed to export the metrics to {endpoint}: {err}");
+                }
+            }
+        });
+    }
+
+    if let Some(endpoint) = otel_config.traces_endpoint {
+        tracing::info!("Exporting traces to {endpoint} every {}s", period.as_secs());
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+            loop {
+                interval.tick().await;
+
+                let spans = std::mem::take(&mut *SPANS.lock().unwrap_or_else(|e| e.into_inner()));
+                if spans.is_empty() {
+                    continue;
+                }
+                let request = json!({
+                    "resourceSpans": [{
+                        "resource": { "attributes": resource_attributes(&state.latest().raw) },
+                        "scopeSpans": [{
+                            "scope": { "name": "helios", "version": env!("CARGO_PKG_VERSION") },
+                            "spans": spans,
+                        }],
+                    }],
+                });
+                if let Err(err) = export(&client, &endpoint, &request).await {
+                    tracing::warn!("Failed to export the traces to {endpoint}: {err}");
+                }
+            }
+        });
+    }
+}
+
+/// POST an export request in the OTLP JSON encoding.
+async fn export(client: &reqwest::Client, endpoint: &str, request: &Value) -> reqwest::Result<()> {
+    let mut request = client
+        .post(endpoint)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(request.to_string());
+    for (name, value) in &config::get().otel.headers {
+        request = request.header(name, value);
+    }
+
+    request.send().await?.error_for_status()?;
+    Ok(())
+}
+
+/// Finished spans in the OTLP JSON encoding, waiting for the next export.
+static SPANS: LazyLock<Mutex<Vec<Value>>> = LazyLock::new(Default::default);
+
+/// Records the spans of [`TRACED_SPANS`] for the export, `None` without `otel.traces_endpoint`.
+///
+/// Filtered on its own, so the spans are recorded whatever the log level is.
+pub fn trace_layer<S>() -> Option<impl Layer<S>>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    config::get().otel.traces_endpoint.as_ref()?;
+
+    Some(SpanRecorder.with_filter(filter_fn(|metadata| {
+        metadata.is_span() && TRACED_SPANS.contains(&metadata.name())
+    })))
+}
+
+/// Wrap every request in an `http.request` span, nothing changes without `otel.traces_endpoint`.
+pub fn with_request_tracing(app: Router) -> Router {
+    if config::get().otel.traces_endpoint.is_none() {
+        return app;
+    }
+
+    app.layer(axum::middleware::from_fn(trace_request))
+}
+
+async fn trace_request(request: Request, next: Next) -> Response {
+    let method = request.method().clone();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string());
+    // `{method} {route}` like the semantic conventions, the path would make too many names
+    let name = match &route {
+        Some(route) => format!("{method} {route}"),
+        None => method.to_string(),
+    };
+    let span = tracing::info_span!(
+        "http.request",
+        otel.name = name,
+        http.request.method = %method,
+        http.route = route,
+        url.path = request.uri().path(),
+        http.response.status_code = tracing::field::Empty,
+    );
+
+    let response = next.run(request).instrument(span.clone()).await;
+    span.record("http.response.status_code", response.status().as_u16());
+    response
+}
+
+struct SpanRecorder;
+
+/// A span being recorded, kept in the extensions of the span until it closes.
+struct RecordedSpan {
+    trace_id: String,
+    span_id: String,
+    parent_span_id: Option<String>,
+    /// The `otel.name` field, or the name of the span.
+    name: String,
+    kind: u8,
+    start: SystemTime,
+    attributes: Vec<(&'static str, Value)>,
+}
+
+impl<S> Layer<S> for SpanRecorder
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attributes: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        // the parent is only one of the traced spans, the filter hides the others
+        let parent = span.parent().and_then(|parent| {
+            parent
+                .extensions()
+                .get::<RecordedSpan>()
+                .map(|parent| (parent.trace_id.clone(), parent.span_id.clone()))
+        });
+        let (trace_id, parent_span_id) = match parent {
+            Some((trace_id, span_id)) => (trace_id, Some(span_id)),
+            None => (random_id(2), None),
+        };
+
+        let metadata = attributes.metadata();
+        let mut recorded = RecordedSpan {
+            trace_id,
+            span_id: random_id(1),
+            parent_span_id,
+            name: metadata.name().to_string(),
+            kind: if metadata.name() == "http.request" {
+                SPAN_KIND_SERVER
+            } else {
+                SPAN_KIND_INTERNAL
+            },
+            start: SystemTime::now(),
+            attributes: vec![],
+        };
+        attributes.record(&mut recorded);
+        span.extensions_mut().insert(recorded);
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id)
+            && let Some(recorded) = span.extensions_mut().get_mut::<RecordedSpan>()
+        {
+            values.record(recorded);
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(recorded) = ctx
+            .span(&id)
+            .and_then(|span| span.extensions_mut().remove::<RecordedSpan>())
+        else {
+            return;
+        };
+
+        let mut spans = SPANS.lock().unwrap_or_else(|e| e.into_inner());
+        if spans.len() < MAX_BUFFERED_SPANS {
+            spans.push(recorded.finish(SystemTime::now()));
+        }
+    }
+}
+
+impl RecordedSpan {
+    /// The `Span` message of the OTLP JSON encoding.
+    fn finish(self, end: SystemTime) -> Value {
+        let server_error = self.attributes.iter().any(|(key, value)| {
+            *key == "http.response.status_code"
+                && value["intValue"]
+                    .as_str()
+                    .and_then(|status| status.parse::<u16>().ok())
+                    .is_some_and(|status| status >= 500)
+        });
+        let attributes = self
+            .attributes
+            .into_iter()
+            .map(|(key, value)| json!({ "key": key, "value": value }))
+            .collect::<Vec<_>>();
+
+        let mut span = json!({
+            "traceId": self.trace_id,
+            "spanId": self.span_id,
+            "name": self.name,
+            "kind": self.kind,
+            "startTimeUnixNano": unix_nanos(self.start),
+            "endTimeUnixNano": unix_nanos(end),
+            "attributes": attributes,
+        });
+        if let Some(parent_span_id) = self.parent_span_id {
+            span["parentSpanId"] = json!(parent_span_id);
+        }
+        if server_error {
+            span["status"] = json!({ "code": STATUS_CODE_ERROR });
+        }
+        span
+    }
+
+    fn set(&mut self, field: &Field, value: Value) {
+        if field.name() == "otel.name" {
+            if let Some(name) = value["stringValue"].as_str() {
+                self.name = name.to_string();
+            }
+            return;
+        }
+
+        self.attributes.retain(|(key, _)| *key != field.name());
+        self.attributes.push((field.name(), value));
+    }
+}
+
+/// The fields as `AnyValue`s, 64-bit integers are strings in OTLP JSON.
+impl Visit for RecordedSpan {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.set(field, json!({ "stringValue": value }));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.set(field, json!({ "intValue": value.to_string() }));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.set(field, json!({ "intValue": value.to_string() }));
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.set(field, json!({ "doubleValue": value }));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.set(field, json!({ "boolValue": value }));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.set(field, json!({ "stringValue": format!("{value:?}") }));
+    }
+}
+
+/// A random ID of `words` 64-bit words in hex, 2 for a trace ID and 1 for a span ID.
+fn random_id(words: usize) -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    (0..words)
+        .map(|_| {
+            // `RandomState` keys are random, and never the same twice in a thread
+            let word = RandomState::new().hash_one(COUNTER.fetch_add(1, Ordering::Relaxed));
+            format!("{word:016x}")
+        })
+        .collect()
+}
+
+fn unix_nanos(time: SystemTime) -> String {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
+        .to_string()
+}
+
+/// `ExportMetricsServiceRequest` with the system metrics, named after the semantic conventions.
+fn export_request(raw: &SystemInfoRaw, collected_at: i64) -> Value {
+    let mut metrics = Metrics {
+        metrics: vec![],
+        time: nanos(collected_at),
+        boot_time: raw
+            .os
+            .as_ref()
+            .map(|os| nanos(collected_at - os.uptime_seconds as i64)),
+    };
+
+    if let Some(os) = &raw.os {
+        metrics.gauge(
+            "system.uptime",
+            "Time since the system booted.",
+            "s",
+            vec![(vec![], os.uptime_seconds as f64)],
+        );
+    }
+
+    if let Some(cpu) = &raw.cpu {
+        metrics.gauge(
+            "system.cpu.utilization",
+            "CPU usage across every core.",
+            "1",
+            vec![(vec![], f64::from(cpu.usage_percent) / 100.0)],
+        );
+        metrics.gauge(
+            "system.cpu.frequency",
+            "Current CPU frequency.",
+            "Hz",
+            vec![(vec![], cpu.frequency_mhz as f64 * 1_000_000.0)],
+        );
+    }
+
+    if let Some(memory) = &raw.memory {
+        let free = memory.total_bytes.saturating_sub(memory.used_bytes);
+        metrics.up_down_counter(
+            "system.memory.usage",
+            "Physical memory in use and free.",
+            "By",
+            vec![
+                (
+                    vec![("system.memory.state", "used".to_string())],
+                    memory.used_bytes,
+                ),
+                (vec![("system.memory.state", "free".to_string())], free),
+            ],
+        );
+        metrics.gauge(
+            "system.memory.utilization",
+            "Share of the physical memory in use.",
+            "1",
+            vec![(
+                vec![("system.memory.state", "used".to_string())],
+                memory.usage_percent / 100.0,
+            )],
+        );
+    }
+
+    if let Some(swap) = raw.swap.as_ref().filter(|swap| swap.total_bytes > 0) {
+        let free = swap.total_bytes.saturating_sub(swap.used_bytes);
+        metrics.up_down_counter(
+            "system.paging.usage",
+            "Swap space in use and free.",
+            "By",
+            vec![
+                (
+                    vec![("system.paging.state", "used".to_string())],
+                    swap.used_bytes,
+                ),
+                (vec![("system.paging.state", "free".to_string())], free),
+            ],
+        );
+    }
+
+    if let Some(disks) = &raw.disks {
+        let mut usage = vec![];
+        let mut utilization = vec![];
+        for disk in disks {
+            let attributes = |state: &str| {
+                vec![
+                    ("system.device", disk.name.clone()),
+                    ("system.filesystem.mountpoint", disk.mount_point.clone()),
+                    ("system.filesystem.type", disk.file_system.clone()),
+                    ("system.filesystem.state", state.to_string()),
+                ]
+            };
+            usage.push((attributes("used"), disk.used_bytes));
+            usage.push((attributes("free"), disk.available_bytes));
+            utilization.push((attributes("used"), disk.usage_percent / 100.0));
+        }
+        metrics.up_down_counter(
+            "system.filesystem.usage",
+            "File system space in use and free.",
+            "By",
+            usage,
+        );
+        metrics.gauge(
+            "system.filesystem.utilization",
+            "Share of the file system in use.",
+            "1",
+            utilization,
+        );
+    }
+
+    if let Some(disk_io) = &raw.disk_io {
+        let mut data_points = vec![];
+        for device in &disk_io.devices {
+            let attributes = |direction: &str| {
+                vec![
+                    ("system.device", device.name.clone()),
+                    ("disk.io.direction", direction.to_string()),
+                ]
+            };
+            data_points.push((attributes("read"), device.read_bytes));
+            data_points.push((attributes("write"), device.written_bytes));
+        }
+        let start = metrics.boot_time.clone();
+        metrics.counter(
+            "system.disk.io",
+            "Bytes transferred by the disk since boot.",
+            "By",
+            start,
+            data_points,
+        );
+    }
+
+    if let Some(network) = &raw.network {
+        let mut data_points = vec![];
+        for interface in &network.interfaces {
+            let attributes = |direction: &str| {
+                vec![
+                    ("network.interface.name", interface.name.clone()),
+                    ("network.io.direction", direction.to_string()),
+                ]
+            };
+            data_points.push((attributes("receive"), interface.rx_bytes));
+            data_points.push((attributes("transmit"), interface.tx_bytes));
+        }
+        // the interfaces can come up after boot, so there's no reliable start time
+        metrics.counter(
+            "system.network.io",
+            "Bytes transferred by the interface since it came up.",
+            "By",
+            None,
+            data_points,
+        );
+    }
+
+    if let Some(temperatures) = &raw.temperatures {
+        metrics.gauge(
+            "hw.temperature",
+            "Temperature reported by the sensor.",
+            "Cel",
+            temperatures
+                .iter()
+                .map(|sensor| {
+                    (
+                        vec![("hw.id", sensor.label.clone())],
+                        f64::from(sensor.celsius),
+                    )
+                })
+                .collect(),
+        );
+    }
+
+    if let Some(batteries) = &raw.batteries {
+        metrics.gauge(
+            "hw.battery.charge",
+            "Charge of the battery.",
+            "1",
+            batteries
+                .iter()
+                .map(|battery| {
+                    (
+                        vec![("hw.id", battery.name.clone())],
+                        f64::from(battery.capacity_percent) / 100.0,
+                    )
+                })
+                .collect(),
+        );
+    }
+
+    if let Some(services) = &raw.services {
+        metrics.gauge(
+            "helios.systemd.failed_units",
+            "Number of failed systemd units.",
+            "{unit}",
+            vec![(vec![], services.failed as f64)],
+        );
+    }
+
+    json!({
+        "resourceMetrics": [{
+            "resource": { "attributes": resource_attributes(raw) },
+            "scopeMetrics": [{
+                "scope": { "name": "helios", "version": env!("CARGO_PKG_VERSION") },
+                "metrics": metrics.metrics,
+            }],
+        }],
+    })
+}
+
+impl Metrics {
+    fn gauge(
+        &mut self,
+        name: &str,
+        description: &str,
+        unit: &str,
+        data_points: Vec<(Vec<(&str, String)>, f64)>,
+    ) {
+        let data_points = data_points
+            .into_iter()
+            .filter(|(_, value)| value.is_finite())
+            .map(|(attributes, value)| {
+                json!({
+                    "attributes": key_values(&attributes),
+                    "timeUnixNano": self.time,
+                    "asDouble": value,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        self.metrics.push(json!({
+            "name": name,
+            "description": description,
+            "unit": unit,
+            "gauge": { "dataPoints": data_points },
+        }));
+    }
+
+    /// A monotonic sum, `start` is when the counters were zero.
+    fn counter(
+        &mut self,
+        name: &str,
+        description: &str,
+        unit: &str,
+        start: Option<String>,
+        data_points: Vec<(Vec<(&str, String)>, u64)>,
+    ) {
+        self.sum(name, description, unit, true, start, data_points);
+    }
+
+    /// A sum that can go down, e.g. the memory in use.
+    fn up_down_counter(
+        &mut self,
+        name: &str,
+        description: &str,
+        unit: &str,
+        data_points: Vec<(Vec<(&str, String)>, u64)>,
+    ) {
+        self.sum(name, description, unit, false, None, data_points);
+    }
+
+    fn sum(
+        &mut self,
+        name: &str,
+        description: &str,
+        unit: &str,
+        monotonic: bool,
+        start: Option<String>,
+        data_points: Vec<(Vec<(&str, String)>, u64)>,
+    ) {
+        let data_points = data_points
+            .into_iter()
+            .map(|(attributes, value)| {
+                let mut data_point = json!({
+                    "attributes": key_values(&attributes),
+                    "timeUnixNano": self.time,
+                    "asInt": value.to_string(),
+                });
+                if let Some(start) = &start {
+                    data_point["startTimeUnixNano"] = json!(start);
+                }
+                data_point
+            })
+            .collect::<Vec<_>>();
+
+        self.metrics.push(json!({
+            "name": name,
+            "description": description,
+            "unit": unit,
+            "sum": {
+                "dataPoints": data_points,
+                "aggregationTemporality": CUMULATIVE,
+                "isMonotonic": monotonic,
+            },
+        }));
+    }
+}
+
+/// `host.name`, `os.type`, and friends describing where the metrics come from.
+fn resource_attributes(raw: &SystemInfoRaw) -> Vec<Value> {
+    let mut attributes = vec![
+        ("service.name", config::get().otel.service_name.clone()),
+        ("service.version", env!("CARGO_PKG_VERSION").to_string()),
+        ("host.name", raw.host.clone()),
+        ("host.arch", host_arch().to_string()),
+        ("os.type", os_type().to_string()),
+    ];
+    if let Some(os) = &raw.os {
+        attributes.push(("os.description", os.name.clone()));
+        attributes.push(("os.version", os.kernel.clone()));
+    }
+
+    key_values(&attributes)
+}
+
+fn key_values(attributes: &[(&str, String)]) -> Vec<Value> {
+    attributes
+        .iter()
+        .map(|(key, value)| json!({ "key": key, "value": { "stringValue": value } }))
+        .collect()
+}
+
+/// The `os.type` value of the semantic conventions.
+fn os_type() -> &'static str {
+    match std::env::consts::OS {
+        "macos" => "darwin",
+        "dragonfly" => "dragonflybsd",
+        "illumos" | "solaris" => "solaris",
+        os => os,
+    }
+}
+
+/// The `host.arch` value of the semantic conventions.
+fn host_arch() -> &'static str {
+    match std::env::consts::ARCH {
+        "x86_64" => "amd64",
+        "aarch64" => "arm64",
+        "arm" => "arm32",
+        "powerpc64" => "ppc64",
+        arch => arch,
+    }
+}
+
+fn nanos(seconds: i64) -> String {
+    (i128::from(seconds) * 1_000_000_000).to_string()
+}
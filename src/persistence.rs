@@ -0,0 +1,199 @@
+use std::{path::Path, sync::Mutex, time::Duration};
+
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use rusqlite::{Connection, params};
+use serde::Serialize;
+
+use crate::{
+    config,
+    history::{HistoryQuery, HistorySample},
+    state::AppState,
+};
+
+/// Upper bound of samples returned by one query, about a week at the default interval.
+const MAX_QUERY_SAMPLES: usize = 10_080;
+
+/// Snapshots stored in a local SQLite database, so the history survives restarts.
+pub struct MetricStore {
+    conn: Mutex<Connection>,
+}
+
+#[derive(Debug, Serialize)]
+struct StoredHistoryResponse {
+    samples: Vec<HistorySample>,
+}
+
+impl MetricStore {
+    pub fn open(path: &Path) -> rusqlite::Result<Self> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS samples (
+                timestamp INTEGER PRIMARY KEY,
+                cpu_usage_percent REAL,
+                memory_used_bytes INTEGER,
+                memory_usage_percent REAL,
+                disk_used_bytes INTEGER,
+                disk_total_bytes INTEGER,
+                network_rx_bytes_per_sec INTEGER,
+                network_tx_bytes_per_sec INTEGER
+            )",
+        )?;
+
+        Ok(MetricStore {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    pub fn insert(&self, sample: &HistorySample) -> rusqlite::Result<()> {
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        conn.execute(
+            "INSERT OR REPLACE INTO samples VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                sample.timestamp,
+                sample.cpu_usage_percent,
+                sample.memory_used_bytes,
+                sample.memory_usage_percent,
+                sample.disk_used_bytes,
+                sample.disk_total_bytes,
+                sample.network_rx_bytes_per_sec,
+                sample.network_tx_bytes_per_sec,
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    /// Delete every sample older than `before`, returning how many were removed.
+    pub fn prune(&self, before: i64) -> rusqlite::Result<usize> {
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        conn.execute("DELETE FROM samples WHERE timestamp < ?1", params![before])
+    }
+
+    /// The newest `limit` samples between `from` and `to` (both inclusive), oldest first.
+    pub fn range(
+        &self,
+        from: Option<i64>,
+        to: Option<i64>,
+        limit: usize,
+    ) -> rusqlite::Result<Vec<HistorySample>> {
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        let mut statement = conn.prepare(
+            "SELECT * FROM (
+                SELECT * FROM samples
+                WHERE timestamp >= ?1 AND timestamp <= ?2
+                ORDER BY timestamp DESC
+                LIMIT ?3
+            ) ORDER BY timestamp ASC",
+        )?;
+
+        let rows = statement.query_map(
+            params![from.unwrap_or(i64::MIN), to.unwrap_or(i64::MAX), limit],
+            sample_from_row,
+        )?;
+
+        rows.collect()
+    }
+
+    /// The oldest `limit` samples after `after` (exclusive) up to `to` (inclusive), oldest first,
+    /// to read a long range a page at a time.
+    pub fn page(
+        &self,
+        after: Option<i64>,
+        to: Option<i64>,
+        limit: usize,
+    ) -> rusqlite::Result<Vec<HistorySample>> {
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        let mut statement = conn.prepare(
+            "SELECT * FROM samples
+            WHERE timestamp > ?1 AND timestamp <= ?2
+            ORDER BY timestamp ASC
+            LIMIT ?3",
+        )?;
+
+        let rows = statement.query_map(
+            params![after.unwrap_or(i64::MIN), to.unwrap_or(i64::MAX), limit],
+            sample_from_row,
+        )?;
+
+        rows.collect()
+    }
+}
+
+fn sample_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<HistorySample> {
+    Ok(HistorySample {
+        timestamp: row.get(0)?,
+        cpu_usage_percent: row.get(1)?,
+        memory_used_bytes: row.get(2)?,
+        memory_usage_percent: row.get(3)?,
+        disk_used_bytes: row.get(4)?,
+        disk_total_bytes: row.get(5)?,
+        network_rx_bytes_per_sec: row.get(6)?,
+        network_tx_bytes_per_sec: row.get(7)?,
+    })
+}
+
+/// Periodically store the latest snapshot and prune the ones past the retention.
+pub fn spawn_persist_task(state: AppState) {
+    let Some(store) = state.store() else {
+        return;
+    };
+
+    let persistence_config = &config::get().persistence;
+    let period = Duration::from_secs(persistence_config.interval.max(1));
+    let retention_secs = persistence_config.retention_days.saturating_mul(86_400) as i64;
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+
+            let snapshot = state.latest();
+            let sample = HistorySample::from_raw(snapshot.collected_at, &snapshot.raw);
+            let store = store.clone();
+            let result = tokio::task::spawn_blocking(move || {
+                store.insert(&sample)?;
+                store.prune(sample.timestamp.saturating_sub(retention_secs))
+            })
+            .await;
+
+            match result {
+                Ok(Ok(_)) => {}
+                Ok(Err(err)) => tracing::error!("Failed to store snapshot: {err}"),
+                Err(err) => tracing::error!("Failed to store snapshot: {err}"),
+            }
+        }
+    });
+}
+
+pub async fn stored_history_handler(
+    State(state): State<AppState>,
+    Query(query): Query<HistoryQuery>,
+) -> Response {
+    let Some(store) = state.store() else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    let result =
+        tokio::task::spawn_blocking(move || store.range(query.from, query.to, MAX_QUERY_SAMPLES))
+            .await;
+
+    match result {
+        Ok(Ok(samples)) => Json(StoredHistoryResponse { samples }).into_response(),
+        Ok(Err(err)) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({
+                "status": "error",
+                "message": format!("Failed to read stored history: {err}")
+            })),
+        )
+            .into_response(),
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+}
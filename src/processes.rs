@@ -0,0 +1,80 @@
+use axum::{Json, extract::Query, response::IntoResponse};
+use serde::{Deserialize, Serialize};
+use sysinfo::{MINIMUM_CPU_UPDATE_INTERVAL, ProcessRefreshKind, ProcessesToUpdate, System};
+
+const DEFAULT_LIMIT: usize = 10;
+const MAX_LIMIT: usize = 100;
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProcessSort {
+    #[default]
+    Cpu,
+    Mem,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProcessesQuery {
+    #[serde(default)]
+    sort: ProcessSort,
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessInfoRaw {
+    pub pid: u32,
+    pub name: String,
+    pub cpu_percent: f32,
+    pub rss_bytes: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct ProcessesResponse {
+    sort: ProcessSort,
+    processes: Vec<ProcessInfoRaw>,
+}
+
+/// Snapshot of the process table, sorted and truncated to `limit` entries.
+///
+/// This blocks for [`MINIMUM_CPU_UPDATE_INTERVAL`] since CPU usage needs two samples.
+pub fn top_processes(sort: ProcessSort, limit: usize) -> Vec<ProcessInfoRaw> {
+    let refresh_kind = ProcessRefreshKind::nothing().with_cpu().with_memory();
+    let mut sys = System::new();
+    sys.refresh_processes_specifics(ProcessesToUpdate::All, true, refresh_kind);
+    std::thread::sleep(MINIMUM_CPU_UPDATE_INTERVAL);
+    sys.refresh_processes_specifics(ProcessesToUpdate::All, true, refresh_kind);
+
+    let mut processes: Vec<ProcessInfoRaw> = sys
+        .processes()
+        .values()
+        // skip threads, they would show up as duplicates of their parent
+        .filter(|process| process.thread_kind().is_none())
+        .map(|process| ProcessInfoRaw {
+            pid: process.pid().as_u32(),
+            name: process.name().to_string_lossy().to_string(),
+            cpu_percent: process.cpu_usage(),
+            rss_bytes: process.memory(),
+        })
+        .collect();
+
+    match sort {
+        ProcessSort::Cpu => {
+            processes.sort_by(|a, b| b.cpu_percent.total_cmp(&a.cpu_percent));
+        }
+        ProcessSort::Mem => processes.sort_by_key(|process| std::cmp::Reverse(process.rss_bytes)),
+    }
+    processes.truncate(limit);
+
+    processes
+}
+
+pub async fn processes_handler(Query(query): Query<ProcessesQuery>) -> impl IntoResponse {
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let sort = query.sort;
+
+    let processes = tokio::task::spawn_blocking(move || top_processes(sort, limit))
+        .await
+        .unwrap_or_default();
+
+    Json(ProcessesResponse { sort, processes })
+}
@@ -0,0 +1,266 @@
+use std::time::Duration;
+
+use axum::{
+    Json,
+    body::Bytes,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::{config, state::AppState, sysgetter::SystemInfo};
+
+const SIGNATURE_HEADER: &str = "x-helios-signature";
+const TIMESTAMP_HEADER: &str = "x-helios-timestamp";
+/// Pushes signed further in the past or future are rejected, so captured ones can't be replayed.
+const MAX_CLOCK_SKEW: u64 = 300;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A snapshot sent by an agent to a hub.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PushPayload {
+    /// Shown before the `@` of the agent's block on the hub.
+    pub username: String,
+    pub info: SystemInfo,
+}
+
+/// Periodically push the latest snapshot to `push.url` when one is configured.
+pub fn spawn_push_task(state: AppState) {
+    let push_config = config::get().push.clone();
+    let Some(url) = push_config.url else {
+        return;
+    };
+    let Some(secret) = push_config.secret else {
+        tracing::warn!("Not pushing to {url}, `push.secret` is not set");
+        return;
+    };
+
+    let client = match reqwest::Client::builder().timeout(REQUEST_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(err) => {
+            tracing::error!("Failed to create the push HTTP client: {err}");
+            return;
+        }
+    };
+    let period = Duration::from_secs(push_config.interval.max(1));
+    tracing::info!("Pushing snapshots to {url} every {}s", period.as_secs());
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+
+            let payload = PushPayload {
+                username: config::get().username.clone(),
+                info: state.latest().info,
+            };
+            let body = match serde_json::to_vec(&payload) {
+                Ok(body) => body,
+                Err(err) => {
+                    tracing::error!("Failed to serialize the snapshot: {err}");
+                    continue;
+                }
+            };
+            let timestamp = chrono::Utc::now().timestamp();
+
+            let result = client
+                .post(&url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(TIMESTAMP_HEADER, timestamp)
+                .header(SIGNATURE_HEADER, sign(&secret, timestamp, &body))
+                .body(body)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+            if let Err(err) = result {
+                tracing::warn!("Failed to push the snapshot to {url}: {err}");
+            }
+        }
+    });
+}
+
+/// Accept a signed snapshot from an agent, see `federation.push_secret`.
+pub async fn push_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let Some(secret) = &config::get().federation.push_secret else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    let timestamp = headers
+        .get(TIMESTAMP_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<i64>().ok());
+    let signature = headers
+        .get(SIGNATURE_HEADER)
+        .and_then(|value| value.to_str().ok());
+    let (Some(timestamp), Some(signature)) = (timestamp, signature) else {
+        return error(StatusCode::UNAUTHORIZED, "Missing signature or timestamp");
+    };
+
+    if !is_fresh(timestamp, chrono::Utc::now().timestamp()) {
+        return error(
+            StatusCode::UNAUTHORIZED,
+            "Timestamp is too far off, check the agent's clock",
+        );
+    }
+    if !verify(secret, timestamp, &body, signature) {
+        return error(StatusCode::UNAUTHORIZED, "Invalid signature");
+    }
+
+    match serde_json::from_slice::<PushPayload>(&body) {
+        Ok(payload) => {
+            state.record_push(payload);
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Err(err) => error(StatusCode::BAD_REQUEST, &format!("Invalid snapshot: {err}")),
+    }
+}
+
+fn error(status: StatusCode, message: &str) -> Response {
+    (
+        status,
+        Json(serde_json::json!({
+            "status": "error",
+            "message": message
+        })),
+    )
+        .into_response()
+}
+
+/// Whether a push signed at `timestamp` is recent enough at `now`.
+fn is_fresh(timestamp: i64, now: i64) -> bool {
+    now.abs_diff(timestamp) <= MAX_CLOCK_SKEW
+}
+
+fn mac(secret: &str, timestamp: i64, body: &[u8]) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC should accept keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    mac
+}
+
+/// `sha256=<hex>` HMAC of `<timestamp>.<body>`.
+fn sign(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let digest = mac(secret, timestamp, body).finalize().into_bytes();
+    let hex = digest
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+
+    format!("sha256={hex}")
+}
+
+fn verify(secret: &str, timestamp: i64, body: &[u8], signature: &str) -> bool {
+    let Some(hex) = signature.strip_prefix("sha256=") else {
+        return false;
+    };
+    if hex.len() % 2 != 0 || !hex.is_ascii() {
+        return false;
+    }
+    let Some(expected) = (0..hex.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&hex[index..index + 2], 16).ok())
+        .collect::<Option<Vec<u8>>>()
+    else {
+        return false;
+    };
+
+    // constant time comparison
+    mac(secret, timestamp, body).verify_slice(&expected).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "hunter2";
+    const BODY: &[u8] = br#"{"username":"agent"}"#;
+
+    #[test]
+    fn accepts_its_own_signature() {
+        let signature = sign(SECRET, 1_700_000_000, BODY);
+
+        assert!(signature.starts_with("sha256="));
+        assert!(verify(SECRET, 1_700_000_000, BODY, &signature));
+        assert!(verify(
+            SECRET,
+            1_700_000_000,
+            BODY,
+            &signature.to_ascii_uppercase().replace("SHA256=", "sha256=")
+        ));
+    }
+
+    #[test]
+    fn rejects_anything_else_signed() {
+        let signature = sign(SECRET, 1_700_000_000, BODY);
+
+        assert!(!verify("hunter3", 1_700_000_000, BODY, &signature));
+        assert!(!verify(SECRET, 1_700_000_001, BODY, &signature));
+        assert!(!verify(
+            SECRET,
+            1_700_000_000,
+            br#"{"username":"other"}"#,
+            &signature
+        ));
+    }
+
+    #[test]
+    fn rejects_malformed_signatures() {
+        let signature = sign(SECRET, 1_700_000_000, BODY);
+        let hex = signature.strip_prefix("sha256=").unwrap();
+
+        assert!(!verify(SECRET, 1_700_000_000, BODY, hex));
+        assert!(!verify(SECRET, 1_700_000_000, BODY, &format!("sha1={hex}")));
+        assert!(!verify(
+            SECRET,
+            1_700_000_000,
+            BODY,
+            &signature[..signature.len() - 1]
+        ));
+        assert!(!verify(
+            SECRET,
+            1_700_000_000,
+            BODY,
+            &signature[..signature.len() - 2]
+        ));
+        assert!(!verify(
+            SECRET,
+            1_700_000_000,
+            BODY,
+            &format!("{signature}00")
+        ));
+        assert!(!verify(SECRET, 1_700_000_000, BODY, "sha256="));
+        assert!(!verify(SECRET, 1_700_000_000, BODY, "sha256=zz"));
+        assert!(!verify(SECRET, 1_700_000_000, BODY, "sha256=\u{e9}\u{e9}"));
+    }
+
+    #[test]
+    fn allows_clock_skew_in_both_directions() {
+        let now = 1_700_000_000;
+
+        assert!(is_fresh(now, now));
+        assert!(is_fresh(now - 300, now));
+        assert!(is_fresh(now + 300, now));
+    }
+
+    #[test]
+    fn rejects_stale_and_future_timestamps() {
+        let now = 1_700_000_000;
+
+        assert!(!is_fresh(now - 301, now));
+        assert!(!is_fresh(now + 301, now));
+        assert!(!is_fresh(0, now));
+        assert!(!is_fresh(i64::MIN, now));
+        assert!(!is_fresh(i64::MAX, now));
+    }
+}
@@ -0,0 +1,92 @@
+//! Reloading the configuration file on `SIGHUP`, without restarting the server.
+
+use tokio::signal::unix::{SignalKind, signal};
+
+use crate::{
+    auth,
+    cli::Cli,
+    config::{self, Config},
+    live,
+};
+
+/// Reload the configuration on every `SIGHUP`, e.g. from `systemctl reload helios`.
+///
+/// Most settings are read when they are used, so they apply from the next request or refresh.
+/// The ones only read at startup are logged as needing a restart.
+pub fn spawn_reload_task(cli: Cli) {
+    let mut hangup = match signal(SignalKind::hangup()) {
+        Ok(hangup) => hangup,
+        Err(err) => {
+            tracing::warn!("Not reloading the configuration on SIGHUP: {err}");
+            return;
+        }
+    };
+
+    tokio::spawn(async move {
+        while hangup.recv().await.is_some() {
+            reload(&cli);
+        }
+    });
+}
+
+fn reload(cli: &Cli) {
+    let mut reloaded = match Config::load(cli.config.clone()) {
+        Ok(config) => config,
+        Err(err) => {
+            tracing::error!("Keeping the current configuration, {err}");
+            return;
+        }
+    };
+    cli.apply(&mut reloaded);
+    if let Err(err) = auth::validate(&reloaded.auth) {
+        tracing::error!("Keeping the current configuration, {err}");
+        return;
+    }
+
+    let current = config::get();
+    // the refresh task would forget which alerts are firing and refresh early for nothing
+    if reloaded == *current {
+        tracing::info!("The configuration is unchanged");
+        return;
+    }
+
+    let needs_restart = needs_restart(&current, &reloaded);
+    config::replace(reloaded);
+    live::config_reloaded();
+    tracing::info!("Reloaded the configuration");
+    if !needs_restart.is_empty() {
+        tracing::warn!(
+            "Restart helios to apply the changes to {}",
+            needs_restart.join(", ")
+        );
+    }
+}
+
+/// The changed settings that are only read at startup.
+fn needs_restart(current: &Config, reloaded: &Config) -> Vec<&'static str> {
+    let changed = [
+        ("bind", current.bind != reloaded.bind),
+        ("port", current.port != reloaded.port),
+        ("base_path", current.base_path != reloaded.base_path),
+        ("log_level", current.log_level != reloaded.log_level),
+        (
+            "access_log_level",
+            current.access_log_level != reloaded.access_log_level,
+        ),
+        ("log_format", current.log_format != reloaded.log_format),
+        ("collectors", current.collectors != reloaded.collectors),
+        ("history", current.history != reloaded.history),
+        ("persistence", current.persistence != reloaded.persistence),
+        ("federation", current.federation != reloaded.federation),
+        ("push", current.push != reloaded.push),
+        ("influx", current.influx != reloaded.influx),
+        ("otel", current.otel != reloaded.otel),
+        ("cors", current.cors != reloaded.cors),
+        ("tls", current.tls != reloaded.tls),
+    ];
+
+    changed
+        .into_iter()
+        .filter_map(|(name, changed)| changed.then_some(name))
+        .collect()
+}
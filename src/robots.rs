@@ -0,0 +1,59 @@
+use axum::{
+    http::{HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+
+use crate::config;
+
+/// `/robots.txt`, from `robots.content`.
+pub async fn robots_txt() -> Response {
+    text(config::get().robots.content.clone())
+}
+
+/// `/.well-known/security.txt`, from `[security_txt]`, a 404 until a contact is set.
+pub async fn security_txt() -> Response {
+    let security = &config::get().security_txt;
+    if security.contact.is_empty() {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+
+    let mut fields = security
+        .contact
+        .iter()
+        .map(|contact| format!("Contact: {contact}"))
+        .collect::<Vec<_>>();
+    let expires = security.expires.clone().unwrap_or_else(|| {
+        (chrono::Utc::now() + chrono::Duration::days(365))
+            .to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+    });
+    fields.push(format!("Expires: {expires}"));
+    let optional = [
+        ("Encryption", &security.encryption),
+        ("Policy", &security.policy),
+        ("Acknowledgments", &security.acknowledgments),
+    ];
+    for (name, value) in optional {
+        if let Some(value) = value {
+            fields.push(format!("{name}: {value}"));
+        }
+    }
+    if !security.preferred_languages.is_empty() {
+        fields.push(format!(
+            "Preferred-Languages: {}",
+            security.preferred_languages.join(", ")
+        ));
+    }
+
+    text(fields.join("\n") + "\n")
+}
+
+fn text(body: String) -> Response {
+    (
+        [(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("text/plain; charset=utf-8"),
+        )],
+        body,
+    )
+        .into_response()
+}
@@ -0,0 +1,139 @@
+use std::{path::Path, time::Duration};
+
+use serde::Serialize;
+
+use crate::{config, state::AppState};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// The network is often not up yet right after a boot, so a failed notification is retried.
+const ATTEMPTS: u32 = 5;
+const RETRY_DELAY: Duration = Duration::from_secs(15);
+/// The boot time is derived from the clock and the uptime, and moves when the clock is adjusted.
+const BOOT_TIME_TOLERANCE: i64 = 60;
+
+/// Sent to `startup.webhooks` when helios starts.
+#[derive(Debug, Serialize)]
+struct StartupEvent {
+    event: StartupKind,
+    host: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    os: Option<String>,
+    /// Unix timestamp in seconds.
+    boot_time: i64,
+    /// Boot time of the last run, left out on the first run and without `startup.state_file`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    previous_boot_time: Option<i64>,
+    version: &'static str,
+    /// Unix timestamp in seconds.
+    timestamp: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum StartupKind {
+    /// The machine booted since the last run.
+    Rebooted,
+    /// Only helios restarted, or there was no last run to compare with.
+    Started,
+}
+
+/// Notify `startup.webhooks` that helios started, and whether the machine rebooted since the
+/// last run.
+pub fn spawn_startup_task(state: &AppState) {
+    let startup_config = &config::get().startup;
+    if startup_config.webhooks.is_empty() {
+        return;
+    }
+    if startup_config.reboots_only && startup_config.state_file.is_none() {
+        tracing::warn!("Not notifying about reboots, `startup.state_file` is not set");
+        return;
+    }
+
+    let boot_time = sysinfo::System::boot_time() as i64;
+    let previous_boot_time = startup_config
+        .state_file
+        .as_deref()
+        .and_then(|path| remember_boot_time(path, boot_time));
+    let rebooted = previous_boot_time
+        .is_some_and(|previous| (boot_time - previous).abs() > BOOT_TIME_TOLERANCE);
+    if startup_config.reboots_only && !rebooted {
+        return;
+    }
+
+    let raw = state.latest().raw;
+    let event = StartupEvent {
+        event: if rebooted {
+            StartupKind::Rebooted
+        } else {
+            StartupKind::Started
+        },
+        host: raw.host,
+        os: raw.os.map(|os| os.name),
+        boot_time,
+        previous_boot_time,
+        version: env!("CARGO_PKG_VERSION"),
+        timestamp: chrono::Utc::now().timestamp(),
+    };
+    let body = match serde_json::to_vec(&event) {
+        Ok(body) => body,
+        Err(err) => {
+            tracing::error!("Failed to serialize the startup notification: {err}");
+            return;
+        }
+    };
+    let client = match reqwest::Client::builder().timeout(REQUEST_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(err) => {
+            tracing::error!("Failed to create the startup notification HTTP client: {err}");
+            return;
+        }
+    };
+
+    for url in startup_config.webhooks.clone() {
+        let client = client.clone();
+        let body = body.clone();
+        tokio::spawn(async move {
+            for attempt in 1..=ATTEMPTS {
+                let result = client
+                    .post(&url)
+                    .header(reqwest::header::CONTENT_TYPE, "application/json")
+                    .body(body.clone())
+                    .send()
+                    .await
+                    .and_then(|response| response.error_for_status());
+                match result {
+                    Ok(_) => return,
+                    Err(err) if attempt < ATTEMPTS => {
+                        tracing::debug!("Failed to send the startup notification to {url}: {err}");
+                        tokio::time::sleep(RETRY_DELAY * attempt).await;
+                    }
+                    Err(err) => {
+                        tracing::warn!("Failed to send the startup notification to {url}: {err}");
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// Store `boot_time` in `path`, returning the one stored by the last run.
+fn remember_boot_time(path: &Path, boot_time: i64) -> Option<i64> {
+    let previous = match std::fs::read_to_string(path) {
+        Ok(contents) => contents.trim().parse().ok(),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+        Err(err) => {
+            tracing::warn!(
+                "Failed to read the last boot time from {}: {err}",
+                path.display()
+            );
+            None
+        }
+    };
+    if previous != Some(boot_time)
+        && let Err(err) = std::fs::write(path, format!("{boot_time}\n"))
+    {
+        tracing::warn!("Failed to store the boot time in {}: {err}", path.display());
+    }
+
+    previous
+}
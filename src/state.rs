@@ -0,0 +1,254 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, RwLock},
+};
+
+use axum::http::{HeaderMap, header};
+use serde::Deserialize;
+
+#[cfg(feature = "sqlite")]
+use crate::persistence::MetricStore;
+use crate::{
+    collectors,
+    config::{self, DurationStyle},
+    federation::PeerStatus,
+    history::{History, HistorySample},
+    i18n::{self, Locale},
+    push::PushPayload,
+    sysgetter::{self, SystemInfo, SystemInfoRaw},
+};
+
+/// Rendered lines kept for `/api/v1/info/delta`, 10 minutes at the default refresh interval.
+const RECENT_SNAPSHOTS: usize = 60;
+
+/// State shared by every handler, the HTML page and the APIs all read the same snapshot.
+#[derive(Clone)]
+pub struct AppState {
+    latest: Arc<RwLock<Snapshot>>,
+    history: Arc<RwLock<History>>,
+    /// Lines of the last snapshots with their `collected_at`, oldest first.
+    recent: Arc<RwLock<VecDeque<(i64, SystemInfo)>>>,
+    peers: Arc<RwLock<Vec<PeerStatus>>>,
+    #[cfg(feature = "sqlite")]
+    store: Option<Arc<MetricStore>>,
+}
+
+/// One collection, both as structured data and as rendered display lines.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    /// Unix timestamp in seconds of when the collection finished.
+    pub collected_at: i64,
+    pub raw: SystemInfoRaw,
+    pub info: SystemInfo,
+}
+
+impl Snapshot {
+    pub async fn collect() -> Self {
+        let registry = collectors::registry();
+        let raw = registry.collect().await;
+        let info = registry.render(&raw);
+
+        Snapshot {
+            collected_at: chrono::Utc::now().timestamp(),
+            raw,
+            info,
+        }
+    }
+
+    /// The display lines in `locale` with durations in `durations`, rendered again from `raw`
+    /// unless they are the English and full ones of `info`.
+    pub fn info_in(&self, locale: Locale, durations: DurationStyle) -> SystemInfo {
+        if locale == Locale::En && durations == DurationStyle::Full {
+            return self.info.clone();
+        }
+
+        i18n::with_locale(locale, || {
+            sysgetter::with_duration_style(durations, || collectors::registry().render(&self.raw))
+        })
+    }
+}
+
+/// `?duration=`, overriding the configured style of the output format.
+#[derive(Debug, Default, Deserialize)]
+pub struct DurationQuery {
+    pub duration: Option<DurationStyle>,
+}
+
+/// The locale the display lines of a request are rendered in, see [`i18n::negotiate`].
+pub fn request_locale(headers: &HeaderMap) -> Locale {
+    i18n::negotiate(
+        headers
+            .get(header::ACCEPT_LANGUAGE)
+            .and_then(|value| value.to_str().ok()),
+    )
+}
+
+impl AppState {
+    /// Create the state with a first snapshot, so handlers never see an empty one.
+    pub async fn new() -> Self {
+        let snapshot = Snapshot::collect().await;
+        let history_config = &config::get().history;
+        let mut history = History::new(history_config.retention, &history_config.tiers);
+
+        #[cfg(feature = "sqlite")]
+        let store = open_store();
+        // pick up where the previous run left off
+        #[cfg(feature = "sqlite")]
+        if let Some(store) = &store {
+            // enough stored snapshots to fill the longest tier again
+            let longest = history_config
+                .tiers
+                .iter()
+                .map(|tier| tier.retention / config::get().persistence.interval.max(1))
+                .max()
+                .unwrap_or_default();
+            let limit = history_config.retention.max(longest as usize);
+            match store.range(None, None, limit) {
+                Ok(samples) => samples.into_iter().for_each(|sample| history.push(sample)),
+                Err(err) => tracing::warn!("Failed to load stored history: {err}"),
+            }
+        }
+
+        history.push(HistorySample::from_raw(
+            snapshot.collected_at,
+            &snapshot.raw,
+        ));
+
+        let recent = VecDeque::from([(snapshot.collected_at, snapshot.info.clone())]);
+
+        AppState {
+            latest: Arc::new(RwLock::new(snapshot)),
+            history: Arc::new(RwLock::new(history)),
+            recent: Arc::new(RwLock::new(recent)),
+            peers: Arc::new(RwLock::new(
+                config::get()
+                    .federation
+                    .peers
+                    .iter()
+                    .map(PeerStatus::new)
+                    .collect(),
+            )),
+            #[cfg(feature = "sqlite")]
+            store,
+        }
+    }
+
+    /// When the most recent snapshot was collected, without cloning it.
+    pub fn collected_at(&self) -> i64 {
+        self.latest
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .collected_at
+    }
+
+    /// The most recent snapshot from the refresh task.
+    pub fn latest(&self) -> Snapshot {
+        self.latest
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Store a new snapshot and record it in the history.
+    pub fn replace(&self, snapshot: Snapshot) {
+        let sample = HistorySample::from_raw(snapshot.collected_at, &snapshot.raw);
+        self.history
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(sample);
+        {
+            let mut recent = self.recent.write().unwrap_or_else(|e| e.into_inner());
+            if recent.len() == RECENT_SNAPSHOTS {
+                recent.pop_front();
+            }
+            recent.push_back((snapshot.collected_at, snapshot.info.clone()));
+        }
+        *self.latest.write().unwrap_or_else(|e| e.into_inner()) = snapshot;
+    }
+
+    /// Lines of the newest kept snapshot collected at or before `since`, `None` when every kept
+    /// one is newer.
+    pub fn info_at(&self, since: i64) -> Option<SystemInfo> {
+        self.recent
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .rev()
+            .find(|(collected_at, _)| *collected_at <= since)
+            .map(|(_, info)| info.clone())
+    }
+
+    /// Recorded samples between `from` and `to`, see [`History::range`].
+    pub fn history(&self, from: Option<i64>, to: Option<i64>) -> Vec<HistorySample> {
+        self.history
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .range(from, to)
+    }
+
+    /// Last known state of every federation peer, the configured ones first.
+    ///
+    /// Pushing peers that went quiet for `federation.push_timeout` are marked unreachable.
+    pub fn peers(&self) -> Vec<PeerStatus> {
+        let mut peers = self.peers.read().unwrap_or_else(|e| e.into_inner()).clone();
+        let now = chrono::Utc::now().timestamp();
+        let push_timeout = config::get().federation.push_timeout as i64;
+        for peer in peers.iter_mut().filter(|peer| peer.url.is_none()) {
+            let silent_for = now - peer.last_seen.unwrap_or(now);
+            if silent_for > push_timeout {
+                peer.error = Some(format!("no push for {silent_for}s"));
+            }
+        }
+
+        peers
+    }
+
+    /// Store a snapshot pushed by an agent, replacing the previous one of the same host.
+    pub fn record_push(&self, payload: PushPayload) {
+        let mut peers = self.peers.write().unwrap_or_else(|e| e.into_inner());
+        let pushed = PeerStatus::pushed(payload.username, payload.info);
+        let existing = peers.iter_mut().find(|peer| {
+            peer.url.is_none() && peer.username == pushed.username && peer.host() == pushed.host()
+        });
+
+        match existing {
+            Some(peer) => *peer = pushed,
+            None => peers.push(pushed),
+        }
+    }
+
+    /// Record the outcome of fetching the peer at `index`, keeping its info when it failed.
+    pub fn update_peer(&self, index: usize, result: Result<SystemInfo, String>) {
+        let mut peers = self.peers.write().unwrap_or_else(|e| e.into_inner());
+        let Some(peer) = peers.get_mut(index) else {
+            return;
+        };
+
+        match result {
+            Ok(info) => {
+                peer.info = Some(info);
+                peer.last_seen = Some(chrono::Utc::now().timestamp());
+                peer.error = None;
+            }
+            Err(err) => peer.error = Some(err),
+        }
+    }
+
+    /// The SQLite store, `None` when `persistence.path` is unset.
+    #[cfg(feature = "sqlite")]
+    pub fn store(&self) -> Option<Arc<MetricStore>> {
+        self.store.clone()
+    }
+}
+
+#[cfg(feature = "sqlite")]
+fn open_store() -> Option<Arc<MetricStore>> {
+    let path = config::get().persistence.path.clone()?;
+    match MetricStore::open(&path) {
+        Ok(store) => Some(Arc::new(store)),
+        Err(err) => {
+            tracing::error!("Failed to open {}: {err}", path.display());
+            None
+        }
+    }
+}
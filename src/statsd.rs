@@ -0,0 +1,170 @@
+use std::net::UdpSocket;
+
+use crate::{config, sysgetter::SystemInfoRaw};
+
+/// Keeps the datagrams below the usual MTU so they aren't fragmented.
+const MAX_PACKET_SIZE: usize = 1432;
+
+/// Sends the metrics of every snapshot to a StatsD server as gauges.
+pub struct Statsd {
+    socket: UdpSocket,
+    prefix: String,
+}
+
+impl Statsd {
+    /// `None` when no `statsd.address` is configured or it can't be resolved.
+    pub fn from_config() -> Option<Self> {
+        let statsd_config = &config::get().statsd;
+        let address = statsd_config.address.as_ref()?;
+
+        let socket = UdpSocket::bind("0.0.0.0:0")
+            .and_then(|socket| socket.connect(address).map(|_| socket))
+            .or_else(|_| {
+                // an IPv6-only address needs an IPv6 socket
+                let socket = UdpSocket::bind("[::]:0")?;
+                socket.connect(address)?;
+                Ok::<_, std::io::Error>(socket)
+            })
+            .and_then(|socket| socket.set_nonblocking(true).map(|_| socket));
+        let socket = match socket {
+            Ok(socket) => socket,
+            Err(err) => {
+                tracing::warn!("Not sending metrics to StatsD at {address}: {err}");
+                return None;
+            }
+        };
+
+        tracing::info!("Sending metrics to StatsD at {address}");
+        Some(Statsd {
+            socket,
+            prefix: statsd_config.prefix.clone(),
+        })
+    }
+
+    /// Send every metric of `raw`, packed into as few datagrams as possible.
+    pub fn send(&self, raw: &SystemInfoRaw) {
+        let prefix = self.prefix.replace("{host}", &sanitize(&raw.host));
+        let mut packet = String::new();
+
+        for (name, value) in gauges(raw) {
+            let line = format!("{prefix}.{name}:{value}|g");
+            if !packet.is_empty() && packet.len() + line.len() + 1 > MAX_PACKET_SIZE {
+                self.send_packet(&packet);
+                packet.clear();
+            }
+            if !packet.is_empty() {
+                packet.push('\n');
+            }
+            packet.push_str(&line);
+        }
+
+        if !packet.is_empty() {
+            self.send_packet(&packet);
+        }
+    }
+
+    fn send_packet(&self, packet: &str) {
+        if let Err(err) = self.socket.send(packet.as_bytes()) {
+            tracing::warn!("Failed to send metrics to StatsD: {err}");
+        }
+    }
+}
+
+/// Every gauge of `raw` as a dotted name and its value.
+fn gauges(raw: &SystemInfoRaw) -> Vec<(String, f64)> {
+    let mut gauges = vec![];
+
+    if let Some(os) = &raw.os {
+        gauges.push(("uptime_seconds".to_string(), os.uptime_seconds as f64));
+    }
+    if let Some(cpu) = &raw.cpu {
+        gauges.push((
+            "cpu.usage_percent".to_string(),
+            f64::from(cpu.usage_percent),
+        ));
+        gauges.push(("cpu.frequency_mhz".to_string(), cpu.frequency_mhz as f64));
+    }
+    for (name, usage) in [("memory", &raw.memory), ("swap", &raw.swap)] {
+        if let Some(usage) = usage {
+            gauges.push((format!("{name}.total_bytes"), usage.total_bytes as f64));
+            gauges.push((format!("{name}.used_bytes"), usage.used_bytes as f64));
+            gauges.push((format!("{name}.usage_percent"), usage.usage_percent));
+        }
+    }
+    for disk in raw.disks.iter().flatten() {
+        let mount = sanitize(&disk.mount_point);
+        gauges.push((format!("disk.{mount}.total_bytes"), disk.total_bytes as f64));
+        gauges.push((format!("disk.{mount}.used_bytes"), disk.used_bytes as f64));
+        gauges.push((format!("disk.{mount}.usage_percent"), disk.usage_percent));
+    }
+    if let Some(disk_io) = &raw.disk_io {
+        if let Some(rate) = disk_io.read_bytes_per_sec {
+            gauges.push(("disk_io.read_bytes_per_sec".to_string(), rate as f64));
+        }
+        if let Some(rate) = disk_io.write_bytes_per_sec {
+            gauges.push(("disk_io.write_bytes_per_sec".to_string(), rate as f64));
+        }
+    }
+    if let Some(network) = &raw.network {
+        if let Some(rate) = network.rx_bytes_per_sec {
+            gauges.push(("network.rx_bytes_per_sec".to_string(), rate as f64));
+        }
+        if let Some(rate) = network.tx_bytes_per_sec {
+            gauges.push(("network.tx_bytes_per_sec".to_string(), rate as f64));
+        }
+        for interface in &network.interfaces {
+            let name = sanitize(&interface.name);
+            if let Some(rate) = interface.rx_bytes_per_sec {
+                gauges.push((format!("network.{name}.rx_bytes_per_sec"), rate as f64));
+            }
+            if let Some(rate) = interface.tx_bytes_per_sec {
+                gauges.push((format!("network.{name}.tx_bytes_per_sec"), rate as f64));
+            }
+        }
+    }
+    for sensor in raw.temperatures.iter().flatten() {
+        gauges.push((
+            format!("temperature.{}", sanitize(&sensor.label)),
+            f64::from(sensor.celsius),
+        ));
+    }
+    for battery in raw.batteries.iter().flatten() {
+        gauges.push((
+            format!("battery.{}.capacity_percent", sanitize(&battery.name)),
+            f64::from(battery.capacity_percent),
+        ));
+    }
+    if let Some(containers) = &raw.containers {
+        gauges.push(("containers.running".to_string(), containers.running as f64));
+        gauges.push(("containers.total".to_string(), containers.total as f64));
+    }
+    if let Some(services) = &raw.services {
+        gauges.push(("systemd.failed_units".to_string(), services.failed as f64));
+    }
+
+    gauges.retain(|(_, value)| value.is_finite());
+    gauges
+}
+
+/// Make a host, mount point, or device usable as one segment of a dotted name.
+///
+/// `/` becomes `root` and `/var/lib` becomes `var_lib`.
+fn sanitize(segment: &str) -> String {
+    let sanitized = segment
+        .trim_matches('/')
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || ch == '-' {
+                ch
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+
+    if sanitized.is_empty() {
+        "root".to_string()
+    } else {
+        sanitized
+    }
+}
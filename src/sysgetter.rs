@@ -1,7 +1,18 @@
-use std::{collections::HashSet, net::IpAddr, sync::LazyLock};
+use std::{
+    borrow::Cow,
+    cell::Cell,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    sync::LazyLock,
+};
 
-use serde::Serialize;
-use sysinfo::{Disks, Networks, System};
+use serde::{Deserialize, Serialize};
+use sysinfo::System;
+
+use crate::{
+    config::{self, ByteUnits, DurationStyle, HostCheck},
+    i18n::{self, TimeUnit},
+    logo::LOGO,
+};
 
 const MAC_VERSIONS: [(&str, &str, &str); 23] = [
     ("26", "macOS", "Tahoe"),
@@ -31,9 +42,95 @@ const MAC_VERSIONS: [(&str, &str, &str); 23] = [
     ("10.0", "Mac OS X", "Cheetah"),
 ];
 
-static CACHED_HOST: LazyLock<String> = LazyLock::new(get_pc_host);
-static KERNEL_LONG_VER: LazyLock<String> = LazyLock::new(System::kernel_long_version);
-static OS_NAME: LazyLock<String> = LazyLock::new(|| {
+/// `hw.model` identifiers of Apple silicon Macs and their marketing names, as listed on
+/// <https://support.apple.com/en-us/102852>.
+const MAC_MODELS: [(&str, &str); 47] = [
+    ("MacBookAir10,1", "MacBook Air (M1, 2020)"),
+    ("MacBookPro17,1", "MacBook Pro (13-inch, M1, 2020)"),
+    ("Macmini9,1", "Mac mini (M1, 2020)"),
+    ("iMac21,1", "iMac (24-inch, M1, 2021)"),
+    ("iMac21,2", "iMac (24-inch, M1, 2021)"),
+    ("MacBookPro18,1", "MacBook Pro (16-inch, 2021)"),
+    ("MacBookPro18,2", "MacBook Pro (16-inch, 2021)"),
+    ("MacBookPro18,3", "MacBook Pro (14-inch, 2021)"),
+    ("MacBookPro18,4", "MacBook Pro (14-inch, 2021)"),
+    ("Mac13,1", "Mac Studio (M1 Max, 2022)"),
+    ("Mac13,2", "Mac Studio (M1 Ultra, 2022)"),
+    ("Mac14,2", "MacBook Air (M2, 2022)"),
+    ("Mac14,7", "MacBook Pro (13-inch, M2, 2022)"),
+    ("Mac14,3", "Mac mini (M2, 2023)"),
+    ("Mac14,12", "Mac mini (M2 Pro, 2023)"),
+    ("Mac14,5", "MacBook Pro (14-inch, 2023)"),
+    ("Mac14,9", "MacBook Pro (14-inch, 2023)"),
+    ("Mac14,6", "MacBook Pro (16-inch, 2023)"),
+    ("Mac14,10", "MacBook Pro (16-inch, 2023)"),
+    ("Mac14,15", "MacBook Air (15-inch, M2, 2023)"),
+    ("Mac14,13", "Mac Studio (M2 Max, 2023)"),
+    ("Mac14,14", "Mac Studio (M2 Ultra, 2023)"),
+    ("Mac14,8", "Mac Pro (2023)"),
+    ("Mac15,3", "MacBook Pro (14-inch, M3, Nov 2023)"),
+    ("Mac15,4", "iMac (24-inch, M3, 2023)"),
+    ("Mac15,5", "iMac (24-inch, M3, 2023)"),
+    (
+        "Mac15,6",
+        "MacBook Pro (14-inch, M3 Pro or M3 Max, Nov 2023)",
+    ),
+    (
+        "Mac15,8",
+        "MacBook Pro (14-inch, M3 Pro or M3 Max, Nov 2023)",
+    ),
+    (
+        "Mac15,10",
+        "MacBook Pro (14-inch, M3 Pro or M3 Max, Nov 2023)",
+    ),
+    ("Mac15,7", "MacBook Pro (16-inch, Nov 2023)"),
+    ("Mac15,9", "MacBook Pro (16-inch, Nov 2023)"),
+    ("Mac15,11", "MacBook Pro (16-inch, Nov 2023)"),
+    ("Mac15,12", "MacBook Air (13-inch, M3, 2024)"),
+    ("Mac15,13", "MacBook Air (15-inch, M3, 2024)"),
+    ("Mac15,14", "Mac Studio (2025)"),
+    ("Mac16,1", "MacBook Pro (14-inch, M4, 2024)"),
+    ("Mac16,6", "MacBook Pro (14-inch, M4 Pro or M4 Max, 2024)"),
+    ("Mac16,8", "MacBook Pro (14-inch, M4 Pro or M4 Max, 2024)"),
+    ("Mac16,5", "MacBook Pro (16-inch, 2024)"),
+    ("Mac16,7", "MacBook Pro (16-inch, 2024)"),
+    ("Mac16,2", "iMac (24-inch, 2024)"),
+    ("Mac16,3", "iMac (24-inch, 2024)"),
+    ("Mac16,10", "Mac mini (2024)"),
+    ("Mac16,11", "Mac mini (2024)"),
+    ("Mac16,12", "MacBook Air (13-inch, M4, 2025)"),
+    ("Mac16,13", "MacBook Air (15-inch, M4, 2025)"),
+    ("Mac16,9", "Mac Studio (2025)"),
+];
+
+const ANSI_RESET: &str = "\x1b[0m";
+// bold #dfacac, matches `.host-header`
+const ANSI_HOST: &str = "\x1b[1;38;2;223;172;172m";
+// bold #acacfc, matches `.detail-line-root`
+const ANSI_KEY: &str = "\x1b[1;38;2;172;172;252m";
+// `.palette-1` to `.palette-16` from style.css
+pub const PALETTE: [(u8, u8, u8); 16] = [
+    (0x51, 0xba, 0xfc),
+    (0xac, 0xac, 0xfc),
+    (0x7c, 0x80, 0xd4),
+    (0x54, 0x54, 0x80),
+    (0x54, 0x54, 0xf3),
+    (0x80, 0x2c, 0xd4),
+    (0x04, 0x32, 0xc0),
+    (0xe3, 0xb3, 0x54),
+    (0xdb, 0xa0, 0x04),
+    (0xdf, 0xac, 0xac),
+    (0xaf, 0x54, 0x57),
+    (0xb0, 0x04, 0x43),
+    (0x54, 0x04, 0x1a),
+    (0xe0, 0xfc, 0xc5),
+    (0x3e, 0xac, 0xac),
+    (0x04, 0x54, 0x54),
+];
+
+pub(crate) static CACHED_HOST: LazyLock<String> = LazyLock::new(get_pc_host);
+pub(crate) static KERNEL_LONG_VER: LazyLock<String> = LazyLock::new(System::kernel_long_version);
+pub(crate) static OS_NAME: LazyLock<String> = LazyLock::new(|| {
     let os_name = System::name().unwrap_or_else(|| "Unknown".to_string());
 
     let mut actual_os_name = os_name.clone();
@@ -55,305 +152,791 @@ static OS_NAME: LazyLock<String> = LazyLock::new(|| {
 
     actual_os_name
 });
-static HOSTNAME: LazyLock<String> =
+pub static HOSTNAME: LazyLock<String> =
     LazyLock::new(|| System::host_name().unwrap_or_else(|| "unknown.local".to_string()));
+pub(crate) static SHELL_NAME: LazyLock<Option<String>> = LazyLock::new(get_default_shell);
+pub(crate) static INIT_SYSTEM: LazyLock<Option<String>> = LazyLock::new(get_init_system);
+pub(crate) static DESKTOP_ENVIRONMENT: LazyLock<Option<String>> =
+    LazyLock::new(get_desktop_environment);
+pub(crate) static VIRTUALIZATION: LazyLock<Option<String>> = LazyLock::new(get_virtualization);
+pub(crate) static TIMEZONE: LazyLock<Option<String>> = LazyLock::new(get_timezone);
+pub(crate) static BOARD: LazyLock<Option<String>> = LazyLock::new(get_board);
+pub(crate) static FIRMWARE: LazyLock<Option<String>> = LazyLock::new(get_firmware);
 
 // some static information about the system
 // static VIRT_HOST: &str
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LineInfo {
     key: String,
     value: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<LineStatus>,
+    /// UTC offset in seconds of a line showing the local time, the page keeps it ticking.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    clock_offset: Option<i32>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+/// Highlights a line that needs attention, e.g. a failing disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineStatus {
+    Warning,
+    Critical,
+}
+
+impl LineStatus {
+    fn ansi_color(&self) -> &'static str {
+        match self {
+            // #e3b354, `.palette-8`
+            LineStatus::Warning => "\x1b[38;2;227;179;84m",
+            // #af5457, `.palette-11`
+            LineStatus::Critical => "\x1b[38;2;175;84;87m",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemInfo {
     host: String,
     lines: Vec<LineInfo>,
 }
 
-impl From<(String, String)> for LineInfo {
-    fn from(tuple: (String, String)) -> Self {
+/// Lines that differ between two [`SystemInfo`] snapshots.
+#[derive(Debug, Clone, Serialize)]
+pub struct SystemInfoDelta {
+    changed: Vec<LineInfo>,
+    removed: Vec<String>,
+}
+
+impl SystemInfoDelta {
+    pub fn is_empty(&self) -> bool {
+        self.changed.is_empty() && self.removed.is_empty()
+    }
+}
+
+impl LineInfo {
+    pub fn new(key: impl Into<String>, value: impl Into<String>) -> Self {
         LineInfo {
-            key: tuple.0,
-            value: tuple.1,
+            key: key.into(),
+            value: value.into(),
+            status: None,
+            clock_offset: None,
         }
     }
-}
 
-impl SystemInfo {
-    pub fn as_html_info(&self) -> String {
-        let mut html = String::new();
-        // host
-        html.push_str(r#"<p class="host-header">noaione<span class="host-at">@</span>"#);
-        html.push_str(&self.host);
-        html.push_str("</p>\n");
-
-        // create separator
-        // noaione@ = 8 len
-        let host_length = self.host.len() + 8;
-        html.push_str(r#"<p class="detail-line">"#);
-        html.push_str(&"-".repeat(host_length));
-        html.push_str("</p>\n");
+    pub fn with_status(mut self, status: Option<LineStatus>) -> Self {
+        self.status = status;
+        self
+    }
 
-        for line in &self.lines {
-            html.push_str(r#"<p class="detail-line"><span class="detail-line-root">"#);
-            html.push_str(&line.key);
-            html.push_str("</span>: ");
-            html.push_str(&line.value);
-            html.push_str("</p>\n");
+    pub fn with_clock_offset(mut self, clock_offset: i32) -> Self {
+        self.clock_offset = Some(clock_offset);
+        self
+    }
+
+    /// The label translated to `locale`, see [`i18n::Locale::label`].
+    pub fn localized(mut self, locale: i18n::Locale) -> Self {
+        if let Cow::Owned(key) = locale.label(&self.key) {
+            self.key = key;
         }
+        self
+    }
 
-        html
+    pub fn key(&self) -> &str {
+        &self.key
     }
-}
 
-/// Not a future, but a function that retrieves system information.
-pub fn get_system_info_by_lines_unlocked() -> SystemInfo {
-    let mut sys = System::new_all();
-    sys.refresh_all();
+    pub fn value(&self) -> &str {
+        &self.value
+    }
 
-    let mut merged_lines: Vec<(String, String)> = vec![];
+    pub fn status(&self) -> Option<LineStatus> {
+        self.status
+    }
+}
 
-    // Get OS name
-    let actual_os_name = OS_NAME.clone();
-    merged_lines.push(("OS".to_string(), actual_os_name));
+impl From<(String, String)> for LineInfo {
+    fn from(tuple: (String, String)) -> Self {
+        LineInfo::new(tuple.0, tuple.1)
+    }
+}
 
-    // Get hostname
-    let pc_host = CACHED_HOST.clone();
-    if !pc_host.is_empty() {
-        merged_lines.push(("Host".to_string(), pc_host));
+impl SystemInfo {
+    pub fn new(host: String, lines: Vec<LineInfo>) -> Self {
+        SystemInfo { host, lines }
     }
 
-    // Get kernel
-    let kernel_version = KERNEL_LONG_VER.clone();
-    merged_lines.push(("Kernel".to_string(), kernel_version));
+    pub fn host(&self) -> &str {
+        &self.host
+    }
 
-    // Get system uptime (in seconds, convert to human readable)
-    let uptime_seconds = System::uptime();
-    let uptime_str = format_uptime(uptime_seconds);
+    pub fn lines(&self) -> &[LineInfo] {
+        &self.lines
+    }
 
-    merged_lines.push(("Uptime".to_string(), uptime_str));
+    /// Compare against an older snapshot, returning new or modified lines and the keys that disappeared.
+    pub fn diff(&self, previous: &SystemInfo) -> SystemInfoDelta {
+        let changed = self
+            .lines
+            .iter()
+            .filter(|line| !previous.lines.contains(line))
+            .cloned()
+            .collect();
+        let removed = previous
+            .lines
+            .iter()
+            .filter(|old| !self.lines.iter().any(|line| line.key == old.key))
+            .map(|old| old.key.clone())
+            .collect();
 
-    // Get CPU information
-    let cpus = sys.cpus();
-    let cpu_count = cpus.len();
-    if !cpus.is_empty() {
-        let cpu = &cpus[0];
-        let cpu_brand = cpu.brand().to_string();
-        let cpu_freq = calculate_cpu_freq(cpu.frequency());
-        merged_lines.push((
-            "CPU".to_string(),
-            format!("{cpu_brand} ({cpu_count}) @ {cpu_freq}"),
-        ))
+        SystemInfoDelta { changed, removed }
     }
 
-    let total_memory = sys.total_memory();
-    let used_memory = sys.used_memory();
-    let memory_usage = if total_memory > 0 {
-        (used_memory as f64 / total_memory as f64) * 100.0
-    } else {
-        0.0
-    };
-
-    merged_lines.push((
-        "Memory".to_string(),
-        format!(
-            "{} / {} ({:.1}%)",
-            format_bytes(used_memory),
-            format_bytes(total_memory),
-            memory_usage
-        ),
-    ));
-
-    let total_swap = sys.total_swap();
-    let used_swap = sys.used_swap();
-    let swap_usage = if total_swap > 0 {
-        (used_swap as f64 / total_swap as f64) * 100.0
-    } else {
-        0.0
-    };
+    /// Render the info as neofetch-like plaintext, with the values aligned.
+    ///
+    /// When `ansi` is set the header, keys, and a color palette are drawn with ANSI escape codes.
+    /// The distro logo is drawn to the left of the info, like neofetch.
+    pub fn as_text_info(&self, ansi: bool) -> String {
+        let mut rows: Vec<String> = vec![];
+        let username = &config::get().username;
+        let paint = |color: &str, value: &str| {
+            if ansi {
+                format!("{color}{value}{ANSI_RESET}")
+            } else {
+                value.to_string()
+            }
+        };
 
-    if total_swap > 0 {
-        merged_lines.push((
-            "Swap".to_string(),
-            format!(
-                "{} / {} ({:.1}%)",
-                format_bytes(used_swap),
-                format_bytes(total_swap),
-                swap_usage
-            ),
+        rows.push(format!(
+            "{}@{}",
+            paint(ANSI_HOST, username),
+            paint(ANSI_HOST, &self.host)
         ));
-    } else {
-        merged_lines.push(("Swap".to_string(), "Disabled".to_string()));
-    }
-
-    let disks = Disks::new_with_refreshed_list();
-    let mut mounted: HashSet<String> = HashSet::new();
-    let mut disk_lines: Vec<(String, String)> = vec![];
-    for disk in &disks {
-        let total_space = disk.total_space();
-        let available_space = disk.available_space();
-        let used_space = total_space - available_space;
-        let usage_percent = if total_space > 0 {
-            (used_space as f64 / total_space as f64) * 100.0
-        } else {
-            0.0
-        };
+        rows.push("-".repeat(self.host.len() + username.chars().count() + 1));
 
-        let disk_name = disk.name().to_string_lossy().to_string();
-        if mounted.contains(&disk_name) {
-            continue; // Skip already processed disks
+        let key_width = self
+            .lines
+            .iter()
+            .map(|line| line.key.chars().count())
+            .max()
+            .unwrap_or(0);
+        for line in &self.lines {
+            let padding = key_width - line.key.chars().count();
+            let value = match line.status {
+                Some(status) => paint(status.ansi_color(), &line.value),
+                None => line.value.clone(),
+            };
+            rows.push(format!(
+                "{}:{}{value}",
+                paint(ANSI_KEY, &line.key),
+                " ".repeat(padding + 1)
+            ));
         }
-        mounted.insert(disk_name);
 
-        let file_system = disk.file_system().to_string_lossy();
-        if file_system.is_empty() {
-            continue; // Skip disks without a file system
+        if ansi {
+            // same palette as the web page, two rows of eight blocks
+            rows.push(String::new());
+            for row in PALETTE.chunks(8) {
+                let mut blocks = String::new();
+                for (r, g, b) in row {
+                    blocks.push_str(&format!("\x1b[48;2;{r};{g};{b}m   "));
+                }
+                blocks.push_str(ANSI_RESET);
+                rows.push(blocks);
+            }
         }
-        match file_system.as_ref() {
-            "tmpfs" | "devtmpfs" | "overlay" | "squashfs" => continue, // Skip temporary or special filesystems
-            _ => {}
+
+        let mut text = String::new();
+        match *LOGO {
+            Some(logo) => {
+                let logo_width = logo.width();
+                let logo_color = logo.ansi_color();
+                let mut logo_lines = logo.lines();
+                for row in rows {
+                    let logo_line = logo_lines.next().unwrap_or_default();
+                    let padding = logo_width - logo_line.chars().count();
+                    text.push_str(&paint(&logo_color, logo_line));
+                    text.push_str(&" ".repeat(padding + 3));
+                    text.push_str(&row);
+                    text.push('\n');
+                }
+                // the logo can be taller than the info
+                for logo_line in logo_lines {
+                    text.push_str(&paint(&logo_color, logo_line));
+                    text.push('\n');
+                }
+            }
+            None => {
+                for row in rows {
+                    text.push_str(&row);
+                    text.push('\n');
+                }
+            }
         }
 
-        disk_lines.push((
-            format!(
-                "{} / {} ({:.1}%) - {file_system}",
-                format_bytes(used_space),
-                format_bytes(total_space),
-                usage_percent
-            ),
-            disk.mount_point().to_string_lossy().to_string(),
-        ));
+        text
     }
 
-    let disk_total = disk_lines.len();
-    if disk_total > 0 {
-        for (line, mnt_point) in &disk_lines {
-            let disk_key = if disk_total > 1 {
-                format!("Disk ({})", mnt_point)
-            } else {
-                "Disk".to_string()
-            };
+    /// Render the info as a Markdown table under a `username@host` heading.
+    ///
+    /// Lines that need attention get their status appended, since Markdown has no colors.
+    pub fn as_markdown(&self) -> String {
+        let username = &config::get().username;
+        let mut markdown = format!(
+            "### {}@{}\n\n| Key | Value |\n| --- | --- |\n",
+            escape_markdown(username),
+            escape_markdown(&self.host)
+        );
 
-            merged_lines.push((disk_key, line.to_string()));
+        for line in &self.lines {
+            let value = escape_markdown(&line.value);
+            let value = match line.status {
+                Some(LineStatus::Warning) => format!("**{value}** _(warning)_"),
+                Some(LineStatus::Critical) => format!("**{value}** _(critical)_"),
+                None => value,
+            };
+            markdown.push_str(&format!(
+                "| **{}** | {value} |\n",
+                escape_markdown(&line.key)
+            ));
         }
+
+        markdown
     }
+}
 
-    let networks = Networks::new_with_refreshed_list();
-    let mut valid_ipv4 = 0;
-    let mut valid_ipv6 = 0;
-    for (_, network) in &networks {
-        let ip_address = network.ip_networks();
-        for ip in ip_address {
-            match ip.addr {
-                IpAddr::V4(addr) => {
-                    if !addr.is_broadcast()
-                        && !addr.is_documentation()
-                        && !addr.is_link_local()
-                        && !addr.is_loopback()
-                        && !addr.is_multicast()
-                        && !addr.is_unspecified()
-                        && !addr.is_private()
-                    {
-                        valid_ipv4 += 1;
-                    }
-                }
-                IpAddr::V6(addr) => {
-                    if !addr.is_loopback()
-                        && !addr.is_multicast()
-                        && !addr.is_unicast_link_local()
-                        && !addr.is_unique_local()
-                        && !addr.is_unspecified()
-                    {
-                        valid_ipv6 += 1;
-                    }
-                }
-            }
+/// Escape the characters that would break out of a table cell or start formatting.
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        if matches!(
+            ch,
+            '\\' | '|' | '*' | '_' | '`' | '[' | ']' | '<' | '>' | '#'
+        ) {
+            escaped.push('\\');
         }
+        escaped.push(ch);
     }
+    escaped
+}
 
-    if valid_ipv4 > 0 || valid_ipv6 > 0 {
-        let mut string_data = vec![];
-        if valid_ipv4 > 0 {
-            string_data.push(format!("{valid_ipv4}x IPv4"));
-        }
-        if valid_ipv6 > 0 {
-            string_data.push(format!("{valid_ipv6}x IPv6"));
+/// Structured, unformatted view of the system used by the JSON API.
+///
+/// Each section is filled in by its collector, disabled collectors leave theirs empty.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SystemInfoRaw {
+    pub host: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub os: Option<OsInfoRaw>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub packages: Option<Vec<PackageCountRaw>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session: Option<SessionInfoRaw>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu: Option<CpuInfoRaw>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory: Option<UsageInfoRaw>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub swap: Option<UsageInfoRaw>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub swap_devices: Option<Vec<SwapDeviceRaw>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disks: Option<Vec<DiskInfoRaw>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disk_io: Option<DiskIoInfoRaw>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub smart: Option<Vec<SmartDiskRaw>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raid: Option<Vec<RaidArrayRaw>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network: Option<NetworkInfoRaw>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub public_ip: Option<PublicIpRaw>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperatures: Option<Vec<TemperatureInfoRaw>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub batteries: Option<Vec<BatteryInfoRaw>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub containers: Option<ContainersInfoRaw>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub services: Option<ServicesInfoRaw>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub users: Option<UsersInfoRaw>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub processes: Option<ProcessesInfoRaw>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time: Option<TimeInfoRaw>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entropy: Option<EntropyInfoRaw>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub throttling: Option<ThrottlingInfoRaw>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub container_limits: Option<ContainerLimitsRaw>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub monitors: Option<Vec<HostMonitorRaw>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http_monitors: Option<Vec<HttpMonitorRaw>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom: Option<Vec<CustomLineRaw>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub helios: Option<SelfInfoRaw>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
+pub struct OsInfoRaw {
+    pub name: String,
+    pub machine: Option<String>,
+    pub kernel: String,
+    pub uptime_seconds: u64,
+    /// Hypervisor or container runtime helios runs in, "Bare metal" if none was detected.
+    pub virtualization: Option<String>,
+    /// Motherboard vendor and model, only on bare metal.
+    pub board: Option<String>,
+    /// BIOS or UEFI vendor, version, and release date, only on bare metal.
+    pub firmware: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PackageCountRaw {
+    /// dpkg, rpm, pacman, apk, or brew.
+    pub manager: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionInfoRaw {
+    pub shell: Option<String>,
+    pub init: Option<String>,
+    pub desktop: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
+pub struct CpuInfoRaw {
+    pub brand: String,
+    pub cores: usize,
+    pub frequency_mhz: u64,
+    /// Usage across every core since the previous refresh.
+    pub usage_percent: f32,
+    /// Package temperature, `None` without a sensor or when `cpu.temperature` is off.
+    pub temperature_celsius: Option<f32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
+pub struct UsageInfoRaw {
+    pub total_bytes: u64,
+    pub used_bytes: u64,
+    pub usage_percent: f64,
+}
+
+/// A swap partition, file, or zram device from `/proc/swaps`.
+#[derive(Debug, Clone, Serialize)]
+pub struct SwapDeviceRaw {
+    pub device: String,
+    /// partition or file, zram devices are partitions.
+    pub kind: String,
+    pub size_bytes: u64,
+    pub used_bytes: u64,
+    pub usage_percent: f64,
+    /// Devices with a higher priority are used first.
+    pub priority: i32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
+pub struct DiskInfoRaw {
+    pub name: String,
+    pub mount_point: String,
+    pub file_system: String,
+    pub total_bytes: u64,
+    pub used_bytes: u64,
+    pub available_bytes: u64,
+    pub usage_percent: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
+pub struct DiskIoInfoRaw {
+    /// Read rate across every disk since the previous refresh.
+    pub read_bytes_per_sec: Option<u64>,
+    /// Write rate across every disk since the previous refresh.
+    pub write_bytes_per_sec: Option<u64>,
+    pub devices: Vec<DiskIoDeviceRaw>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
+pub struct DiskIoDeviceRaw {
+    pub name: String,
+    /// Total bytes read since boot.
+    pub read_bytes: u64,
+    /// Total bytes written since boot.
+    pub written_bytes: u64,
+    pub read_bytes_per_sec: Option<u64>,
+    pub write_bytes_per_sec: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SmartDiskRaw {
+    pub device: String,
+    pub model: Option<String>,
+    /// Overall SMART self-assessment, `None` if the disk did not report one.
+    pub passed: Option<bool>,
+    pub reallocated_sectors: Option<u64>,
+    pub temperature_celsius: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RaidArrayRaw {
+    pub name: String,
+    /// raid0, raid1, raid5, etc.
+    pub level: Option<String>,
+    /// clean, degraded, inactive, resyncing, recovering, reshaping, or checking.
+    pub state: String,
+    pub devices_total: u32,
+    pub devices_active: u32,
+    pub sync_action: Option<String>,
+    pub sync_percent: Option<f32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
+pub struct NetworkInfoRaw {
+    pub ipv4_count: usize,
+    pub ipv6_count: usize,
+    /// Globally routable addresses, `None` unless `network.show_addresses` is set.
+    pub addresses: Option<Vec<String>>,
+    /// Download rate across every interface since the previous refresh.
+    pub rx_bytes_per_sec: Option<u64>,
+    /// Upload rate across every interface since the previous refresh.
+    pub tx_bytes_per_sec: Option<u64>,
+    pub interfaces: Vec<InterfaceTrafficRaw>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
+pub struct InterfaceTrafficRaw {
+    pub name: String,
+    /// Total bytes received since the interface came up.
+    pub rx_bytes: u64,
+    /// Total bytes sent since the interface came up.
+    pub tx_bytes: u64,
+    pub rx_bytes_per_sec: Option<u64>,
+    pub tx_bytes_per_sec: Option<u64>,
+}
+
+/// One network interface as listed by `/api/v1/interfaces`.
+#[derive(Debug, Clone, Serialize)]
+pub struct InterfaceInfoRaw {
+    pub name: String,
+    /// `None` for interfaces without one, like loopback or tunnels.
+    pub mac: Option<String>,
+    /// Addresses with their prefix length, e.g. `192.168.1.10/24`.
+    pub addresses: Vec<String>,
+    pub mtu: u64,
+    /// `up`, `down`, `unknown`, etc., only on Linux.
+    pub state: Option<String>,
+    /// Total bytes received since the interface came up.
+    pub rx_bytes: u64,
+    /// Total bytes sent since the interface came up.
+    pub tx_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PublicIpRaw {
+    pub ipv4: Option<Ipv4Addr>,
+    pub ipv6: Option<Ipv6Addr>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
+pub struct TemperatureInfoRaw {
+    pub label: String,
+    pub celsius: f32,
+    pub critical_celsius: Option<f32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BatteryInfoRaw {
+    pub name: String,
+    pub capacity_percent: u8,
+    /// Charging, Discharging, Full, Not charging, or Unknown.
+    pub status: String,
+    /// Current full capacity compared to the design capacity.
+    pub health_percent: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ContainersInfoRaw {
+    pub running: usize,
+    pub total: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ServicesInfoRaw {
+    /// Number of failed systemd units.
+    pub failed: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UsersInfoRaw {
+    /// Number of distinct logged-in users.
+    pub count: usize,
+    /// Every login session, empty when `users.count_only` is set.
+    pub sessions: Vec<UserSessionRaw>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TimeInfoRaw {
+    /// IANA name like `Europe/Berlin`, `None` if it couldn't be detected.
+    pub timezone: Option<String>,
+    /// Offset of the local time from UTC in seconds.
+    pub utc_offset_seconds: i32,
+    /// Local time when the snapshot was collected, e.g. `2026-10-16 14:03:05`.
+    pub local_time: String,
+}
+
+/// Raspberry Pi firmware throttling, `flags` is the raw `get_throttled` value.
+#[derive(Debug, Clone, Serialize)]
+pub struct ThrottlingInfoRaw {
+    pub flags: u32,
+    pub active: Vec<String>,
+    pub since_boot: Vec<String>,
+}
+
+/// Limits of the cgroup helios runs in, set when they're lower than what the host has.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ContainerLimitsRaw {
+    pub memory_bytes: Option<u64>,
+    /// CPU quota in cores, e.g. `1.5`.
+    pub cpus: Option<f32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EntropyInfoRaw {
+    pub available_bits: u32,
+    pub pool_size_bits: Option<u32>,
+}
+
+/// The helios process itself, not the machine.
+#[derive(Debug, Clone, Serialize)]
+pub struct SelfInfoRaw {
+    pub version: String,
+    pub uptime_seconds: u64,
+    /// Resident memory of the process.
+    pub rss_bytes: u64,
+    /// Since the previous refresh, 100% is one full core.
+    pub cpu_usage_percent: f32,
+}
+
+/// A `[[monitors.hosts]]` entry and how its last check went.
+#[derive(Debug, Clone, Serialize)]
+pub struct HostMonitorRaw {
+    pub name: String,
+    pub host: String,
+    pub check: HostCheck,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    pub up: bool,
+    /// Round trip of the echo request or the TCP handshake, only when up.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<f64>,
+    /// Why the host is down.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Unix timestamp in seconds of the check.
+    pub checked_at: i64,
+}
+
+/// A `[[monitors.http]]` entry and how its last check went.
+#[derive(Debug, Clone, Serialize)]
+pub struct HttpMonitorRaw {
+    pub name: String,
+    pub url: String,
+    pub up: bool,
+    /// Status code of the response, when there was one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    /// Time until the response, including its body when a keyword is looked for.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<f64>,
+    /// Why the URL is down.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Unix timestamp in seconds of the check.
+    pub checked_at: i64,
+}
+
+/// A line from `[[lines.custom]]`.
+#[derive(Debug, Clone, Serialize)]
+pub struct CustomLineRaw {
+    pub key: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessesInfoRaw {
+    /// Running processes, without kernel threads.
+    pub count: usize,
+    /// Threads of every process, `None` unless `processes.threads` is set.
+    pub threads: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UserSessionRaw {
+    pub user: String,
+    pub tty: String,
+    /// Remote host or IP for SSH and similar logins.
+    pub host: Option<String>,
+}
+
+impl UsageInfoRaw {
+    pub fn new(total_bytes: u64, used_bytes: u64) -> Self {
+        UsageInfoRaw {
+            total_bytes,
+            used_bytes,
+            usage_percent: usage_percent(used_bytes, total_bytes),
         }
+    }
 
-        merged_lines.push(("Network".to_string(), string_data.join(", ")));
+    pub fn as_display(&self) -> String {
+        format!(
+            "{} / {} ({:.1}%)",
+            format_bytes(self.used_bytes),
+            format_bytes(self.total_bytes),
+            self.usage_percent
+        )
     }
+}
 
-    let all_lines = merged_lines
-        .into_iter()
-        .map(LineInfo::from)
-        .collect::<Vec<_>>();
+pub fn usage_percent(used: u64, total: u64) -> f64 {
+    if total > 0 {
+        (used as f64 / total as f64) * 100.0
+    } else {
+        0.0
+    }
+}
 
-    SystemInfo {
-        host: HOSTNAME.clone(),
-        lines: all_lines,
+/// Hide the host part of an address, `203.0.113.7` becomes `203.0.x.x` and IPv6 keeps the first two groups.
+pub fn mask_ip(addr: IpAddr) -> String {
+    match addr {
+        IpAddr::V4(addr) => {
+            let [a, b, ..] = addr.octets();
+            format!("{a}.{b}.x.x")
+        }
+        IpAddr::V6(addr) => {
+            let [a, b, ..] = addr.segments();
+            format!("{a:x}:{b:x}:x:x:x:x:x:x")
+        }
     }
 }
 
-// Helper function to format uptime
-fn format_uptime(seconds: u64) -> String {
+/// Hide the device part of a MAC address, keeping the vendor prefix, e.g. `3c:7c:3f:xx:xx:xx`.
+pub fn mask_mac(mac: &str) -> String {
+    let vendor = mac.split(':').take(3).collect::<Vec<_>>().join(":");
+    format!("{vendor}:xx:xx:xx")
+}
+
+thread_local! {
+    static DURATION_STYLE: Cell<DurationStyle> = const { Cell::new(DurationStyle::Full) };
+}
+
+/// Run `render` with the durations written in `style`, like [`i18n::with_locale`] does for
+/// the language.
+pub fn with_duration_style<T>(style: DurationStyle, render: impl FnOnce() -> T) -> T {
+    let previous = DURATION_STYLE.replace(style);
+    let result = render();
+    DURATION_STYLE.set(previous);
+    result
+}
+
+// Helper function to format uptime, in the style of the current `with_duration_style` and the
+// locale of the current `i18n::with_locale`
+pub fn format_uptime(seconds: u64) -> String {
     let days = seconds / 86400;
     let hours = (seconds % 86400) / 3600;
     let minutes = (seconds % 3600) / 60;
     let secs = seconds % 60;
 
-    if days > 0 {
-        format!(
-            "{} day{}, {} hour{}, {} minute{}",
-            days,
-            if days == 1 { "" } else { "s" },
-            hours,
-            if hours == 1 { "" } else { "s" },
-            minutes,
-            if minutes == 1 { "" } else { "s" }
-        )
+    if DURATION_STYLE.get() == DurationStyle::Iso8601 {
+        let mut duration = "P".to_string();
+        if days > 0 {
+            duration.push_str(&format!("{days}D"));
+        }
+        if hours > 0 || minutes > 0 || secs > 0 || days == 0 {
+            duration.push('T');
+        }
+        for (count, designator) in [(hours, 'H'), (minutes, 'M'), (secs, 'S')] {
+            if count > 0 {
+                duration.push_str(&format!("{count}{designator}"));
+            }
+        }
+        if seconds == 0 {
+            duration.push_str("0S");
+        }
+        return duration;
+    }
+
+    let parts = if days > 0 {
+        vec![
+            (days, TimeUnit::Day),
+            (hours, TimeUnit::Hour),
+            (minutes, TimeUnit::Minute),
+        ]
     } else if hours > 0 {
-        format!(
-            "{} hour{}, {} minute{}",
-            hours,
-            if hours == 1 { "" } else { "s" },
-            minutes,
-            if minutes == 1 { "" } else { "s" }
-        )
+        vec![(hours, TimeUnit::Hour), (minutes, TimeUnit::Minute)]
     } else if minutes > 0 {
-        format!(
-            "{} minute{}, {} second{}",
-            minutes,
-            if minutes == 1 { "" } else { "s" },
-            secs,
-            if secs == 1 { "" } else { "s" }
-        )
+        vec![(minutes, TimeUnit::Minute), (secs, TimeUnit::Second)]
     } else {
-        format!("{} second{}", secs, if secs == 1 { "" } else { "s" })
+        vec![(secs, TimeUnit::Second)]
+    };
+
+    if DURATION_STYLE.get() == DurationStyle::Compact {
+        return parts
+            .into_iter()
+            .map(|(count, unit)| match unit {
+                TimeUnit::Day => format!("{count}d"),
+                TimeUnit::Hour => format!("{count}h"),
+                TimeUnit::Minute => format!("{count}m"),
+                TimeUnit::Second => format!("{count}s"),
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
     }
+
+    let locale = i18n::current();
+    parts
+        .into_iter()
+        .map(|(count, unit)| locale.duration(count, unit))
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
 // Helper function to format bytes
-fn format_bytes(bytes: u64) -> String {
-    const UNITS: &[&str] = &["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+pub fn format_bytes(bytes: u64) -> String {
+    let (base, units) = match config::get().byte_units {
+        ByteUnits::Iec => (1024.0, ["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"]),
+        ByteUnits::Si => (1000.0, ["B", "kB", "MB", "GB", "TB", "PB", "EB"]),
+    };
     let mut size = bytes as f64;
     let mut unit_index = 0;
 
-    while size >= 1024.0 && unit_index < UNITS.len() - 1 {
-        size /= 1024.0;
+    while size >= base && unit_index < units.len() - 1 {
+        size /= base;
         unit_index += 1;
     }
 
     if unit_index == 0 {
-        format!("{} {}", bytes, UNITS[unit_index])
+        format!("{} {}", bytes, units[unit_index])
     } else {
-        format!("{:.1} {}", size, UNITS[unit_index])
+        format!("{:.1} {}", size, units[unit_index])
     }
 }
 
-fn calculate_cpu_freq(freq: u64) -> String {
+pub fn calculate_cpu_freq(freq: u64) -> String {
     // it's in mhz
     if freq >= 1_000 {
         // use 2 decimal places for GHz
@@ -369,19 +952,15 @@ fn calculate_cpu_freq(freq: u64) -> String {
 }
 
 fn get_pc_host() -> String {
-    let host_family = read_dmi(
-        "/sys/devices/virtual/dmi/id/product_family",
-        "/sys/class/dmi/id/product_family",
-    );
-    let host_name = read_dmi(
-        "/sys/devices/virtual/dmi/id/product_name",
-        "/sys/class/dmi/id/product_name",
-    )
-    .or_else(get_host_product_name);
-    let host_version = read_dmi(
-        "/sys/devices/virtual/dmi/id/product_version",
-        "/sys/class/dmi/id/product_version",
-    );
+    if cfg!(target_os = "macos")
+        && let Some(model) = get_mac_model()
+    {
+        return model;
+    }
+
+    let host_family = read_dmi("product_family");
+    let host_name = read_dmi("product_name").or_else(get_host_product_name);
+    let host_version = read_dmi("product_version");
 
     let mut merged_str = String::new();
 
@@ -414,6 +993,95 @@ fn get_pc_host() -> String {
     merged_str.trim().to_string()
 }
 
+/// IANA name of the system timezone, from `$TZ`, `/etc/timezone`, or the `/etc/localtime` link.
+fn get_timezone() -> Option<String> {
+    if let Ok(tz) = std::env::var("TZ") {
+        let tz = tz.trim_start_matches(':').trim();
+        if !tz.is_empty() {
+            return Some(tz.to_string());
+        }
+    }
+
+    if let Ok(timezone) = std::fs::read_to_string("/etc/timezone")
+        && !timezone.trim().is_empty()
+    {
+        return Some(timezone.trim().to_string());
+    }
+
+    // e.g. /usr/share/zoneinfo/Europe/Berlin
+    let target = std::fs::read_link("/etc/localtime").ok()?;
+    let target = target.to_string_lossy();
+    let (_, name) = target.split_once("zoneinfo/")?;
+    Some(name.to_string())
+}
+
+/// Motherboard vendor and model from DMI, e.g. `ASUSTeK COMPUTER INC. PRIME B550-PLUS`.
+fn get_board() -> Option<String> {
+    let vendor = read_dmi_field("board_vendor");
+    let name = read_dmi_field("board_name");
+
+    join_dmi_fields(vendor, name, None)
+}
+
+/// BIOS or UEFI vendor, version, and release date from DMI, e.g. `American Megatrends 2803 (04/27/2022)`.
+fn get_firmware() -> Option<String> {
+    let vendor = read_dmi_field("bios_vendor");
+    let version = read_dmi_field("bios_version");
+    let date = read_dmi_field("bios_date");
+
+    join_dmi_fields(vendor, version, date)
+}
+
+fn read_dmi_field(field: &str) -> Option<String> {
+    read_dmi(field)
+        // vendors that didn't bother leave placeholders like "To Be Filled By O.E.M."
+        .filter(|value| {
+            const PLACEHOLDERS: [&str; 6] = [
+                "to be filled by o.e.m.",
+                "default string",
+                "not applicable",
+                "not specified",
+                "none",
+                "n/a",
+            ];
+            !value.is_empty() && !PLACEHOLDERS.contains(&value.to_lowercase().as_str())
+        })
+}
+
+/// `first second (third)`, skipping the missing parts.
+fn join_dmi_fields(
+    first: Option<String>,
+    second: Option<String>,
+    third: Option<String>,
+) -> Option<String> {
+    let mut joined = [first, second]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if let Some(third) = third {
+        if joined.is_empty() {
+            joined = third;
+        } else {
+            joined.push_str(&format!(" ({third})"));
+        }
+    }
+
+    (!joined.is_empty()).then_some(joined)
+}
+
+/// Marketing name of the Mac, e.g. "MacBook Air (M2, 2022)", or its `hw.model` identifier when it
+/// isn't in [`MAC_MODELS`].
+fn get_mac_model() -> Option<String> {
+    let identifier = command_output("sysctl", &["-n", "hw.model"])?;
+
+    let name = MAC_MODELS
+        .iter()
+        .find(|(model, _)| *model == identifier)
+        .map(|(_, name)| name.to_string());
+    Some(name.unwrap_or(identifier))
+}
+
 fn get_host_product_name() -> Option<String> {
     if let Ok(value) = std::fs::read_to_string("/sys/firmware/devicetree/base/model") {
         return Some(value.trim().to_string());
@@ -427,15 +1095,322 @@ fn get_host_product_name() -> Option<String> {
         return Some(value.trim().to_string());
     }
 
+    // the device tree is often not mounted in containers, cpuinfo still has the board revision
+    get_raspberry_pi_model()
+}
+
+/// Raspberry Pi model decoded from the `Revision` code in `/proc/cpuinfo`, e.g. `c03114` is a
+/// "Raspberry Pi 4 Model B Rev 1.4".
+///
+/// Only the new-style codes used since the Pi 2 are decoded, see
+/// <https://www.raspberrypi.com/documentation/computers/raspberry-pi.html#new-style-revision-codes>.
+fn get_raspberry_pi_model() -> Option<String> {
+    let cpuinfo = std::fs::read_to_string("/proc/cpuinfo").ok()?;
+    let revision = cpuinfo.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        (key.trim() == "Revision").then(|| value.trim())
+    })?;
+    let code = u32::from_str_radix(revision, 16).ok()?;
+    // bit 23 marks the new-style format
+    if code & (1 << 23) == 0 {
+        return None;
+    }
+
+    let model = match (code >> 4) & 0xff {
+        0x00 => "Model A",
+        0x01 => "Model B",
+        0x02 => "Model A+",
+        0x03 => "Model B+",
+        0x04 => "2 Model B",
+        0x06 => "Compute Module",
+        0x08 => "3 Model B",
+        0x09 => "Zero",
+        0x0a => "Compute Module 3",
+        0x0c => "Zero W",
+        0x0d => "3 Model B+",
+        0x0e => "3 Model A+",
+        0x10 => "Compute Module 3+",
+        0x11 => "4 Model B",
+        0x12 => "Zero 2 W",
+        0x13 => "400",
+        0x14 => "Compute Module 4",
+        0x15 => "Compute Module 4S",
+        0x17 => "5 Model B",
+        0x18 => "Compute Module 5",
+        0x19 => "500",
+        0x1a => "Compute Module 5 Lite",
+        _ => return None,
+    };
+
+    Some(format!("Raspberry Pi {model} Rev 1.{}", code & 0xf))
+}
+
+/// A DMI field by its Linux sysfs name, e.g. `product_name`.
+#[cfg(not(any(target_os = "freebsd", target_os = "openbsd")))]
+fn read_dmi(field: &str) -> Option<String> {
+    [
+        format!("/sys/devices/virtual/dmi/id/{field}"),
+        format!("/sys/class/dmi/id/{field}"),
+    ]
+    .iter()
+    .find_map(|path| std::fs::read_to_string(path).ok())
+    .map(|value| value.trim().to_string())
+}
+
+/// A DMI field by its Linux sysfs name, from the SMBIOS values the loader puts in the kernel env.
+#[cfg(target_os = "freebsd")]
+fn read_dmi(field: &str) -> Option<String> {
+    let name = match field {
+        "sys_vendor" => "smbios.system.maker",
+        "product_family" => "smbios.system.family",
+        "product_name" => "smbios.system.product",
+        "product_version" => "smbios.system.version",
+        "board_vendor" => "smbios.planar.maker",
+        "board_name" => "smbios.planar.product",
+        "bios_vendor" => "smbios.bios.vendor",
+        "bios_version" => "smbios.bios.version",
+        "bios_date" => "smbios.bios.reldate",
+        _ => return None,
+    };
+
+    command_output("kenv", &["-q", name])
+}
+
+/// A DMI field by its Linux sysfs name, OpenBSD only exposes the system ones through sysctl.
+#[cfg(target_os = "openbsd")]
+fn read_dmi(field: &str) -> Option<String> {
+    let name = match field {
+        "sys_vendor" => "hw.vendor",
+        "product_name" => "hw.product",
+        "product_version" => "hw.version",
+        _ => return None,
+    };
+
+    command_output("sysctl", &["-n", name])
+}
+
+/// Trimmed stdout of a command, `None` if it fails or prints nothing.
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new(program)
+        .args(args)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!stdout.is_empty()).then_some(stdout)
+}
+
+/// Login shell of the user running helios, from `$SHELL` or `/etc/passwd`.
+fn get_default_shell() -> Option<String> {
+    let shell_path = std::env::var("SHELL")
+        .ok()
+        .or_else(|| current_passwd_field(6))?;
+
+    let shell = shell_path.rsplit('/').next()?.trim();
+    (!shell.is_empty()).then(|| shell.to_string())
+}
+
+/// Name of the user running helios, from `/etc/passwd` or `$USER`.
+pub(crate) fn current_username() -> Option<String> {
+    current_passwd_field(0)
+        .or_else(|| std::env::var("USER").ok())
+        .or_else(|| std::env::var("USERNAME").ok())
+        .filter(|name| !name.trim().is_empty())
+}
+
+/// A field of the `/etc/passwd` entry of the user running helios.
+fn current_passwd_field(index: usize) -> Option<String> {
+    let uid = current_uid()?;
+    let passwd = std::fs::read_to_string("/etc/passwd").ok()?;
+    passwd.lines().find_map(|line| {
+        // name:password:uid:gid:gecos:home:shell
+        let fields: Vec<&str> = line.split(':').collect();
+        (fields.len() == 7 && fields[2].parse() == Ok(uid)).then(|| fields[index].to_string())
+    })
+}
+
+#[cfg(unix)]
+fn current_uid() -> Option<u32> {
+    use std::os::unix::fs::MetadataExt;
+
+    // the owner of our own procfs entry is the effective user
+    std::fs::metadata("/proc/self").ok().map(|meta| meta.uid())
+}
+
+#[cfg(not(unix))]
+fn current_uid() -> Option<u32> {
     None
 }
 
-fn read_dmi(path: &str, class_name: &str) -> Option<String> {
-    if let Ok(value) = std::fs::read_to_string(path) {
-        return Some(value.trim().to_string());
+/// Name of the init system, based on what runs as PID 1.
+fn get_init_system() -> Option<String> {
+    if cfg!(target_os = "macos") {
+        return Some("launchd".to_string());
     }
-    if let Ok(value) = std::fs::read_to_string(class_name) {
-        return Some(value.trim().to_string());
+
+    let comm = std::fs::read_to_string("/proc/1/comm").ok()?;
+    let init = match comm.trim() {
+        "systemd" => "systemd",
+        "runit" | "runit-init" => "runit",
+        "s6-svscan" => "s6",
+        "dinit" => "dinit",
+        "init" | "openrc-init" if std::path::Path::new("/run/openrc").exists() => "OpenRC",
+        "init" => "SysVinit",
+        // containers usually run the app itself as PID 1
+        other => return Some(other.to_string()),
+    };
+
+    Some(init.to_string())
+}
+
+/// Desktop environment, detected from the session env or a running shell process.
+fn get_desktop_environment() -> Option<String> {
+    if let Ok(desktop) = std::env::var("XDG_CURRENT_DESKTOP")
+        && !desktop.trim().is_empty()
+    {
+        return Some(desktop.replace(':', " / "));
+    }
+
+    // helios usually runs as a service without the session env, look at the processes instead
+    const DESKTOP_PROCESSES: [(&str, &str); 9] = [
+        ("gnome-shell", "GNOME"),
+        ("plasmashell", "KDE Plasma"),
+        ("xfce4-session", "Xfce"),
+        ("cinnamon", "Cinnamon"),
+        ("mate-session", "MATE"),
+        ("lxqt-session", "LXQt"),
+        ("lxsession", "LXDE"),
+        ("budgie-wm", "Budgie"),
+        ("enlightenment", "Enlightenment"),
+    ];
+
+    let mut sys = System::new();
+    sys.refresh_processes(sysinfo::ProcessesToUpdate::All, true);
+    sys.processes().values().find_map(|process| {
+        let name = process.name().to_string_lossy();
+        DESKTOP_PROCESSES
+            .iter()
+            .find(|(process_name, _)| name == *process_name)
+            .map(|(_, desktop)| desktop.to_string())
+    })
+}
+
+/// Hypervisor or container runtime we are running under, `None` outside of Linux and FreeBSD.
+fn get_virtualization() -> Option<String> {
+    if cfg!(target_os = "freebsd") {
+        return get_freebsd_virtualization();
+    }
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+
+    // containers share the host kernel and DMI, so they have to be checked first
+    if let Some(container) = get_container_runtime() {
+        return Some(container.to_string());
+    }
+
+    let os_release = std::fs::read_to_string("/proc/sys/kernel/osrelease").unwrap_or_default();
+    let os_release = os_release.to_ascii_lowercase();
+    if os_release.contains("microsoft") {
+        let wsl = if os_release.contains("wsl2") {
+            "WSL2"
+        } else {
+            "WSL"
+        };
+        return Some(wsl.to_string());
+    }
+
+    const HYPERVISORS: [(&str, &str); 9] = [
+        ("kvm", "KVM"),
+        ("qemu", "KVM/QEMU"),
+        ("vmware", "VMware"),
+        ("virtualbox", "VirtualBox"),
+        ("innotek", "VirtualBox"),
+        ("xen", "Xen"),
+        ("parallels", "Parallels"),
+        ("bochs", "Bochs"),
+        ("amazon ec2", "Amazon EC2"),
+    ];
+
+    let dmi = ["sys_vendor", "product_name", "bios_vendor", "board_vendor"]
+        .into_iter()
+        .filter_map(read_dmi)
+        .map(|value| value.to_ascii_lowercase())
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    // Hyper-V guests report the same vendor as Surface laptops, the product gives it away
+    if dmi.contains("microsoft corporation") && dmi.contains("virtual machine") {
+        return Some("Hyper-V".to_string());
+    }
+    if let Some((_, name)) = HYPERVISORS.iter().find(|(needle, _)| dmi.contains(needle)) {
+        return Some(name.to_string());
+    }
+
+    // some hypervisors hide from DMI but still set the CPU flag
+    let cpuinfo = std::fs::read_to_string("/proc/cpuinfo").unwrap_or_default();
+    let hypervisor_flag = cpuinfo
+        .lines()
+        .filter(|line| line.starts_with("flags"))
+        .any(|line| line.split_whitespace().any(|flag| flag == "hypervisor"));
+    if hypervisor_flag {
+        return Some("Virtual machine".to_string());
+    }
+
+    Some("Bare metal".to_string())
+}
+
+/// Jail or hypervisor on FreeBSD, from `security.jail.jailed` and `kern.vm_guest`.
+fn get_freebsd_virtualization() -> Option<String> {
+    if command_output("sysctl", &["-n", "security.jail.jailed"]).as_deref() == Some("1") {
+        return Some("Jail".to_string());
+    }
+
+    let guest = match command_output("sysctl", &["-n", "kern.vm_guest"])?.as_str() {
+        "none" => "Bare metal",
+        "bhyve" => "bhyve",
+        "kvm" => "KVM",
+        "vmware" => "VMware",
+        "vbox" => "VirtualBox",
+        "xen" => "Xen",
+        "hv" => "Hyper-V",
+        "parallels" => "Parallels",
+        _ => "Virtual machine",
+    };
+
+    Some(guest.to_string())
+}
+
+fn get_container_runtime() -> Option<&'static str> {
+    if std::path::Path::new("/.dockerenv").exists() {
+        return Some("Docker");
+    }
+    if std::path::Path::new("/run/.containerenv").exists() {
+        return Some("Podman");
+    }
+
+    // PID 1 gets `container=<runtime>` from LXC, systemd-nspawn, and podman
+    if let Ok(environ) = std::fs::read("/proc/1/environ") {
+        let runtime = environ
+            .split(|b| *b == 0)
+            .find_map(|var| var.strip_prefix(b"container="));
+        match runtime {
+            Some(b"lxc" | b"lxc-libvirt") => return Some("LXC"),
+            Some(b"systemd-nspawn") => return Some("systemd-nspawn"),
+            Some(b"podman") => return Some("Podman"),
+            Some(b"docker") => return Some("Docker"),
+            _ => {}
+        }
+    }
+
+    let cgroup = std::fs::read_to_string("/proc/1/cgroup").unwrap_or_default();
+    if cgroup.contains("/docker") {
+        Some("Docker")
+    } else if cgroup.contains("/lxc") {
+        Some("LXC")
+    } else if cgroup.contains("/kubepods") {
+        Some("Kubernetes")
+    } else {
+        None
     }
-    None
 }
@@ -0,0 +1,151 @@
+use std::sync::LazyLock;
+
+use minijinja::{Environment, context};
+
+use crate::{
+    assets,
+    config::{self, DurationStyle, Theme},
+    federation::PeerStatus,
+    i18n::Locale,
+    logo::LOGO,
+    sysgetter::{HttpMonitorRaw, SystemInfo},
+};
+
+const INDEX_HTML: &str = include_str!("../assets/index.html");
+const INFO_HTML: &str = include_str!("../assets/info.html");
+const API_DOCS_HTML: &str = include_str!("../assets/api-docs.html");
+
+/// Every template with its embedded source.
+const TEMPLATE_FILES: [(&str, &str); 3] = [
+    ("index.html", INDEX_HTML),
+    ("info.html", INFO_HTML),
+    ("api-docs.html", API_DOCS_HTML),
+];
+
+/// Templates of the HTML page, values are escaped since the names end in `.html`.
+static TEMPLATES: LazyLock<Environment<'static>> = LazyLock::new(|| {
+    let mut env = Environment::new();
+    for (name, source) in TEMPLATE_FILES {
+        if let Err(err) = env.add_template(name, source) {
+            panic!("{name} should be a valid template: {err}");
+        }
+    }
+    env
+});
+
+/// Render a template, read again on every render when `asset_dir` or `--dev` may replace it.
+fn render(name: &str, ctx: minijinja::Value) -> Result<String, minijinja::Error> {
+    if config::get().asset_dir.is_none() && !config::get().dev {
+        return TEMPLATES.get_template(name)?.render(ctx);
+    }
+
+    let mut env = Environment::new();
+    for (file, embedded) in TEMPLATE_FILES {
+        let source = assets::read_override(file).map_or_else(
+            || embedded.to_string(),
+            |source| String::from_utf8_lossy(&source).into_owned(),
+        );
+        env.add_template_owned(file, source)?;
+    }
+    env.get_template(name)?.render(ctx)
+}
+
+/// Stylesheets to link for `theme`, in order.
+fn stylesheets(theme: Theme) -> Vec<String> {
+    let stylesheets = match theme {
+        Theme::Dark => vec!["/assets/style.css"],
+        Theme::Light => vec!["/assets/style.css", "/assets/theme-light.css"],
+        Theme::Custom if config::get().theme.custom_css.is_some() => {
+            vec!["/assets/style.css", "/assets/custom.css"]
+        }
+        Theme::Custom => vec!["/assets/style.css"],
+    };
+
+    let base_path = &config::get().base_path;
+    stylesheets
+        .into_iter()
+        .map(|stylesheet| format!("{base_path}{stylesheet}"))
+        .collect()
+}
+
+/// Render the full HTML page with `info` as the initial content, the `services` below it, and a
+/// block for every peer.
+pub fn render_index(
+    info: &SystemInfo,
+    services: &[HttpMonitorRaw],
+    peers: &[PeerStatus],
+    theme: Theme,
+    locale: Locale,
+    durations: DurationStyle,
+) -> Result<String, minijinja::Error> {
+    let config = config::get();
+    let username = &config.username;
+    // username + @ + host
+    let separator = "-".repeat(info.host().len() + username.chars().count() + 1);
+    let peers = peers
+        .iter()
+        .map(|peer| {
+            context! {
+                username => peer.username,
+                host => peer.host(),
+                separator => "-".repeat(peer.host().len() + peer.username.chars().count() + 1),
+                lines => peer.lines(),
+            }
+        })
+        .collect::<Vec<_>>();
+    let services = services
+        .iter()
+        .map(|service| {
+            context! {
+                name => service.name,
+                url => service.url,
+                state => if service.up { "up" } else { "down" },
+                detail => service_detail(service),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    render(
+        "index.html",
+        context! {
+            username,
+            separator,
+            lang => locale.code(),
+            durations,
+            byte_units => config.byte_units,
+            base_path => config.base_path,
+            host => info.host(),
+            lines => info.lines(),
+            services,
+            services_title => locale.label("Services"),
+            peers,
+            app_name => config.manifest.name,
+            theme_color => config.manifest.theme_color,
+            stylesheets => stylesheets(theme),
+            logo => LOGO.map(|logo| context! { art => logo.art(), color => logo.css_color() }),
+        },
+    )
+}
+
+/// The latency of an up service, or why it is down, like the page's script writes it.
+fn service_detail(service: &HttpMonitorRaw) -> String {
+    match (service.latency_ms, &service.error) {
+        (Some(latency_ms), _) => format!("{latency_ms:.0} ms"),
+        (None, Some(error)) => error.clone(),
+        (None, None) => "down".to_string(),
+    }
+}
+
+/// Render the API explorer page.
+pub fn render_api_docs() -> Result<String, minijinja::Error> {
+    let config = config::get();
+
+    render(
+        "api-docs.html",
+        context! {
+            base_path => config.base_path,
+            app_name => config.manifest.name,
+            theme_color => config.manifest.theme_color,
+        },
+    )
+}
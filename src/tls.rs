@@ -0,0 +1,29 @@
+use std::net::SocketAddr;
+
+use axum::Router;
+use axum_server::tls_rustls::RustlsConfig;
+
+use crate::{config::TlsConfig, notify};
+
+/// Serve the app over HTTPS, terminating TLS with rustls.
+pub async fn serve(app: Router, addr: SocketAddr, tls: &TlsConfig) -> std::io::Result<()> {
+    let (Some(cert), Some(key)) = (&tls.cert, &tls.key) else {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "both `tls.cert` and `tls.key` must be set",
+        ));
+    };
+
+    // only ring is compiled in, make it the process-wide default
+    let _ = rustls::crypto::ring::default_provider().install_default();
+
+    let rustls_config = RustlsConfig::from_pem_file(cert, key).await?;
+    let listener = std::net::TcpListener::bind(addr)?;
+    listener.set_nonblocking(true)?;
+    tracing::info!("Listening on https://{addr}");
+    notify::ready();
+
+    axum_server::from_tcp_rustls(listener, rustls_config)?
+        .serve(app.into_make_service_with_connect_info::<SocketAddr>())
+        .await
+}
@@ -0,0 +1,108 @@
+use std::time::Duration;
+
+use axum::{
+    extract::{
+        State,
+        ws::{Message, WebSocket, WebSocketUpgrade},
+    },
+    response::IntoResponse,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    live::refresh_interval,
+    state::AppState,
+    sysgetter::{SystemInfo, SystemInfoDelta},
+};
+
+const MIN_INTERVAL_SECS: u64 = 1;
+const MAX_INTERVAL_SECS: u64 = 3600;
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ClientMessage {
+    /// Change how often this connection receives updates.
+    SetInterval { seconds: u64 },
+    /// Ask for a full snapshot instead of a delta.
+    Snapshot,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ServerMessage<'a> {
+    Snapshot { data: &'a SystemInfo },
+    Delta(&'a SystemInfoDelta),
+    Interval { seconds: u64 },
+    Error { message: String },
+}
+
+pub async fn ws_handler(State(state): State<AppState>, ws: WebSocketUpgrade) -> impl IntoResponse {
+    ws.on_upgrade(|socket| handle_socket(socket, state))
+}
+
+async fn handle_socket(mut socket: WebSocket, state: AppState) {
+    let mut period = refresh_interval();
+    let mut interval = tokio::time::interval(period);
+    let mut last_sent: Option<SystemInfo> = None;
+
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {
+                let current = state.latest().info;
+
+                let sent = match &last_sent {
+                    // a host change invalidates every line, resend everything
+                    Some(previous) if previous.host() == current.host() => {
+                        let delta = current.diff(previous);
+                        delta.is_empty() || send(&mut socket, &ServerMessage::Delta(&delta)).await
+                    }
+                    _ => send(&mut socket, &ServerMessage::Snapshot { data: &current }).await,
+                };
+                if !sent {
+                    break;
+                }
+
+                last_sent = Some(current);
+            }
+            message = socket.recv() => {
+                let text = match message {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    // pings are answered by axum, binary frames are ignored
+                    Some(Ok(_)) => continue,
+                };
+
+                let reply_ok = match serde_json::from_str::<ClientMessage>(&text) {
+                    Ok(ClientMessage::SetInterval { seconds }) => {
+                        let seconds = seconds.clamp(MIN_INTERVAL_SECS, MAX_INTERVAL_SECS);
+                        period = Duration::from_secs(seconds);
+                        interval = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+                        send(&mut socket, &ServerMessage::Interval { seconds }).await
+                    }
+                    Ok(ClientMessage::Snapshot) => {
+                        let current = state.latest().info;
+                        let ok = send(&mut socket, &ServerMessage::Snapshot { data: &current }).await;
+                        last_sent = Some(current);
+                        ok
+                    }
+                    Err(err) => {
+                        let message = format!("Invalid message: {err}");
+                        send(&mut socket, &ServerMessage::Error { message }).await
+                    }
+                };
+                if !reply_ok {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Send a message to the client, returning `false` when the socket is gone.
+async fn send(socket: &mut WebSocket, message: &ServerMessage<'_>) -> bool {
+    let Ok(payload) = serde_json::to_string(message) else {
+        return true;
+    };
+
+    socket.send(Message::Text(payload.into())).await.is_ok()
+}